@@ -0,0 +1,534 @@
+// Local HTTP API for other desktop tools
+//
+// Opt-in server on 127.0.0.1 answering from the same keyword store, rule set and waivers
+// as the GUI, so calibration and modeling tools need no lookups of their own:
+//
+//   GET  /api/v1/version                          keyword DB date and size, app version
+//   GET  /api/v1/keywords/<abbr>                  keywords with this abbreviation
+//   GET  /api/v1/search?q=<query>&page=&pageSize= search over abbreviations and long names
+//   GET  /api/v1/validate?label=<label>           validation report of one label
+//   POST /api/v1/validate  {"labels": [...]}      validation reports of several labels
+//
+// Every request needs `Authorization: Bearer <token>` with the token shown in Settings.
+// Browsers only send that header cross-origin after a CORS preflight, which is never
+// answered, so web pages cannot call the API. Requests for other hosts than localhost
+// are refused as well, which defeats DNS rebinding.
+use crate::cache;
+use crate::error::{AppError, AppResult};
+use crate::label;
+use crate::launch;
+use crate::rules;
+use crate::settings;
+use crate::store;
+use crate::waiver;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use tauri::{AppHandle, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+pub const DEFAULT_PORT: u16 = 47813;
+const PREFIX: &str = "/api/v1";
+/// Largest accepted request body.
+const MAX_BODY: u64 = 1024 * 1024;
+/// Labels accepted by one POST to `/validate`.
+const MAX_LABELS: usize = 10_000;
+const LOCAL_HOSTS: [&str; 3] = ["127.0.0.1", "localhost", "[::1]"];
+
+/// A listening server and the thread answering its requests.
+pub struct ApiServer {
+    server: Arc<Server>,
+    thread: JoinHandle<()>,
+}
+
+/// The running server, if the API is enabled.
+pub type ApiState = Mutex<Option<ApiServer>>;
+
+/// API configuration and status shown in Settings.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiInfo {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: u16,
+    pub url: String,
+    pub token: Option<String>,
+    /// Set when the server is enabled but could not be started.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ValidateBody {
+    labels: Vec<String>,
+}
+
+/// What a request asks for, once its URL and body are checked.
+#[derive(Debug, PartialEq)]
+enum Endpoint {
+    Version,
+    Keywords(String),
+    Search {
+        q: String,
+        page: usize,
+        page_size: usize,
+    },
+    Validate(String),
+    ValidateAll(Vec<String>),
+}
+
+/// A random token for the `Authorization` header.
+pub fn new_token() -> AppResult<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes)
+        .map_err(|e| AppError::Internal(format!("No random source for the API token: {}", e)))?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Current configuration and whether the server runs.
+pub fn info(app: &AppHandle) -> ApiInfo {
+    let backend_settings = settings::current(app);
+    let running = app.state::<ApiState>().lock().unwrap().is_some();
+    let port = backend_settings.local_api_port;
+    ApiInfo {
+        enabled: backend_settings.local_api_enabled,
+        running,
+        port,
+        url: format!("http://127.0.0.1:{}{}", port, PREFIX),
+        token: backend_settings.local_api_token,
+        error: (backend_settings.local_api_enabled && !running)
+            .then(|| format!("Not running, port {} may be in use, see the log", port)),
+    }
+}
+
+/// Enable or disable the API, optionally on another port or with a new token, and
+/// apply the change right away.
+pub fn configure(
+    app: &AppHandle,
+    enabled: bool,
+    port: Option<u16>,
+    regenerate_token: bool,
+) -> AppResult<ApiInfo> {
+    let mut backend_settings = settings::current(app);
+    backend_settings.local_api_enabled = enabled;
+    if let Some(port) = port {
+        backend_settings.local_api_port = port;
+    }
+    if regenerate_token || backend_settings.local_api_token.is_none() {
+        backend_settings.local_api_token = Some(new_token()?);
+    }
+    settings::save(app, backend_settings)?;
+    restart(app)?;
+    Ok(info(app))
+}
+
+/// Stop the server and start it again with the saved settings if it is enabled.
+pub fn restart(app: &AppHandle) -> AppResult<()> {
+    stop(app);
+    let backend_settings = settings::current(app);
+    if !backend_settings.local_api_enabled {
+        return Ok(());
+    }
+    let token = backend_settings
+        .local_api_token
+        .ok_or_else(|| AppError::Validation("The local API has no token yet".to_string()))?;
+    let port = backend_settings.local_api_port;
+    let server = Server::http(("127.0.0.1", port)).map_err(|e| AppError::Network {
+        message: format!("Failed to start the local API on port {}: {}", port, e),
+        retryable: false,
+    })?;
+    let server = Arc::new(server);
+    let thread = std::thread::spawn({
+        let server = server.clone();
+        let app = app.clone();
+        move || {
+            for request in server.incoming_requests() {
+                handle(&app, request, &token);
+            }
+        }
+    });
+    log::info!("Local API listening on http://127.0.0.1:{}{}", port, PREFIX);
+    *app.state::<ApiState>().lock().unwrap() = Some(ApiServer { server, thread });
+    Ok(())
+}
+
+pub fn stop(app: &AppHandle) {
+    let running = app.state::<ApiState>().lock().unwrap().take();
+    if let Some(running) = running {
+        running.server.unblock();
+        if running.thread.join().is_err() {
+            log::error!("Local API thread panicked");
+        }
+        log::info!("Local API stopped");
+    }
+}
+
+fn handle(app: &AppHandle, mut request: Request, token: &str) {
+    let (status, body) = match route(app, &mut request, token) {
+        Ok(body) => (200, body),
+        Err((status, error)) => {
+            log::warn!(
+                "Local API {} {} failed: {}",
+                request.method(),
+                request.url(),
+                error
+            );
+            (status, serde_json::to_value(&error).unwrap_or_default())
+        }
+    };
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(
+            Header::from_bytes("Content-Type", "application/json; charset=utf-8")
+                .expect("static header is valid"),
+        );
+    if let Err(e) = request.respond(response) {
+        log::warn!("Failed to answer local API request: {}", e);
+    }
+}
+
+type ApiError = (u16, AppError);
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str())
+}
+
+fn check_access(request: &Request, token: &str) -> Result<(), ApiError> {
+    let host = header(request, "Host").unwrap_or_default();
+    let hostname = match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+    if !LOCAL_HOSTS.contains(&hostname) {
+        return Err((
+            403,
+            AppError::Validation(format!("Host '{}' is not allowed", host)),
+        ));
+    }
+    let given = header(request, "Authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    // Compare without stopping at the first difference
+    let matches = given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0;
+    if !matches {
+        return Err((
+            401,
+            AppError::Validation("Missing or invalid API token".to_string()),
+        ));
+    }
+    Ok(())
+}
+
+fn route(
+    app: &AppHandle,
+    request: &mut Request,
+    token: &str,
+) -> Result<serde_json::Value, ApiError> {
+    check_access(request, token)?;
+    match endpoint(request)? {
+        Endpoint::Version => version(app),
+        Endpoint::Keywords(abbr) => {
+            let store = loaded(store::current(app))?;
+            let keywords = store.get_by_abbr(&abbr);
+            if keywords.is_empty() {
+                return Err((404, AppError::Validation(format!("No keyword '{}'", abbr))));
+            }
+            to_json(&keywords)
+        }
+        Endpoint::Search { q, page, page_size } => {
+            to_json(&loaded(store::current(app))?.search(&q, page, page_size))
+        }
+        Endpoint::Validate(label) => to_json(&validate(app, &[label])?.remove(0)),
+        Endpoint::ValidateAll(labels) => to_json(&validate(app, &labels)?),
+    }
+}
+
+fn endpoint(request: &mut Request) -> Result<Endpoint, ApiError> {
+    let url = reqwest::Url::parse(&format!("http://localhost{}", request.url()))
+        .map_err(|e| (400, AppError::Validation(format!("Invalid URL: {}", e))))?;
+    let query = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+    let path = url
+        .path()
+        .strip_prefix(PREFIX)
+        .ok_or_else(|| not_found(url.path()))?;
+    let method = request.method().clone();
+
+    match (&method, path) {
+        (Method::Get, "/version") => Ok(Endpoint::Version),
+        (Method::Get, path) if path.starts_with("/keywords/") => Ok(Endpoint::Keywords(
+            launch::percent_decode(&path["/keywords/".len()..]),
+        )),
+        (Method::Get, "/search") => Ok(Endpoint::Search {
+            q: query("q").ok_or_else(|| missing("q"))?,
+            page: number(query("page"), "page")?.unwrap_or(0),
+            page_size: number(query("pageSize"), "pageSize")?.unwrap_or(store::DEFAULT_PAGE_SIZE),
+        }),
+        (Method::Get, "/validate") => Ok(Endpoint::Validate(
+            query("label").ok_or_else(|| missing("label"))?,
+        )),
+        (Method::Post, "/validate") => {
+            let mut text = String::new();
+            request
+                .as_reader()
+                .take(MAX_BODY)
+                .read_to_string(&mut text)
+                .map_err(|e| (400, AppError::Parse(format!("Failed to read body: {}", e))))?;
+            let body: ValidateBody = serde_json::from_str(&text)
+                .map_err(|e| (400, AppError::Parse(format!("Invalid request body: {}", e))))?;
+            if body.labels.len() > MAX_LABELS {
+                return Err((
+                    400,
+                    AppError::Validation(format!("At most {} labels per request", MAX_LABELS)),
+                ));
+            }
+            Ok(Endpoint::ValidateAll(body.labels))
+        }
+        (_, "/version" | "/search" | "/validate") => Err((
+            405,
+            AppError::Validation(format!("{} is not supported here", method)),
+        )),
+        _ => Err(not_found(url.path())),
+    }
+}
+
+fn version(app: &AppHandle) -> Result<serde_json::Value, ApiError> {
+    let store = store::current(app);
+    let manifest = app
+        .path()
+        .app_local_data_dir()
+        .ok()
+        .and_then(|dir| cache::manifest(&dir));
+    Ok(json!({
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "keywordsLoaded": store.is_loaded(),
+        "keywordCount": store.len(),
+        "updatedDate": manifest.as_ref().and_then(|m| m.updated_date.clone()),
+        "fetchedAt": manifest.as_ref().map(|m| m.fetched_at.clone()),
+        "ruleSet": rules::current(app).name,
+    }))
+}
+
+fn validate(app: &AppHandle, labels: &[String]) -> Result<Vec<label::LabelReport>, ApiError> {
+    let store = loaded(store::current(app))?;
+    let rule_set = rules::current(app);
+    let waivers = waiver::current(app);
+    let today = waiver::today();
+    Ok(labels
+        .iter()
+        .map(|label| {
+            let mut report = label::validate_label_with(label.trim(), store.keywords(), &rule_set);
+            waivers.apply(&mut report, today);
+            report
+        })
+        .collect())
+}
+
+// The store `start_keyword_loading` fills, an empty one means it has not finished yet
+fn loaded(store: Arc<store::KeywordStore>) -> Result<Arc<store::KeywordStore>, ApiError> {
+    if !store.is_loaded() {
+        return Err((
+            503,
+            AppError::network("Keywords are still loading, try again shortly"),
+        ));
+    }
+    Ok(store)
+}
+
+fn to_json(value: &impl Serialize) -> Result<serde_json::Value, ApiError> {
+    serde_json::to_value(value).map_err(|e| {
+        (
+            500,
+            AppError::Internal(format!("Failed to serialize: {}", e)),
+        )
+    })
+}
+
+fn number(value: Option<String>, name: &str) -> Result<Option<usize>, ApiError> {
+    value.map(|value| value.parse()).transpose().map_err(|_| {
+        (
+            400,
+            AppError::Validation(format!("{} must be a number", name)),
+        )
+    })
+}
+
+fn missing(name: &str) -> ApiError {
+    (
+        400,
+        AppError::Validation(format!("Query parameter '{}' is required", name)),
+    )
+}
+
+fn not_found(path: &str) -> ApiError {
+    (404, AppError::Validation(format!("No endpoint {}", path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::net::TcpStream;
+
+    const TOKEN: &str = "0123456789abcdef0123456789abcdef";
+
+    // Sends a raw request to a server on a free port and returns it as the server sees it
+    fn receive(method: &str, path: &str, headers: &[String], body: &str) -> Request {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let mut stream = TcpStream::connect(server.server_addr().to_ip().unwrap()).unwrap();
+        let mut raw = format!("{} {} HTTP/1.1\r\n", method, path);
+        for header in headers {
+            raw.push_str(header);
+            raw.push_str("\r\n");
+        }
+        raw.push_str(&format!("Content-Length: {}\r\n\r\n{}", body.len(), body));
+        stream.write_all(raw.as_bytes()).unwrap();
+        server.recv().unwrap()
+    }
+
+    fn authorized() -> Vec<String> {
+        vec![
+            "Host: 127.0.0.1:47813".to_string(),
+            format!("Authorization: Bearer {}", TOKEN),
+        ]
+    }
+
+    fn get(path: &str) -> Request {
+        receive("GET", path, &authorized(), "")
+    }
+
+    fn access(headers: &[&str]) -> Result<(), u16> {
+        let headers: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+        let request = receive("GET", "/api/v1/version", &headers, "");
+        check_access(&request, TOKEN).map_err(|(status, _)| status)
+    }
+
+    fn status(mut request: Request) -> u16 {
+        endpoint(&mut request).unwrap_err().0
+    }
+
+    #[test]
+    fn accepts_local_hosts_with_the_token() {
+        let authorization = format!("Authorization: Bearer {}", TOKEN);
+        for host in [
+            "Host: 127.0.0.1:47813",
+            "Host: localhost",
+            "Host: [::1]:47813",
+        ] {
+            assert_eq!(access(&[host, &authorization]), Ok(()), "{}", host);
+        }
+    }
+
+    #[test]
+    fn refuses_other_hosts() {
+        let authorization = format!("Authorization: Bearer {}", TOKEN);
+        for host in [
+            "Host: attacker.example:47813",
+            "Host: 127.0.0.1.attacker.example",
+            "Host: localhost:http",
+        ] {
+            assert_eq!(access(&[host, &authorization]), Err(403), "{}", host);
+        }
+        assert_eq!(access(&[&authorization]), Err(403));
+    }
+
+    #[test]
+    fn refuses_missing_or_wrong_tokens() {
+        let host = "Host: localhost:47813";
+        let wrong = format!("Authorization: Bearer {}", TOKEN.replace('0', "1"));
+        let short = format!("Authorization: Bearer {}", &TOKEN[..31]);
+        let basic = format!("Authorization: Basic {}", TOKEN);
+        assert_eq!(access(&[host]), Err(401));
+        for authorization in [&wrong, &short, &basic] {
+            assert_eq!(
+                access(&[host, authorization]),
+                Err(401),
+                "{}",
+                authorization
+            );
+        }
+    }
+
+    #[test]
+    fn parses_endpoints() {
+        assert_eq!(endpoint(&mut get("/api/v1/version")), Ok(Endpoint::Version));
+        assert_eq!(
+            endpoint(&mut get("/api/v1/keywords/T%C3%BCr")),
+            Ok(Endpoint::Keywords("Tür".to_string()))
+        );
+        assert_eq!(
+            endpoint(&mut get("/api/v1/search?q=engine+temp&page=2")),
+            Ok(Endpoint::Search {
+                q: "engine temp".to_string(),
+                page: 2,
+                page_size: store::DEFAULT_PAGE_SIZE,
+            })
+        );
+        assert_eq!(
+            endpoint(&mut get("/api/v1/validate?label=RB_tMax")),
+            Ok(Endpoint::Validate("RB_tMax".to_string()))
+        );
+        let mut post = receive(
+            "POST",
+            "/api/v1/validate",
+            &authorized(),
+            r#"{"labels": ["RB_tMax", "RB_tMin"]}"#,
+        );
+        assert_eq!(
+            endpoint(&mut post),
+            Ok(Endpoint::ValidateAll(vec![
+                "RB_tMax".to_string(),
+                "RB_tMin".to_string()
+            ]))
+        );
+    }
+
+    #[test]
+    fn maps_bad_requests_to_statuses() {
+        assert_eq!(status(get("/other")), 404);
+        assert_eq!(status(get("/api/v1/unknown")), 404);
+        assert_eq!(
+            status(receive("POST", "/api/v1/version", &authorized(), "")),
+            405
+        );
+        assert_eq!(
+            status(receive("DELETE", "/api/v1/search", &authorized(), "")),
+            405
+        );
+        assert_eq!(status(get("/api/v1/search")), 400);
+        assert_eq!(status(get("/api/v1/search?q=Eng&page=first")), 400);
+        assert_eq!(
+            status(receive("POST", "/api/v1/validate", &authorized(), "labels")),
+            400
+        );
+    }
+
+    #[test]
+    fn answers_unavailable_until_keywords_are_loaded() {
+        let loading = loaded(Arc::new(store::KeywordStore::default()));
+        assert_eq!(loading.map(|_| ()).unwrap_err().0, 503);
+        assert!(loaded(Arc::new(store::KeywordStore::new(Vec::new()))).is_ok());
+    }
+
+    #[test]
+    fn tokens_are_random_hex() {
+        let token = new_token().unwrap();
+        assert_eq!(token.len(), 32);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token, new_token().unwrap());
+    }
+}
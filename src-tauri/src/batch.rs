@@ -0,0 +1,120 @@
+// Validation of many labels at once, e.g. everything extracted from an A2L file
+use crate::extract::ExtractedLabel;
+use crate::keyword::Keyword;
+use crate::label::{self, LabelError, LabelReport};
+use crate::rules::RuleSet;
+use crate::waiver::{self, WaiverSet, WaiverSummary};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+/// Validation result of one extracted label.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelResult {
+    #[serde(flatten)]
+    pub source: ExtractedLabel,
+    pub report: LabelReport,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorCount {
+    pub error: LabelError,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSummary {
+    pub total: usize,
+    pub valid: usize,
+    pub invalid: usize,
+    /// Number of labels failing with each error, in error rank order.
+    pub errors: Vec<ErrorCount>,
+    /// Number of labels with each warning, in error rank order.
+    pub warnings: Vec<ErrorCount>,
+    /// Number of labels with each waived finding, in error rank order.
+    pub waived: Vec<ErrorCount>,
+}
+
+/// Aggregated validation report over one or more label sources.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchReport {
+    pub files: Vec<String>,
+    /// Name of the rule set the labels were validated with.
+    pub rule_set: String,
+    pub results: Vec<LabelResult>,
+    pub summary: BatchSummary,
+    pub waivers: WaiverSummary,
+}
+
+impl BatchReport {
+    pub fn has_failures(&self) -> bool {
+        self.summary.invalid > 0
+    }
+}
+
+/// Validate every label; labels occurring several times are only validated once.
+///
+/// Findings covered by one of `waivers` are reported as waived.
+pub fn validate_labels(
+    files: Vec<String>,
+    labels: Vec<ExtractedLabel>,
+    keywords: &[Keyword],
+    rules: &RuleSet,
+    waivers: &WaiverSet,
+) -> BatchReport {
+    let today = waiver::today();
+    let mut used = BTreeSet::new();
+    let mut cache: HashMap<String, LabelReport> = HashMap::new();
+    let results: Vec<LabelResult> = labels
+        .into_iter()
+        .map(|source| {
+            let report = cache
+                .entry(source.name.clone())
+                .or_insert_with(|| {
+                    let mut report = label::validate_label_with(&source.name, keywords, rules);
+                    used.extend(waivers.apply(&mut report, today));
+                    report
+                })
+                .clone();
+            LabelResult { source, report }
+        })
+        .collect();
+
+    let invalid = results.iter().filter(|r| !r.report.valid).count();
+    let waived: Vec<Vec<LabelError>> = results
+        .iter()
+        .map(|r| r.report.waived.iter().map(|w| w.error).collect())
+        .collect();
+    BatchReport {
+        files,
+        rule_set: rules.name.clone(),
+        summary: BatchSummary {
+            total: results.len(),
+            valid: results.len() - invalid,
+            invalid,
+            errors: count_errors(results.iter().map(|r| &r.report.errors)),
+            warnings: count_errors(results.iter().map(|r| &r.report.warnings)),
+            waived: count_errors(waived.iter()),
+        },
+        waivers: waivers.summary(&used, waived.iter().map(Vec::len).sum(), today),
+        results,
+    }
+}
+
+fn count_errors<'a>(per_label: impl Iterator<Item = &'a Vec<LabelError>>) -> Vec<ErrorCount> {
+    let mut counts: HashMap<LabelError, usize> = HashMap::new();
+    for errors in per_label {
+        for error in errors {
+            *counts.entry(*error).or_default() += 1;
+        }
+    }
+    let mut counts: Vec<ErrorCount> = counts
+        .into_iter()
+        .map(|(error, count)| ErrorCount { error, count })
+        .collect();
+    counts.sort_by_key(|e| e.error);
+    counts
+}
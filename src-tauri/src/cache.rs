@@ -0,0 +1,403 @@
+// Crash safe keyword cache in the app data directory
+//
+//   keywords.json                 cached keyword list
+//   keywords.manifest.json        SHA-256 digest, entry count, fetch time and server
+//                                 version of keywords.json
+//   keywords.json.bak (+ .bak manifest)  last known good copy
+//
+// Files are written to a temporary file and renamed, so a crash never leaves a
+// truncated keywords.json behind. Reading verifies the manifest and falls back to
+// the backup when the primary copy is damaged.
+use crate::error::{AppError, AppResult};
+use crate::keyword::{self, ParsedKeywords};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const KEYWORDS_FILE: &str = "keywords.json";
+const MANIFEST_FILE: &str = "keywords.manifest.json";
+const BACKUP_SUFFIX: &str = ".bak";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub sha256: String,
+    /// Number of entries in the keyword array.
+    pub count: usize,
+    pub fetched_at: String,
+    /// `updatedDate` the versions endpoint reported for this download.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_date: Option<String>,
+}
+
+/// Write `bytes` to `path` via a temporary file in the same directory and a rename.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> AppResult<()> {
+    let tmp_path = with_suffix(path, ".tmp");
+    let result = fs::File::create(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(bytes)?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp_path, path));
+    result.map_err(|e| {
+        fs::remove_file(&tmp_path).ok();
+        AppError::CacheIo(format!("Failed to write {}: {}", path.display(), e))
+    })
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+pub fn keywords_path(dir: &Path) -> PathBuf {
+    dir.join(KEYWORDS_FILE)
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    with_suffix(path, BACKUP_SUFFIX)
+}
+
+/// Whether a primary or backup copy exists at all.
+pub fn exists(dir: &Path) -> bool {
+    let path = keywords_path(dir);
+    path.exists() || backup_path(&path).exists()
+}
+
+fn read_manifest(path: &Path) -> AppResult<Option<Manifest>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let json = fs::read_to_string(path)
+        .map_err(|e| AppError::CacheIo(format!("Failed to read {}: {}", path.display(), e)))?;
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| AppError::Parse(format!("Failed to parse {}: {}", path.display(), e)))
+}
+
+/// Read one copy and check it against its manifest.
+///
+/// A copy without manifest (written by an older version) is accepted if it parses.
+fn read_verified(path: &Path, manifest_path: &Path) -> AppResult<(Vec<u8>, ParsedKeywords)> {
+    let bytes = fs::read(path)
+        .map_err(|e| AppError::CacheIo(format!("Failed to read {}: {}", path.display(), e)))?;
+    let manifest = read_manifest(manifest_path)?;
+    if let Some(manifest) = &manifest {
+        let digest = sha256_hex(&bytes);
+        if digest != manifest.sha256 {
+            return Err(AppError::CacheIo(format!(
+                "Checksum mismatch for {}: expected {}, found {}",
+                path.display(),
+                manifest.sha256,
+                digest
+            )));
+        }
+    }
+    let parsed = keyword::parse_keywords(&bytes)
+        .map_err(|e| AppError::Parse(format!("{}: {}", path.display(), e)))?;
+    let count = parsed.keywords.len() + parsed.issues.len();
+    if let Some(manifest) = &manifest
+        && manifest.count != count
+    {
+        return Err(AppError::CacheIo(format!(
+            "{} contains {} entries, manifest expects {}",
+            path.display(),
+            count,
+            manifest.count
+        )));
+    }
+    Ok((bytes, parsed))
+}
+
+/// Load the cached keywords, restoring the backup if the primary copy is damaged.
+pub fn load_keywords(dir: &Path) -> AppResult<ParsedKeywords> {
+    let path = keywords_path(dir);
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let primary_error = match read_verified(&path, &manifest_path) {
+        Ok((_, parsed)) => return Ok(parsed),
+        Err(e) => e,
+    };
+    log::warn!("{}, trying backup", primary_error);
+
+    let backup = backup_path(&path);
+    let backup_manifest = backup_path(&manifest_path);
+    let (bytes, parsed) = read_verified(&backup, &backup_manifest).map_err(|e| {
+        log::error!("Keyword backup unusable as well: {}", e);
+        primary_error
+    })?;
+    log::info!("Restoring keywords from {}", backup.display());
+    // Manifest first like in `store_keywords`; a backup without one leaves none behind
+    let restored = match read_manifest(&backup_manifest) {
+        Ok(Some(manifest)) => write_manifest(&manifest_path, &manifest),
+        Ok(None) if manifest_path.exists() => fs::remove_file(&manifest_path).map_err(|e| {
+            AppError::CacheIo(format!(
+                "Failed to remove {}: {}",
+                manifest_path.display(),
+                e
+            ))
+        }),
+        Ok(None) => Ok(()),
+        Err(e) => Err(e),
+    }
+    .and_then(|_| write_atomic(&path, &bytes));
+    if let Err(e) = restored {
+        log::error!("Failed to restore keyword backup: {}", e);
+    }
+    Ok(parsed)
+}
+
+/// Manifest of the current keywords.json, if there is a readable one.
+pub fn manifest(dir: &Path) -> Option<Manifest> {
+    read_manifest(&dir.join(MANIFEST_FILE)).unwrap_or_else(|e| {
+        log::warn!("{}", e);
+        None
+    })
+}
+
+fn write_manifest(path: &Path, manifest: &Manifest) -> AppResult<()> {
+    let json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| AppError::Parse(format!("Failed to serialize manifest: {}", e)))?;
+    write_atomic(path, json.as_bytes())
+}
+
+/// Atomically replace the cached keywords, keeping the current copy as backup if it is intact.
+pub fn store_keywords(
+    dir: &Path,
+    json: &str,
+    count: usize,
+    updated_date: Option<&str>,
+) -> AppResult<Manifest> {
+    fs::create_dir_all(dir)
+        .map_err(|e| AppError::CacheIo(format!("Failed to create {}: {}", dir.display(), e)))?;
+    let path = keywords_path(dir);
+    let manifest_path = dir.join(MANIFEST_FILE);
+
+    if path.exists() {
+        match read_verified(&path, &manifest_path) {
+            Ok((bytes, previous)) => {
+                let manifest = match read_manifest(&manifest_path)? {
+                    Some(manifest) => manifest,
+                    None => Manifest {
+                        sha256: sha256_hex(&bytes),
+                        count: previous.keywords.len() + previous.issues.len(),
+                        fetched_at: String::new(),
+                        updated_date: None,
+                    },
+                };
+                // Manifest first, as for the primary copy below
+                write_manifest(&backup_path(&manifest_path), &manifest)?;
+                write_atomic(&backup_path(&path), &bytes)?;
+                log::debug!(
+                    "Backed up previous keywords to {}",
+                    backup_path(&path).display()
+                );
+            }
+            Err(e) => log::warn!("Not backing up damaged keyword cache: {}", e),
+        }
+    }
+
+    let manifest = Manifest {
+        sha256: sha256_hex(json.as_bytes()),
+        count,
+        fetched_at: chrono::Utc::now().to_rfc3339(),
+        updated_date: updated_date.map(str::to_string),
+    };
+    // The manifest goes first: a crash in between is detected as checksum mismatch
+    // and answered with the backup instead of silently trusting a stale manifest.
+    write_manifest(&manifest_path, &manifest)?;
+    write_atomic(&path, json.as_bytes())?;
+    log::info!(
+        "Saved {} keywords to {} (sha256 {})",
+        count,
+        path.display(),
+        manifest.sha256
+    );
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Tests run in parallel, every test gets a directory of its own
+    static TEMP_DIRS: AtomicUsize = AtomicUsize::new(0);
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "bbm-cache-{}-{}",
+            std::process::id(),
+            TEMP_DIRS.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    const FIRST: &str = r#"[{"abbrName": "Eng"}, {"abbrName": "Oil"}]"#;
+    const SECOND: &str = r#"[{"abbrName": "Eng"}, {"abbrName": "Oil"}, {"abbrName": "Max"}]"#;
+
+    fn abbr_names(parsed: &ParsedKeywords) -> Vec<&str> {
+        parsed
+            .keywords
+            .iter()
+            .map(|k| k.abbr_name.as_str())
+            .collect()
+    }
+
+    fn backup(dir: &Path) -> PathBuf {
+        backup_path(&keywords_path(dir))
+    }
+
+    fn backup_manifest(dir: &Path) -> AppResult<Option<Manifest>> {
+        read_manifest(&backup_path(&dir.join(MANIFEST_FILE)))
+    }
+
+    #[test]
+    fn stores_and_loads_with_manifest() {
+        let dir = temp_dir();
+        let manifest = store_keywords(&dir, FIRST, 2, Some("2026-01-02")).unwrap();
+        assert_eq!(manifest.sha256, sha256_hex(FIRST.as_bytes()));
+        assert_eq!(manifest.updated_date.as_deref(), Some("2026-01-02"));
+        assert_eq!(super::manifest(&dir), Some(manifest));
+        assert_eq!(
+            abbr_names(&load_keywords(&dir).unwrap()),
+            vec!["Eng", "Oil"]
+        );
+        // Nothing to back up the first time, and no temporary files are left
+        assert!(!backup(&dir).exists());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn keeps_the_previous_copy_as_backup() {
+        let dir = temp_dir();
+        store_keywords(&dir, FIRST, 2, None).unwrap();
+        store_keywords(&dir, SECOND, 3, None).unwrap();
+        assert_eq!(fs::read_to_string(backup(&dir)).unwrap(), FIRST);
+        let manifest = backup_manifest(&dir).unwrap().unwrap();
+        assert_eq!(manifest.sha256, sha256_hex(FIRST.as_bytes()));
+        assert_eq!(manifest.count, 2);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn checksum_mismatch_restores_the_backup() {
+        let dir = temp_dir();
+        store_keywords(&dir, FIRST, 2, None).unwrap();
+        store_keywords(&dir, SECOND, 3, None).unwrap();
+        // Truncated by a crash outside the atomic write, e.g. a full disk
+        fs::write(keywords_path(&dir), &SECOND[..20]).unwrap();
+
+        assert_eq!(
+            abbr_names(&load_keywords(&dir).unwrap()),
+            vec!["Eng", "Oil"]
+        );
+        assert_eq!(fs::read_to_string(keywords_path(&dir)).unwrap(), FIRST);
+        assert_eq!(
+            super::manifest(&dir).unwrap().sha256,
+            sha256_hex(FIRST.as_bytes())
+        );
+        // The restored copy is used directly from now on
+        let (bytes, _) = read_verified(&keywords_path(&dir), &dir.join(MANIFEST_FILE)).unwrap();
+        assert_eq!(bytes, FIRST.as_bytes());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn count_mismatch_falls_back_to_the_backup() {
+        let dir = temp_dir();
+        store_keywords(&dir, FIRST, 2, None).unwrap();
+        store_keywords(&dir, SECOND, 5, None).unwrap();
+        let primary = read_verified(&keywords_path(&dir), &dir.join(MANIFEST_FILE));
+        assert!(matches!(&primary, Err(AppError::CacheIo(m)) if m.contains("manifest expects 5")));
+        assert_eq!(
+            abbr_names(&load_keywords(&dir).unwrap()),
+            vec!["Eng", "Oil"]
+        );
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn damaged_copies_without_backup_fail_with_the_primary_error() {
+        let dir = temp_dir();
+        store_keywords(&dir, FIRST, 2, None).unwrap();
+        fs::write(keywords_path(&dir), SECOND).unwrap();
+        let error = load_keywords(&dir).unwrap_err();
+        assert!(matches!(&error, AppError::CacheIo(m) if m.contains("Checksum mismatch")));
+
+        // A damaged backup does not help either
+        fs::write(backup(&dir), "[").unwrap();
+        assert!(matches!(load_keywords(&dir), Err(AppError::CacheIo(_))));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn damaged_primary_is_not_backed_up() {
+        let dir = temp_dir();
+        store_keywords(&dir, FIRST, 2, None).unwrap();
+        store_keywords(&dir, SECOND, 3, None).unwrap();
+        fs::write(keywords_path(&dir), "[{\"abbrName\": ").unwrap();
+
+        store_keywords(&dir, SECOND, 3, None).unwrap();
+        assert_eq!(fs::read_to_string(backup(&dir)).unwrap(), FIRST);
+        assert_eq!(
+            backup_manifest(&dir).unwrap().unwrap().sha256,
+            sha256_hex(FIRST.as_bytes())
+        );
+        assert_eq!(load_keywords(&dir).unwrap().keywords.len(), 3);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn legacy_copy_without_manifest() {
+        let dir = temp_dir();
+        fs::write(keywords_path(&dir), FIRST).unwrap();
+        assert!(exists(&dir));
+        assert_eq!(super::manifest(&dir), None);
+        assert_eq!(
+            abbr_names(&load_keywords(&dir).unwrap()),
+            vec!["Eng", "Oil"]
+        );
+
+        // Backing it up creates the manifest it never had
+        store_keywords(&dir, SECOND, 3, None).unwrap();
+        let manifest = backup_manifest(&dir).unwrap().unwrap();
+        assert_eq!(manifest.sha256, sha256_hex(FIRST.as_bytes()));
+        assert_eq!(manifest.count, 2);
+        assert_eq!(manifest.fetched_at, "");
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn legacy_backup_does_not_keep_the_damaged_primary_manifest() {
+        let dir = temp_dir();
+        store_keywords(&dir, SECOND, 3, None).unwrap();
+        fs::write(keywords_path(&dir), "not json").unwrap();
+        fs::write(backup(&dir), FIRST).unwrap();
+
+        assert_eq!(
+            abbr_names(&load_keywords(&dir).unwrap()),
+            vec!["Eng", "Oil"]
+        );
+        assert_eq!(super::manifest(&dir), None);
+        assert_eq!(fs::read_to_string(keywords_path(&dir)).unwrap(), FIRST);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn nothing_cached() {
+        let dir = temp_dir();
+        assert!(!exists(&dir));
+        assert!(matches!(load_keywords(&dir), Err(AppError::CacheIo(_))));
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,458 @@
+// Headless command-line mode
+//
+//   bbm-label-explorer validate [--keywords <file>] [--rules <file>] [--waivers <file>]
+//                               [--format <format>] [--output <file>] [<file>... | -]
+//   bbm-label-explorer scan [--config <file>] [--changed-only] [--no-cache]
+//                           [--keywords <file>] [--rules <file>] [--waivers <file>]
+//                           [--format <format>] [--output <file>] <directory>
+//   bbm-label-explorer diff [--base <rev>] [--staged | --head <rev>]
+//                           [--keywords <file>] [--rules <file>] [--waivers <file>]
+//                           [--format <format>] [--output <file>] [<old> <new>]
+//
+// Runs without creating any window and uses the keywords.json cached by the GUI.
+use crate::batch::{self, BatchReport, LabelResult};
+use crate::cache;
+use crate::diff::{self, GitTarget};
+use crate::extract;
+use crate::keyword::{self, Keyword};
+use crate::report::{self, ReportFormat};
+use crate::rules::{self, RuleSet};
+use crate::scan;
+use crate::waiver::{self, WaiverSet};
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Must match `identifier` in tauri.conf.json, Tauri uses it as the app data folder name.
+const APP_IDENTIFIER: &str = "BBMLabelExplorer";
+
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_INVALID_LABELS: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str = "\
+Usage:
+  bbm-label-explorer validate [--keywords <file>] [--rules <file>] [--waivers <file>]
+                              [--format <format>] [--output <file>] [<file>... | -]
+  bbm-label-explorer scan [--config <file>] [--changed-only] [--no-cache]
+                          [--keywords <file>] [--rules <file>] [--waivers <file>]
+                          [--format <format>] [--output <file>] <directory>
+  bbm-label-explorer diff [--base <rev>] [--staged | --head <rev>]
+                          [--keywords <file>] [--rules <file>] [--waivers <file>]
+                          [--format <format>] [--output <file>] [<old> <new>]
+  bbm-label-explorer [<label> | bbm-label://validate?label=<label> | bbm-label://keyword/<abbr>]
+
+Without a command the explorer window opens, filled in with the label or keyword
+if one is given; a running explorer shows it instead.
+
+validate checks the labels of every given file:
+  *.a2l    MEASUREMENT, CHARACTERISTIC and AXIS_PTS names
+  *.arxml  every SHORT-NAME
+  *.c, *.h and other C/C++ files: the default scan patterns below
+  other    one label per line (blank lines and lines starting with # are ignored)
+Labels are read line by line from stdin when no file or '-' is given.
+
+scan checks the C/C++ sources and headers below a directory: the first argument
+of MEASUREMENT, CHARACTERISTIC and AXIS_PTS macros and static variables named
+like a label (ID_...). A bbm-scan.toml in the directory can change the macros,
+the static variable patterns, the extensions and the ignored paths. Only files
+changed since the last scan are read again.
+
+diff only checks labels that were added or renamed, so legacy labels never fail
+it. Without paths it compares the A2L, ARXML and C/C++ files changed in the git
+repository of the current directory: the working tree (or the staged files with
+--staged, e.g. in a pre-commit hook, or revision --head) against --base, HEAD by
+default. With <old> <new> it compares two files or two directories.
+
+Options:
+  --keywords <file>  Keyword database to use instead of the cached keywords.json
+  --rules <file>     Rule set (.toml or .json) to use instead of the built-in rules
+  --waivers <file>   Waiver file (.toml or .json) listing accepted violations
+  --format <format>  Report format: text (default), json, sarif or junit
+  --output <file>    Write the report to a file instead of stdout, the format
+                     follows the extension (.json, .sarif, .xml) unless given
+  --config <file>    Scan configuration to use instead of <directory>/bbm-scan.toml
+  --changed-only     Only report labels of files changed since the last scan
+  --no-cache         Read every file, without using or updating the scan cache
+  --base <rev>       Revision to compare with, HEAD by default
+  --staged           Compare the staged files instead of the working tree
+  --head <rev>       Compare this revision instead of the working tree
+
+Exit codes: 0 all labels valid, 1 at least one label invalid, 2 usage or I/O error";
+
+/// Run the command line mode if the arguments ask for it.
+///
+/// Returns `None` when the GUI should start, otherwise the process exit code.
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = args.first()?;
+    let code = match command.as_str() {
+        "validate" | "scan" | "diff" => {
+            attach_parent_console();
+            let result = match command.as_str() {
+                "scan" => scan(&args[1..]),
+                "diff" => diff(&args[1..]),
+                _ => validate(&args[1..]),
+            };
+            match result {
+                Ok(code) => code,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    EXIT_USAGE
+                }
+            }
+        }
+        "help" | "--help" | "-h" => {
+            attach_parent_console();
+            println!("{}", USAGE);
+            EXIT_OK
+        }
+        _ => return None,
+    };
+    Some(code)
+}
+
+// Release builds use the Windows GUI subsystem, so stdout/stderr only reach the
+// terminal once we attach to the console of the calling process.
+#[cfg(windows)]
+fn attach_parent_console() {
+    unsafe extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
+
+/// App data folder holding the keywords.json written by `start_keyword_loading`.
+fn default_data_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join(APP_IDENTIFIER))
+}
+
+/// Scan cache folder shared with the GUI.
+fn default_scan_cache_dir() -> Option<PathBuf> {
+    default_data_dir().map(|dir| dir.join(scan::CACHE_DIR))
+}
+
+/// Keywords of `path`, or of the GUI's cache, checked against its manifest and restored
+/// from the backup like the GUI does.
+pub fn load_keywords(path: Option<PathBuf>) -> Result<Vec<Keyword>, String> {
+    let (source, parsed) = match path {
+        Some(path) => {
+            let parsed = keyword::read_keywords_file(&path)?;
+            (path, parsed)
+        }
+        None => {
+            let dir = default_data_dir()
+                .ok_or("Unable to determine the keyword cache location, use --keywords")?;
+            let parsed = cache::load_keywords(&dir).map_err(|e| {
+                format!(
+                    "{} (start the application once to download the keyword database)",
+                    e
+                )
+            })?;
+            (cache::keywords_path(&dir), parsed)
+        }
+    };
+    for issue in &parsed.issues {
+        eprintln!(
+            "warning: skipping malformed keyword #{} in {}: {}",
+            issue.index,
+            source.display(),
+            issue.message
+        );
+    }
+    Ok(parsed.keywords)
+}
+
+// Options shared by all commands producing a report
+#[derive(Default)]
+struct ReportOptions<'a> {
+    keywords_path: Option<PathBuf>,
+    rule_set: RuleSet,
+    waivers: WaiverSet,
+    format_name: Option<&'a str>,
+    output: Option<PathBuf>,
+}
+
+impl<'a> ReportOptions<'a> {
+    /// Consume `arg` and its value if it is a report option.
+    fn parse(
+        &mut self,
+        arg: &str,
+        iter: &mut impl Iterator<Item = &'a String>,
+    ) -> Result<bool, String> {
+        match arg {
+            "--keywords" => {
+                let path = iter.next().ok_or("--keywords requires a file")?;
+                self.keywords_path = Some(PathBuf::from(path));
+            }
+            "--rules" => {
+                let path = iter.next().ok_or("--rules requires a file")?;
+                self.rule_set =
+                    rules::read_rule_set(&PathBuf::from(path)).map_err(|e| e.to_string())?;
+            }
+            "--waivers" => {
+                let path = iter.next().ok_or("--waivers requires a file")?;
+                self.waivers = waiver::read_waivers(&PathBuf::from(path))?;
+            }
+            "--format" => {
+                self.format_name = Some(iter.next().ok_or("--format requires a format")?.as_str());
+            }
+            "--output" => {
+                let path = iter.next().ok_or("--output requires a file")?;
+                self.output = Some(PathBuf::from(path));
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Machine-readable format asked for, `None` for the text report.
+    fn report_format(&self) -> Result<Option<ReportFormat>, String> {
+        match (self.format_name, &self.output) {
+            (Some("text"), Some(_)) => {
+                Err("--output needs --format json, sarif or junit".to_string())
+            }
+            (Some("text"), None) | (None, None) => Ok(None),
+            (Some(name), _) => ReportFormat::parse(name).map(Some),
+            (None, Some(output)) => ReportFormat::from_path(output).map(Some).ok_or_else(|| {
+                format!(
+                    "cannot tell the report format from {}, use --format",
+                    output.display()
+                )
+            }),
+        }
+    }
+
+    /// Print or write the report and return the exit code.
+    fn finish(self, report: &BatchReport, format: Option<ReportFormat>) -> Result<i32, String> {
+        match (format, self.output) {
+            (Some(format), Some(output)) => {
+                let rendered = report::render(report, format)?;
+                fs::write(&output, rendered)
+                    .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+                print_report(report);
+            }
+            (Some(format), None) => println!("{}", report::render(report, format)?),
+            (None, _) => print_report(report),
+        }
+        Ok(if report.has_failures() {
+            EXIT_INVALID_LABELS
+        } else {
+            EXIT_OK
+        })
+    }
+}
+
+fn validate(args: &[String]) -> Result<i32, String> {
+    let mut options = ReportOptions::default();
+    let mut inputs = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if options.parse(arg, &mut iter)? {
+            continue;
+        }
+        match arg.as_str() {
+            flag if flag.starts_with("--") => {
+                return Err(format!("unknown option {}\n\n{}", flag, USAGE));
+            }
+            path => inputs.push(path.to_string()),
+        }
+    }
+    let report_format = options.report_format()?;
+
+    let mut labels = Vec::new();
+    if inputs.is_empty() {
+        inputs.push("-".to_string());
+    }
+    for input in &inputs {
+        if input == "-" {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| format!("Failed to read stdin: {}", e))?;
+            labels.extend(extract::extract_text(&text, None));
+        } else {
+            labels.extend(extract::extract_file(&PathBuf::from(input)).map_err(|e| e.to_string())?);
+        }
+    }
+
+    let keywords = load_keywords(options.keywords_path.take())?;
+    let report = batch::validate_labels(
+        inputs,
+        labels,
+        &keywords,
+        &options.rule_set,
+        &options.waivers,
+    );
+    options.finish(&report, report_format)
+}
+
+fn scan(args: &[String]) -> Result<i32, String> {
+    let mut options = ReportOptions::default();
+    let mut config_path = None;
+    let mut changed_only = false;
+    let mut use_cache = true;
+    let mut root = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if options.parse(arg, &mut iter)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--config" => {
+                let path = iter.next().ok_or("--config requires a file")?;
+                config_path = Some(PathBuf::from(path));
+            }
+            "--changed-only" => changed_only = true,
+            "--no-cache" => use_cache = false,
+            flag if flag.starts_with("--") => {
+                return Err(format!("unknown option {}\n\n{}", flag, USAGE));
+            }
+            path if root.is_none() => root = Some(PathBuf::from(path)),
+            _ => return Err(format!("scan takes a single directory\n\n{}", USAGE)),
+        }
+    }
+    let root = root.ok_or_else(|| format!("scan requires a directory\n\n{}", USAGE))?;
+    let report_format = options.report_format()?;
+    let config = match &config_path {
+        Some(path) => scan::read_scan_config(path),
+        None => scan::config_for(&root),
+    }
+    .map_err(|e| e.to_string())?;
+    let cache_dir = use_cache.then(default_scan_cache_dir).flatten();
+
+    let scanned = scan::scan(&root, &config, cache_dir.as_deref(), changed_only)
+        .map_err(|e| e.to_string())?;
+    if report_format.is_none() || options.output.is_some() {
+        println!(
+            "Scanned {} source files, {} changed since the last scan",
+            scanned.files,
+            scanned.changed.len()
+        );
+    }
+    let keywords = load_keywords(options.keywords_path.take())?;
+    let files = vec![root.display().to_string()];
+    let report = batch::validate_labels(
+        files,
+        scanned.labels,
+        &keywords,
+        &options.rule_set,
+        &options.waivers,
+    );
+    options.finish(&report, report_format)
+}
+
+fn diff(args: &[String]) -> Result<i32, String> {
+    let mut options = ReportOptions::default();
+    let mut base = None;
+    let mut target = GitTarget::WorkTree;
+    let mut paths = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if options.parse(arg, &mut iter)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--base" => base = Some(iter.next().ok_or("--base requires a revision")?.as_str()),
+            "--staged" | "--cached" => target = GitTarget::Index,
+            "--head" => {
+                let revision = iter.next().ok_or("--head requires a revision")?;
+                target = GitTarget::Revision(revision.clone());
+            }
+            flag if flag.starts_with("--") => {
+                return Err(format!("unknown option {}\n\n{}", flag, USAGE));
+            }
+            path => paths.push(PathBuf::from(path)),
+        }
+    }
+    let report_format = options.report_format()?;
+
+    let changes = match paths.as_slice() {
+        [] => diff::git_diff(Path::new("."), base.unwrap_or("HEAD"), &target)?,
+        [old, new] if base.is_none() && target == GitTarget::WorkTree => diff::path_diff(old, new)?,
+        [_, _] => return Err("--base, --staged and --head only apply to git diffs".to_string()),
+        _ => return Err(format!("diff takes no or two paths\n\n{}", USAGE)),
+    };
+    if report_format.is_none() || options.output.is_some() {
+        println!(
+            "{} labels added or renamed in {} files, {} unchanged, {} removed",
+            changes.added.len(),
+            changes.files.len(),
+            changes.unchanged,
+            changes.removed
+        );
+    }
+    let keywords = load_keywords(options.keywords_path.take())?;
+    let report = batch::validate_labels(
+        changes.files,
+        changes.added,
+        &keywords,
+        &options.rule_set,
+        &options.waivers,
+    );
+    options.finish(&report, report_format)
+}
+
+fn print_report(report: &BatchReport) {
+    for result in &report.results {
+        print_result(result);
+    }
+    let summary = &report.summary;
+    println!(
+        "\n{} labels checked, {} valid, {} invalid",
+        summary.total, summary.valid, summary.invalid
+    );
+    for count in &summary.errors {
+        println!("  {:>6}  {}", count.count, count.error);
+    }
+    for count in &summary.warnings {
+        println!("  {:>6}  warning: {}", count.count, count.error);
+    }
+    for count in &summary.waived {
+        println!("  {:>6}  waived: {}", count.count, count.error);
+    }
+    let waivers = &report.waivers;
+    for waiver in &waivers.expired {
+        println!(
+            "expired waiver: {} {} (expired {}, {})",
+            waiver.label,
+            waiver.rule,
+            waiver.expires.as_deref().unwrap_or_default(),
+            waiver.author
+        );
+    }
+    for waiver in &waivers.unused {
+        println!(
+            "unused waiver: {} {} ({})",
+            waiver.label, waiver.rule, waiver.author
+        );
+    }
+}
+
+fn print_result(result: &LabelResult) {
+    let verdict = if result.report.valid { "OK  " } else { "FAIL" };
+    match &result.source.file {
+        Some(file) => println!(
+            "{} {} ({}:{} {})",
+            verdict, result.source.name, file, result.source.line, result.source.kind
+        ),
+        None => println!("{} {}", verdict, result.source.name),
+    }
+    for error in &result.report.errors {
+        println!("       - {}", error);
+    }
+    for warning in &result.report.warnings {
+        println!("       - warning: {}", warning);
+    }
+    for waived in &result.report.waived {
+        println!(
+            "       - waived: {} ({}, {})",
+            waived.error, waived.justification, waived.author
+        );
+    }
+}
@@ -0,0 +1,204 @@
+// Environment specific configuration (see docs/environment-config.md)
+//
+// config.dev.yaml and config.prod.yaml are embedded at build time, APP_ENV
+// selects one of them. Secrets never live in these files, they come from
+// environment variables.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::OnceLock;
+
+const DEV_CONFIG: &str = include_str!("../config.dev.yaml");
+const PROD_CONFIG: &str = include_str!("../config.prod.yaml");
+
+/// Environment variable holding the API token.
+const API_TOKEN_VAR: &str = "BBM_API_TOKEN";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    Dev,
+    Prod,
+}
+
+impl Environment {
+    fn parse(value: &str) -> Option<Environment> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "dev" | "development" => Some(Environment::Dev),
+            "prod" | "production" => Some(Environment::Prod),
+            _ => None,
+        }
+    }
+
+    /// `APP_ENV` at runtime, then `APP_ENV` at build time, then the build profile.
+    fn select() -> Result<Environment, ConfigError> {
+        let value = std::env::var("APP_ENV")
+            .ok()
+            .or_else(|| option_env!("APP_ENV").map(str::to_string));
+        match value {
+            Some(value) => Environment::parse(&value).ok_or(ConfigError::UnknownEnvironment(value)),
+            None if cfg!(debug_assertions) => Ok(Environment::Dev),
+            None => Ok(Environment::Prod),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    UnknownEnvironment(String),
+    Parse(String),
+    MissingKeys(Vec<&'static str>),
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::UnknownEnvironment(env) => {
+                write!(f, "Unknown APP_ENV '{}', expected dev or prod", env)
+            }
+            ConfigError::Parse(e) => write!(f, "Failed to parse configuration: {}", e),
+            ConfigError::MissingKeys(keys) => {
+                write!(
+                    f,
+                    "Missing required configuration keys: {}",
+                    keys.join(", ")
+                )
+            }
+            ConfigError::Invalid(e) => write!(f, "Invalid configuration: {}", e),
+        }
+    }
+}
+
+/// Application configuration of the selected environment.
+///
+/// Serializing it only yields the non-secret values.
+#[derive(Debug, Clone, Serialize)]
+pub struct AppConfig {
+    pub environment: Environment,
+    pub api_url: String,
+    pub keywords_endpoint: String,
+    pub version_endpoint: String,
+    pub request_timeout_secs: u64,
+    pub connect_timeout_secs: u64,
+    pub debug_mode: bool,
+    pub log_level: String,
+    #[serde(skip_serializing)]
+    pub api_token: Option<String>,
+}
+
+// Everything optional so missing keys can be reported together
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    environment: Option<Environment>,
+    api_url: Option<String>,
+    keywords_endpoint: Option<String>,
+    version_endpoint: Option<String>,
+    request_timeout_secs: Option<u64>,
+    connect_timeout_secs: Option<u64>,
+    debug_mode: Option<bool>,
+    log_level: Option<String>,
+}
+
+impl AppConfig {
+    /// Parse and validate a YAML configuration for the given environment.
+    pub fn from_yaml(yaml: &str, environment: Environment) -> Result<AppConfig, ConfigError> {
+        let raw: RawConfig =
+            serde_yaml::from_str(yaml).map_err(|e| ConfigError::Parse(e.to_string()))?;
+
+        let mut missing = Vec::new();
+        if raw.api_url.is_none() {
+            missing.push("api_url");
+        }
+        if raw.keywords_endpoint.is_none() {
+            missing.push("keywords_endpoint");
+        }
+        if raw.version_endpoint.is_none() {
+            missing.push("version_endpoint");
+        }
+        if !missing.is_empty() {
+            return Err(ConfigError::MissingKeys(missing));
+        }
+        if let Some(declared) = raw.environment
+            && declared != environment
+        {
+            return Err(ConfigError::Invalid(format!(
+                "file declares environment {:?} but {:?} was selected",
+                declared, environment
+            )));
+        }
+
+        let config = AppConfig {
+            environment,
+            api_url: raw.api_url.unwrap_or_default(),
+            keywords_endpoint: raw.keywords_endpoint.unwrap_or_default(),
+            version_endpoint: raw.version_endpoint.unwrap_or_default(),
+            request_timeout_secs: raw.request_timeout_secs.unwrap_or(30),
+            connect_timeout_secs: raw.connect_timeout_secs.unwrap_or(10),
+            debug_mode: raw.debug_mode.unwrap_or(environment == Environment::Dev),
+            log_level: raw.log_level.unwrap_or_else(|| "info".to_string()),
+            api_token: std::env::var(API_TOKEN_VAR)
+                .ok()
+                .filter(|token| !token.trim().is_empty()),
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if !self.api_url.starts_with("http://") && !self.api_url.starts_with("https://") {
+            return Err(ConfigError::Invalid(format!(
+                "api_url must be an http(s) URL, got '{}'",
+                self.api_url
+            )));
+        }
+        for (key, endpoint) in [
+            ("keywords_endpoint", &self.keywords_endpoint),
+            ("version_endpoint", &self.version_endpoint),
+        ] {
+            if !endpoint.starts_with('/') {
+                return Err(ConfigError::Invalid(format!("{} must start with '/'", key)));
+            }
+        }
+        if self.request_timeout_secs == 0 || self.connect_timeout_secs == 0 {
+            return Err(ConfigError::Invalid(
+                "timeouts must be at least one second".to_string(),
+            ));
+        }
+        self.log_level_filter()?;
+        Ok(())
+    }
+
+    pub fn log_level_filter(&self) -> Result<log::LevelFilter, ConfigError> {
+        self.log_level
+            .parse()
+            .map_err(|_| ConfigError::Invalid(format!("unknown log_level '{}'", self.log_level)))
+    }
+
+    fn load() -> Result<AppConfig, ConfigError> {
+        let environment = Environment::select()?;
+        let yaml = match environment {
+            Environment::Dev => DEV_CONFIG,
+            Environment::Prod => PROD_CONFIG,
+        };
+        AppConfig::from_yaml(yaml, environment)
+    }
+}
+
+/// Configuration of the current environment, loaded once.
+///
+/// Invalid configuration is reported and replaced by the production file so
+/// the application still starts.
+pub fn get_config() -> &'static AppConfig {
+    static CONFIG: OnceLock<AppConfig> = OnceLock::new();
+    CONFIG.get_or_init(|| match AppConfig::load() {
+        Ok(config) => config,
+        Err(e) => {
+            // The logger may not be initialised yet
+            eprintln!("{}, falling back to production configuration", e);
+            log::error!("{}, falling back to production configuration", e);
+            AppConfig::from_yaml(PROD_CONFIG, Environment::Prod)
+                .expect("embedded config.prod.yaml must be valid")
+        }
+    })
+}
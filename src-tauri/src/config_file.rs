@@ -0,0 +1,24 @@
+// Files users write by hand: rule sets, waiver files and scan configurations
+//
+// Each of them may be TOML or JSON, told apart by the file extension.
+use crate::error::{AppError, AppResult};
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::Path;
+
+/// Read `path` as TOML if it ends in `.toml` and as JSON otherwise.
+///
+/// `what` names the file in parse errors, e.g. `rule set`.
+pub fn read_toml_or_json<T: DeserializeOwned>(path: &Path, what: &str) -> AppResult<T> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| AppError::CacheIo(format!("Failed to read {}: {}", path.display(), e)))?;
+    let is_toml = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+    if is_toml {
+        toml::from_str(&text).map_err(|e| e.to_string())
+    } else {
+        serde_json::from_str(&text).map_err(|e| e.to_string())
+    }
+    .map_err(|e| AppError::Parse(format!("Invalid {} {}: {}", what, path.display(), e)))
+}
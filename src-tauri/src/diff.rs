@@ -0,0 +1,348 @@
+// Labels introduced by a change
+//
+// Compares the labels of two versions of a set of files, either two revisions of a git
+// repository or two files or directories on disk. Labels whose name does not occur in
+// the old version were added or renamed and are the only ones validated, so legacy
+// labels do not fail a pre-commit hook or merge gate.
+use crate::extract::{self, ExtractedLabel, SourceFormat};
+use crate::scan::{self, ScanConfig};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Which version of the files a git diff compares the base revision with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitTarget {
+    /// The files in the working tree, including untracked files that are not ignored.
+    WorkTree,
+    /// The staged files, as committed by a pre-commit hook.
+    Index,
+    Revision(String),
+}
+
+/// Labels of the new version that are not in the old one.
+#[derive(Debug, Clone, Default)]
+pub struct LabelDiff {
+    /// Changed files that can contain labels, in their new location.
+    pub files: Vec<String>,
+    pub added: Vec<ExtractedLabel>,
+    /// Labels of the new version that already existed.
+    pub unchanged: usize,
+    /// Labels of the old version that no longer exist.
+    pub removed: usize,
+}
+
+fn compare(old: Vec<ExtractedLabel>, new: Vec<ExtractedLabel>, files: Vec<String>) -> LabelDiff {
+    let old_names: HashSet<&str> = old.iter().map(|label| label.name.as_str()).collect();
+    let new_names: HashSet<&str> = new.iter().map(|label| label.name.as_str()).collect();
+    let removed = old_names.difference(&new_names).count();
+    let (unchanged, added): (Vec<_>, Vec<_>) = new
+        .into_iter()
+        .partition(|label| old_names.contains(label.name.as_str()));
+    LabelDiff {
+        files,
+        added,
+        unchanged: unchanged.len(),
+        removed,
+    }
+}
+
+/// Only formats with a defined label syntax take part, plain text files could be anything.
+fn has_labels(path: &Path) -> bool {
+    SourceFormat::from_path(path) != SourceFormat::Text
+}
+
+/// Labels added between `base` and `target` in the git repository containing `dir`.
+///
+/// C/C++ files are read with the `bbm-scan.toml` of the repository root, whose
+/// ignore patterns apply to all files.
+pub fn git_diff(dir: &Path, base: &str, target: &GitTarget) -> Result<LabelDiff, String> {
+    let root = PathBuf::from(
+        String::from_utf8_lossy(&git(dir, &["rev-parse", "--show-toplevel"])?).trim(),
+    );
+    let config = scan::config_for(&root).map_err(|e| e.to_string())?;
+
+    let mut args = vec!["diff", "--name-status", "-M", "-z", "--no-ext-diff", base];
+    match target {
+        GitTarget::WorkTree => {}
+        GitTarget::Index => args.push("--cached"),
+        GitTarget::Revision(revision) => args.push(revision.as_str()),
+    }
+    let output = git(&root, &args)?;
+    let mut changes = parse_name_status(&String::from_utf8_lossy(&output))?;
+    // git diff only knows tracked files, new files are usually not added yet
+    if *target == GitTarget::WorkTree {
+        let output = git(&root, &["ls-files", "--others", "--exclude-standard", "-z"])?;
+        changes.extend(
+            String::from_utf8_lossy(&output)
+                .split('\0')
+                .filter(|path| !path.is_empty())
+                .map(|path| (None, Some(path.to_string()))),
+        );
+    }
+
+    let mut old_labels = Vec::new();
+    let mut new_labels = Vec::new();
+    let mut files = Vec::new();
+    for (old_path, new_path) in changes {
+        if let Some(path) = &old_path
+            && has_labels(Path::new(path))
+            && !config.is_ignored(path)
+        {
+            let text = git_show(&root, &format!("{}:{}", base, path))?;
+            old_labels.extend(extract_git_file(&text, path, &config)?);
+        }
+        if let Some(path) = &new_path
+            && has_labels(Path::new(path))
+            && !config.is_ignored(path)
+        {
+            let text = match target {
+                GitTarget::WorkTree => {
+                    let bytes = fs::read(root.join(path))
+                        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+                    String::from_utf8_lossy(&bytes).into_owned()
+                }
+                GitTarget::Index => git_show(&root, &format!(":{}", path))?,
+                GitTarget::Revision(revision) => {
+                    git_show(&root, &format!("{}:{}", revision, path))?
+                }
+            };
+            new_labels.extend(extract_git_file(&text, path, &config)?);
+            files.push(path.clone());
+        }
+    }
+    Ok(compare(old_labels, new_labels, files))
+}
+
+fn extract_git_file(
+    text: &str,
+    path: &str,
+    config: &ScanConfig,
+) -> Result<Vec<ExtractedLabel>, String> {
+    let format = SourceFormat::from_path(Path::new(path));
+    extract::extract_source(text, format, Some(path.to_string()), config)
+        .map_err(|e| format!("{}: {}", path, e))
+}
+
+// Old and new path of a changed file; added files have no old path, deleted files no new path
+type FileChange = (Option<String>, Option<String>);
+
+// `git diff --name-status -z` output
+fn parse_name_status(output: &str) -> Result<Vec<FileChange>, String> {
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
+    let mut changes = Vec::new();
+    while let Some(status) = fields.next() {
+        let mut path = || {
+            fields
+                .next()
+                .map(str::to_string)
+                .ok_or_else(|| format!("Unexpected git diff output after status {}", status))
+        };
+        let change = match status.chars().next() {
+            Some('A') => (None, Some(path()?)),
+            Some('D') => (Some(path()?), None),
+            Some('R') => (Some(path()?), Some(path()?)),
+            // The source of a copy is unchanged, its labels are not in the diff
+            Some('C') => {
+                path()?;
+                (None, Some(path()?))
+            }
+            _ => {
+                let path = path()?;
+                (Some(path.clone()), Some(path))
+            }
+        };
+        changes.push(change);
+    }
+    Ok(changes)
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<Vec<u8>, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+fn git_show(root: &Path, object: &str) -> Result<String, String> {
+    git(root, &["show", object]).map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Labels added between two files, or two directories such as unpacked releases.
+///
+/// Directories are compared as a whole, so labels moved between files are not new.
+/// Their A2L, ARXML and C/C++ files are read with the `bbm-scan.toml` of the new
+/// directory.
+pub fn path_diff(old: &Path, new: &Path) -> Result<LabelDiff, String> {
+    match (old.is_dir(), new.is_dir()) {
+        (false, false) => {
+            let old_labels = extract::extract_file(old).map_err(|e| e.to_string())?;
+            let new_labels = extract::extract_file(new).map_err(|e| e.to_string())?;
+            Ok(compare(
+                old_labels,
+                new_labels,
+                vec![new.display().to_string()],
+            ))
+        }
+        (true, true) => {
+            let mut config = scan::config_for(new).map_err(|e| e.to_string())?;
+            config.extensions.extend(["a2l", "arxml"].map(String::from));
+            let (old_labels, _) = extract_tree(old, &config)?;
+            let (new_labels, files) = extract_tree(new, &config)?;
+            Ok(compare(old_labels, new_labels, files))
+        }
+        _ => Err(format!(
+            "{} and {} must both be files or both be directories",
+            old.display(),
+            new.display()
+        )),
+    }
+}
+
+fn extract_tree(
+    root: &Path,
+    config: &ScanConfig,
+) -> Result<(Vec<ExtractedLabel>, Vec<String>), String> {
+    let mut labels = Vec::new();
+    let mut files = Vec::new();
+    for relative in scan::source_files(root, config).map_err(|e| e.to_string())? {
+        let path = root.join(&relative);
+        let bytes =
+            fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let file = path.display().to_string();
+        labels.extend(
+            extract::extract_source(
+                &String::from_utf8_lossy(&bytes),
+                SourceFormat::from_path(&path),
+                Some(file.clone()),
+                config,
+            )
+            .map_err(|e| e.to_string())?,
+        );
+        files.push(file);
+    }
+    Ok((labels, files))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(name: &str, line: usize) -> ExtractedLabel {
+        ExtractedLabel {
+            name: name.to_string(),
+            kind: "MEASUREMENT".to_string(),
+            file: Some("new.a2l".to_string()),
+            line,
+        }
+    }
+
+    fn change(old: Option<&str>, new: Option<&str>) -> FileChange {
+        (old.map(str::to_string), new.map(str::to_string))
+    }
+
+    #[test]
+    fn parses_name_status_records() {
+        let output = "M\0src/a.c\0A\0src/new.c\0D\0old.a2l\0\
+                      R087\0src/before.c\0src/after.c\0C100\0template.arxml\0copy.arxml\0";
+        assert_eq!(
+            parse_name_status(output).unwrap(),
+            vec![
+                change(Some("src/a.c"), Some("src/a.c")),
+                change(None, Some("src/new.c")),
+                change(Some("old.a2l"), None),
+                change(Some("src/before.c"), Some("src/after.c")),
+                change(None, Some("copy.arxml")),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_paths_with_spaces_and_tabs() {
+        let output = "A\0dir with space/a\tb.c\0";
+        assert_eq!(
+            parse_name_status(output).unwrap(),
+            vec![change(None, Some("dir with space/a\tb.c"))]
+        );
+        assert!(parse_name_status("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_truncated_name_status_output() {
+        assert!(parse_name_status("R100\0src/before.c\0").is_err());
+        assert!(parse_name_status("M\0").is_err());
+    }
+
+    #[test]
+    fn only_labels_missing_from_the_old_version_are_added() {
+        let old = vec![
+            label("Eng_nSpd", 1),
+            label("Eng_tOil", 2),
+            label("Eng_Old", 3),
+        ];
+        let new = vec![
+            label("Eng_nSpd", 4),
+            label("Eng_tOil", 5),
+            label("Eng_tOil", 6),
+            label("Eng_pRail", 7),
+        ];
+        let diff = compare(old, new, vec!["new.a2l".to_string()]);
+        assert_eq!(diff.added, vec![label("Eng_pRail", 7)]);
+        assert_eq!(diff.unchanged, 3);
+        assert_eq!(diff.removed, 1);
+        assert_eq!(diff.files, vec!["new.a2l".to_string()]);
+    }
+
+    #[test]
+    fn work_tree_diff_includes_untracked_files() {
+        let dir = std::env::temp_dir().join(format!("bbm-diff-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let measurement =
+            |name: &str| format!("/begin MEASUREMENT {} \"\"\n/end MEASUREMENT\n", name);
+        fs::write(dir.join("old.a2l"), measurement("Eng_nSpd")).unwrap();
+        git(&dir, &["init", "-q"]).unwrap();
+        git(&dir, &["add", "old.a2l"]).unwrap();
+        let commit = [
+            "-c",
+            "user.name=test",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "base",
+        ];
+        git(&dir, &commit).unwrap();
+        fs::write(dir.join("new.a2l"), measurement("Eng_tOil")).unwrap();
+
+        let result = git_diff(&dir, "HEAD", &GitTarget::WorkTree);
+        fs::remove_dir_all(&dir).ok();
+        let diff = result.unwrap();
+        assert_eq!(diff.files, vec!["new.a2l".to_string()]);
+        let added: Vec<&str> = diff.added.iter().map(|label| label.name.as_str()).collect();
+        assert_eq!(added, vec!["Eng_tOil"]);
+    }
+
+    #[test]
+    fn renamed_labels_count_as_added_and_removed() {
+        let diff = compare(
+            vec![label("Eng_nSpd", 1)],
+            vec![label("Eng_nSpeed", 1)],
+            Vec::new(),
+        );
+        assert_eq!(diff.added, vec![label("Eng_nSpeed", 1)]);
+        assert_eq!(diff.unchanged, 0);
+        assert_eq!(diff.removed, 1);
+    }
+}
@@ -0,0 +1,118 @@
+// Error type shared by all commands and background tasks
+//
+// Commands return it to the webview as `{ code, message, retryable }`. Background
+// tasks have no caller to return to, they emit the same payload as `backend-error`.
+use crate::fetch::FetchError;
+use serde::Serialize;
+use serde::ser::{SerializeStruct, Serializer};
+use std::fmt;
+use tauri::{AppHandle, Emitter};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    /// The keyword API could not be reached or answered with an error status.
+    Network { message: String, retryable: bool },
+    /// A response or file did not contain what was expected.
+    Parse(String),
+    /// Reading or writing the cache, settings or other app data failed.
+    CacheIo(String),
+    /// A window could not be found, shown or positioned.
+    Window(String),
+    /// The input of a command was rejected.
+    Validation(String),
+    /// A background task panicked or was cancelled.
+    Internal(String),
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+/// Payload of the `backend-error` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendError<'a> {
+    #[serde(flatten)]
+    pub error: &'a AppError,
+    /// Background task that failed, e.g. `keyword-loading`.
+    pub source: &'a str,
+}
+
+impl AppError {
+    pub fn network(message: impl Into<String>) -> AppError {
+        AppError::Network {
+            message: message.into(),
+            retryable: true,
+        }
+    }
+
+    /// Stable identifier the frontend can branch on.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Network { .. } => "network",
+            AppError::Parse(_) => "parse",
+            AppError::CacheIo(_) => "cache_io",
+            AppError::Window(_) => "window",
+            AppError::Validation(_) => "validation",
+            AppError::Internal(_) => "internal",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::Network { message, .. }
+            | AppError::Parse(message)
+            | AppError::CacheIo(message)
+            | AppError::Window(message)
+            | AppError::Validation(message)
+            | AppError::Internal(message) => message,
+        }
+    }
+
+    /// Whether repeating the same call may succeed.
+    pub fn retryable(&self) -> bool {
+        match self {
+            AppError::Network { retryable, .. } => *retryable,
+            AppError::Parse(_)
+            | AppError::CacheIo(_)
+            | AppError::Window(_)
+            | AppError::Validation(_)
+            | AppError::Internal(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", self.message())?;
+        state.serialize_field("retryable", &self.retryable())?;
+        state.end()
+    }
+}
+
+impl From<FetchError> for AppError {
+    fn from(error: FetchError) -> AppError {
+        match error {
+            FetchError::Transport(_) | FetchError::Status { .. } => AppError::Network {
+                retryable: error.is_retryable(),
+                message: error.to_string(),
+            },
+            FetchError::ContentType(_) | FetchError::Parse(_) => AppError::Parse(error.to_string()),
+        }
+    }
+}
+
+/// Log a failed background task and report it to the webview as `backend-error`.
+pub fn emit(app: &AppHandle, source: &str, error: &AppError) {
+    log::error!("{} failed: {}", source, error);
+    if let Err(e) = app.emit("backend-error", BackendError { error, source }) {
+        log::error!("Failed to emit backend-error: {}", e);
+    }
+}
@@ -0,0 +1,417 @@
+// Label extraction from source files (plain label lists, ASAP2, AUTOSAR XML and C/C++)
+use crate::error::{AppError, AppResult};
+use crate::scan::{self, ScanConfig};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// A2L blocks whose first parameter is a label.
+const A2L_LABEL_BLOCKS: &[&str] = &["MEASUREMENT", "CHARACTERISTIC", "AXIS_PTS"];
+
+/// A label found in a file, together with where it was found.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractedLabel {
+    pub name: String,
+    /// What defined the label, e.g. `MEASUREMENT` or the ARXML element owning the short-name.
+    pub kind: String,
+    pub file: Option<String>,
+    /// 1-based line number.
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceFormat {
+    Text,
+    A2l,
+    Arxml,
+    C,
+}
+
+impl SourceFormat {
+    pub fn from_path(path: &Path) -> SourceFormat {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("a2l") => SourceFormat::A2l,
+            Some("arxml") => SourceFormat::Arxml,
+            Some("c" | "h" | "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx") => SourceFormat::C,
+            _ => SourceFormat::Text,
+        }
+    }
+}
+
+/// Read a file and extract its labels, choosing the format from the extension.
+pub fn extract_file(path: &Path) -> AppResult<Vec<ExtractedLabel>> {
+    let bytes = fs::read(path)
+        .map_err(|e| AppError::CacheIo(format!("Failed to read {}: {}", path.display(), e)))?;
+    // A2L files are frequently Latin-1 encoded, labels themselves are ASCII
+    let text = String::from_utf8_lossy(&bytes);
+    let labels = extract_source(
+        &text,
+        SourceFormat::from_path(path),
+        Some(path.display().to_string()),
+        &ScanConfig::default(),
+    )?;
+    log::info!("Extracted {} labels from {}", labels.len(), path.display());
+    Ok(labels)
+}
+
+/// Extract the labels of file contents in the given format.
+pub fn extract_source(
+    text: &str,
+    format: SourceFormat,
+    file: Option<String>,
+    scan_config: &ScanConfig,
+) -> AppResult<Vec<ExtractedLabel>> {
+    Ok(match format {
+        SourceFormat::Text => extract_text(text, file),
+        SourceFormat::A2l => extract_a2l(text, file),
+        SourceFormat::Arxml => extract_arxml(text, file)?,
+        SourceFormat::C => scan::extract_c(text, file, scan_config),
+    })
+}
+
+/// One label per line, blank lines and `#` comments are ignored.
+pub fn extract_text(text: &str, file: Option<String>) -> Vec<ExtractedLabel> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line, name)| ExtractedLabel {
+            name: name.to_string(),
+            kind: "label".to_string(),
+            file: file.clone(),
+            line,
+        })
+        .collect()
+}
+
+/// Names of all MEASUREMENT, CHARACTERISTIC and AXIS_PTS blocks of an ASAP2 file.
+pub fn extract_a2l(text: &str, file: Option<String>) -> Vec<ExtractedLabel> {
+    let mut labels = Vec::new();
+    let mut tokens = A2lTokens::new(text);
+    while let Some((token, _)) = tokens.next() {
+        if token != "/begin" {
+            continue;
+        }
+        let Some((block, _)) = tokens.next() else {
+            break;
+        };
+        if !A2L_LABEL_BLOCKS.contains(&block) {
+            continue;
+        }
+        if let Some((name, line)) = tokens.next() {
+            labels.push(ExtractedLabel {
+                name: name.to_string(),
+                kind: block.to_string(),
+                file: file.clone(),
+                line,
+            });
+        }
+    }
+    labels
+}
+
+// Whitespace separated A2L tokens with their line, skipping comments and strings
+struct A2lTokens<'a> {
+    text: &'a str,
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> A2lTokens<'a> {
+    fn new(text: &'a str) -> Self {
+        A2lTokens {
+            text,
+            pos: 0,
+            line: 1,
+        }
+    }
+
+    // Advance past `len` bytes, keeping the line count up to date
+    fn skip(&mut self, len: usize) {
+        let end = (self.pos + len).min(self.text.len());
+        self.line += self.text[self.pos..end].matches('\n').count();
+        self.pos = end;
+    }
+
+    fn skip_until(&mut self, pattern: &str) {
+        match self.text[self.pos..].find(pattern) {
+            Some(offset) => self.skip(offset + pattern.len()),
+            None => self.skip(self.text.len() - self.pos),
+        }
+    }
+}
+
+impl<'a> Iterator for A2lTokens<'a> {
+    type Item = (&'a str, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let rest = &self.text[self.pos..];
+            let c = rest.chars().next()?;
+            if c.is_whitespace() {
+                self.skip(c.len_utf8());
+            } else if rest.starts_with("/*") {
+                self.skip(2);
+                self.skip_until("*/");
+            } else if rest.starts_with("//") {
+                self.skip_until("\n");
+            } else if c == '"' {
+                // Strings may contain escaped quotes and span lines
+                let mut end = rest.len();
+                let mut escaped = false;
+                for (i, ch) in rest.char_indices().skip(1) {
+                    match ch {
+                        '\\' if !escaped => escaped = true,
+                        '"' if !escaped => {
+                            end = i + 1;
+                            break;
+                        }
+                        _ => escaped = false,
+                    }
+                }
+                self.skip(end);
+            } else {
+                let len = rest
+                    .find(|ch: char| ch.is_whitespace() || ch == '"')
+                    .unwrap_or(rest.len());
+                let token = &self.text[self.pos..self.pos + len];
+                let line = self.line;
+                self.skip(len);
+                return Some((token, line));
+            }
+        }
+    }
+}
+
+/// Every `SHORT-NAME` of an AUTOSAR XML file, with the element that owns it as kind.
+pub fn extract_arxml(text: &str, file: Option<String>) -> AppResult<Vec<ExtractedLabel>> {
+    let mut labels = Vec::new();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut pos = 0;
+    let mut line = 1;
+
+    while let Some(offset) = text[pos..].find('<') {
+        line += text[pos..pos + offset].matches('\n').count();
+        pos += offset;
+        let rest = &text[pos..];
+
+        // Comments, CDATA, processing instructions and declarations carry no elements
+        let skip_to = if rest.starts_with("<!--") {
+            Some("-->")
+        } else if rest.starts_with("<![CDATA[") {
+            Some("]]>")
+        } else if rest.starts_with("<?") {
+            Some("?>")
+        } else if rest.starts_with("<!") {
+            Some(">")
+        } else {
+            None
+        };
+        if let Some(end_marker) = skip_to {
+            let len = rest
+                .find(end_marker)
+                .map(|i| i + end_marker.len())
+                .ok_or_else(|| AppError::Parse(format!("Unterminated markup at line {}", line)))?;
+            line += rest[..len].matches('\n').count();
+            pos += len;
+            continue;
+        }
+
+        let len = rest
+            .find('>')
+            .map(|i| i + 1)
+            .ok_or_else(|| AppError::Parse(format!("Unterminated tag at line {}", line)))?;
+        let tag = &rest[1..len - 1];
+        let tag_line = line;
+        line += tag.matches('\n').count();
+        pos += len;
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            // Tolerate unbalanced documents: pop up to the matching element
+            if let Some(index) = stack.iter().rposition(|open| *open == name) {
+                stack.truncate(index);
+            }
+            continue;
+        }
+        if tag.ends_with('/') {
+            continue;
+        }
+        let name = tag
+            .split(|c: char| c.is_whitespace())
+            .next()
+            .unwrap_or_default();
+
+        if name == "SHORT-NAME" {
+            let content_len = text[pos..].find("</SHORT-NAME").ok_or_else(|| {
+                AppError::Parse(format!("Unterminated SHORT-NAME at line {}", tag_line))
+            })?;
+            let content = text[pos..pos + content_len].trim();
+            if !content.is_empty() {
+                labels.push(ExtractedLabel {
+                    name: content.to_string(),
+                    kind: stack.last().copied().unwrap_or("AUTOSAR").to_string(),
+                    file: file.clone(),
+                    line: tag_line,
+                });
+            }
+            line += text[pos..pos + content_len].matches('\n').count();
+            pos += content_len;
+            continue;
+        }
+        stack.push(name);
+    }
+    Ok(labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names_and_lines(labels: &[ExtractedLabel]) -> Vec<(&str, &str, usize)> {
+        labels
+            .iter()
+            .map(|label| (label.name.as_str(), label.kind.as_str(), label.line))
+            .collect()
+    }
+
+    const A2L: &str = r#"ASAP2_VERSION 1 71
+/begin PROJECT Demo ""
+  /begin MODULE Engine "engine \"control\" /begin MEASUREMENT Quoted"
+    /* /begin MEASUREMENT Commented "block comment"
+       /end MEASUREMENT */
+    // /begin CHARACTERISTIC LineComment ""
+    /begin MEASUREMENT Eng_nSpd "engine
+      speed" UWORD CM_Spd 0 0 0 8000
+      /begin IF_DATA XCP /begin DAQ_EVENT FIXED_EVENT_LIST /end DAQ_EVENT /end IF_DATA
+    /end MEASUREMENT
+    /begin COMPU_METHOD CM_Spd "" IDENTICAL "%6.0" "rpm" /end COMPU_METHOD
+    /begin CHARACTERISTIC Eng_tOilMax_C "" VALUE 0x1000 RL 0 CM 0 200 /end CHARACTERISTIC
+    /begin AXIS_PTS
+      Eng_nSpd_Ax "" 0x2000 NO_INPUT_QUANTITY RL 0 CM 8 0 8000
+    /end AXIS_PTS
+  /end MODULE
+/end PROJECT
+"#;
+
+    #[test]
+    fn a2l_label_blocks_with_lines() {
+        let labels = extract_a2l(A2L, Some("demo.a2l".to_string()));
+        assert_eq!(
+            names_and_lines(&labels),
+            vec![
+                ("Eng_nSpd", "MEASUREMENT", 7),
+                ("Eng_tOilMax_C", "CHARACTERISTIC", 12),
+                ("Eng_nSpd_Ax", "AXIS_PTS", 14),
+            ]
+        );
+        assert!(
+            labels
+                .iter()
+                .all(|label| label.file.as_deref() == Some("demo.a2l"))
+        );
+    }
+
+    #[test]
+    fn a2l_tokens_skip_comments_and_strings() {
+        let tokens: Vec<(&str, usize)> =
+            A2lTokens::new("a /* b\n c */ \"d\\\" e\" f // g\nh").collect();
+        assert_eq!(tokens, vec![("a", 1), ("f", 2), ("h", 3)]);
+        // Unterminated comments and strings end the input
+        assert_eq!(A2lTokens::new("a /* b").count(), 1);
+        assert_eq!(A2lTokens::new("a \"b").count(), 1);
+    }
+
+    #[test]
+    fn a2l_truncated_block_yields_nothing() {
+        assert!(extract_a2l("/begin MEASUREMENT", None).is_empty());
+        assert!(extract_a2l("/begin", None).is_empty());
+    }
+
+    const ARXML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- <SHORT-NAME>Commented</SHORT-NAME> -->
+<AUTOSAR xmlns="http://autosar.org/schema/r4.0">
+  <AR-PACKAGES>
+    <AR-PACKAGE>
+      <SHORT-NAME>Eng</SHORT-NAME>
+      <ELEMENTS>
+        <APPLICATION-SW-COMPONENT-TYPE UUID="1">
+          <SHORT-NAME>
+            Eng_nSpd
+          </SHORT-NAME>
+          <DESC><L-2 L="EN"><![CDATA[<SHORT-NAME>InCdata</SHORT-NAME>]]></L-2></DESC>
+          <ADMIN-DATA/>
+        </APPLICATION-SW-COMPONENT-TYPE>
+        <PARAMETER-DATA-PROTOTYPE><SHORT-NAME>Eng_tOilMax_C</SHORT-NAME></PARAMETER-DATA-PROTOTYPE>
+      </ELEMENTS>
+    </AR-PACKAGE>
+  </AR-PACKAGES>
+</AUTOSAR>
+"#;
+
+    #[test]
+    fn arxml_short_names_with_owning_element() {
+        let labels = extract_arxml(ARXML, None).unwrap();
+        assert_eq!(
+            names_and_lines(&labels),
+            vec![
+                ("Eng", "AR-PACKAGE", 6),
+                ("Eng_nSpd", "APPLICATION-SW-COMPONENT-TYPE", 9),
+                ("Eng_tOilMax_C", "PARAMETER-DATA-PROTOTYPE", 15),
+            ]
+        );
+    }
+
+    #[test]
+    fn arxml_tolerates_unbalanced_elements() {
+        let text = "<A><B><C></B><SHORT-NAME>X</SHORT-NAME></A><SHORT-NAME>Y</SHORT-NAME>";
+        let labels = extract_arxml(text, None).unwrap();
+        assert_eq!(
+            names_and_lines(&labels),
+            vec![("X", "A", 1), ("Y", "AUTOSAR", 1)]
+        );
+    }
+
+    #[test]
+    fn arxml_reports_unterminated_markup() {
+        assert!(extract_arxml("<A>\n<!-- open", None).is_err());
+        assert!(extract_arxml("<A>\n<B", None).is_err());
+        assert!(extract_arxml("<SHORT-NAME>X", None).is_err());
+    }
+
+    #[test]
+    fn text_skips_blank_lines_and_comments() {
+        let labels = extract_text("Eng_nSpd\n\n  # note\n  Eng_tOil  \n", None);
+        assert_eq!(
+            names_and_lines(&labels),
+            vec![("Eng_nSpd", "label", 1), ("Eng_tOil", "label", 4)]
+        );
+    }
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(
+            SourceFormat::from_path(Path::new("x.A2L")),
+            SourceFormat::A2l
+        );
+        assert_eq!(
+            SourceFormat::from_path(Path::new("x.arxml")),
+            SourceFormat::Arxml
+        );
+        assert_eq!(SourceFormat::from_path(Path::new("x.hpp")), SourceFormat::C);
+        assert_eq!(
+            SourceFormat::from_path(Path::new("labels.txt")),
+            SourceFormat::Text
+        );
+        assert_eq!(
+            SourceFormat::from_path(Path::new("labels")),
+            SourceFormat::Text
+        );
+    }
+}
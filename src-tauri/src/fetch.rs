@@ -0,0 +1,323 @@
+// Keyword download with status-aware retries
+//
+// Transport errors, timeouts and the statuses 408, 429 and 5xx are retried with
+// exponential backoff, or after the delay requested by `Retry-After`. Other
+// failures, e.g. a 404 or an HTML login page served with status 200, are not
+// retried; the caller falls back to the cached keywords.
+use crate::keyword::{self, ParsedKeywords};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use reqwest::header::{CONTENT_TYPE, HeaderMap, RETRY_AFTER};
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: usize,
+    /// Delay before the first retry, doubled for every further one.
+    pub base_delay: Duration,
+    /// Upper bound for backoff and `Retry-After` delays.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn backoff(&self, retry: usize) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1) as u32);
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+#[derive(Debug)]
+pub enum FetchError {
+    /// Connection failed, timed out or the body could not be read.
+    Transport(String),
+    Status {
+        status: StatusCode,
+        retry_after: Option<Duration>,
+    },
+    /// The server answered with something other than JSON.
+    ContentType(String),
+    Parse(String),
+}
+
+impl FetchError {
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            FetchError::Transport(_) => true,
+            FetchError::Status { status, .. } => {
+                status.is_server_error()
+                    || *status == StatusCode::TOO_MANY_REQUESTS
+                    || *status == StatusCode::REQUEST_TIMEOUT
+            }
+            FetchError::ContentType(_) | FetchError::Parse(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Transport(e) => write!(f, "Request failed: {}", e),
+            FetchError::Status { status, .. } => write!(f, "Server responded with {}", status),
+            FetchError::ContentType(content_type) => {
+                write!(f, "Expected a JSON response, got '{}'", content_type)
+            }
+            FetchError::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Delay requested by a `Retry-After` header, in seconds or as HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+async fn fetch_once(client: &reqwest::Client, url: &str) -> Result<ParsedKeywords, FetchError> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| FetchError::Transport(e.to_string()))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(FetchError::Status {
+            status,
+            retry_after: retry_after(response.headers()),
+        });
+    }
+    if let Some(content_type) = response.headers().get(CONTENT_TYPE) {
+        let content_type = content_type
+            .to_str()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if !content_type.contains("json") {
+            return Err(FetchError::ContentType(content_type));
+        }
+    }
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| FetchError::Transport(format!("Failed to read response body: {}", e)))?;
+    keyword::parse_keywords(&body).map_err(FetchError::Parse)
+}
+
+/// Download and parse the keyword list, retrying according to `policy`.
+///
+/// `on_retry(retry, max_retries, error, delay)` is called before every retry.
+pub async fn fetch_keywords(
+    client: &reqwest::Client,
+    url: &str,
+    policy: &RetryPolicy,
+    mut on_retry: impl FnMut(usize, usize, &FetchError, Duration),
+) -> Result<ParsedKeywords, FetchError> {
+    let mut retry = 0;
+    loop {
+        log::debug!("API fetch attempt {}", retry);
+        let error = match fetch_once(client, url).await {
+            Ok(parsed) => {
+                log::info!("API call succeeded: {}", url);
+                return Ok(parsed);
+            }
+            Err(e) => e,
+        };
+        log::error!("API call failed: {} - {}", url, error);
+        if !error.is_retryable() || retry == policy.max_retries {
+            return Err(error);
+        }
+        retry += 1;
+        let delay = match &error {
+            FetchError::Status {
+                retry_after: Some(delay),
+                ..
+            } => (*delay).min(policy.max_delay),
+            _ => policy.backoff(retry),
+        };
+        on_retry(retry, policy.max_retries, &error, delay);
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const KEYWORDS: &str = r#"[{"abbrName":"Eng","longNameEn":"engine"}]"#;
+
+    // Serves the scripted responses in order (the last one repeats) and counts requests
+    fn mock_server(responses: Vec<String>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/ubk-keywords", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buffer) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buffer[..n]),
+                    }
+                }
+                let index = counter.fetch_add(1, Ordering::SeqCst);
+                let response = &responses[index.min(responses.len() - 1)];
+                stream.write_all(response.as_bytes()).ok();
+            }
+        });
+        (url, requests)
+    }
+
+    fn response(status: &str, headers: &[&str], body: &str) -> String {
+        let mut response = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\n", status, body.len());
+        for header in headers {
+            response.push_str(header);
+            response.push_str("\r\n");
+        }
+        response.push_str("Connection: close\r\n\r\n");
+        response.push_str(body);
+        response
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(50),
+        }
+    }
+
+    fn fetch(
+        url: &str,
+        policy: &RetryPolicy,
+    ) -> (Result<ParsedKeywords, FetchError>, Vec<Duration>) {
+        let mut delays = Vec::new();
+        let result = tauri::async_runtime::block_on(fetch_keywords(
+            &reqwest::Client::new(),
+            url,
+            policy,
+            |_, _, _, delay| delays.push(delay),
+        ));
+        (result, delays)
+    }
+
+    #[test]
+    fn retries_server_errors_and_honours_retry_after() {
+        let (url, requests) = mock_server(vec![
+            response("503 Service Unavailable", &["Retry-After: 0"], ""),
+            response("200 OK", &["Content-Type: application/json"], KEYWORDS),
+        ]);
+        let (result, delays) = fetch(&url, &fast_policy());
+        let parsed = result.unwrap();
+        assert_eq!(parsed.keywords.len(), 1);
+        assert_eq!(parsed.keywords[0].abbr_name, "Eng");
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(delays, vec![Duration::ZERO]);
+    }
+
+    #[test]
+    fn retry_after_is_capped_by_the_policy() {
+        let (url, _) = mock_server(vec![
+            response("429 Too Many Requests", &["Retry-After: 3600"], ""),
+            response("200 OK", &["Content-Type: application/json"], KEYWORDS),
+        ]);
+        let (result, delays) = fetch(&url, &fast_policy());
+        assert!(result.is_ok());
+        assert_eq!(delays, vec![Duration::from_millis(50)]);
+    }
+
+    #[test]
+    fn gives_up_after_max_retries() {
+        let (url, requests) = mock_server(vec![response("500 Internal Server Error", &[], "")]);
+        let (result, delays) = fetch(&url, &fast_policy());
+        assert!(matches!(
+            result,
+            Err(FetchError::Status { status, .. }) if status == StatusCode::INTERNAL_SERVER_ERROR
+        ));
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            delays,
+            vec![Duration::from_millis(1), Duration::from_millis(2)]
+        );
+    }
+
+    #[test]
+    fn does_not_retry_client_errors() {
+        let (url, requests) = mock_server(vec![response("404 Not Found", &[], "")]);
+        let (result, _) = fetch(&url, &fast_policy());
+        assert!(matches!(result, Err(FetchError::Status { .. })));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn rejects_html_login_page() {
+        let (url, requests) = mock_server(vec![response(
+            "200 OK",
+            &["Content-Type: text/html; charset=utf-8"],
+            "<html>Please log in</html>",
+        )]);
+        let (result, _) = fetch(&url, &fast_policy());
+        assert!(matches!(result, Err(FetchError::ContentType(_))));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn rejects_json_that_is_not_a_keyword_list() {
+        let (url, _) = mock_server(vec![response(
+            "200 OK",
+            &["Content-Type: application/json"],
+            r#"{"error":"maintenance"}"#,
+        )]);
+        let (result, _) = fetch(&url, &fast_policy());
+        assert!(matches!(result, Err(FetchError::Parse(_))));
+    }
+
+    #[test]
+    fn retries_connection_errors() {
+        // Bind and drop a listener to get a port nobody listens on
+        let url = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}/ubk-keywords", listener.local_addr().unwrap())
+        };
+        let (result, delays) = fetch(&url, &fast_policy());
+        assert!(matches!(result, Err(FetchError::Transport(_))));
+        assert_eq!(delays.len(), 2);
+    }
+
+    #[test]
+    fn parses_retry_after_dates() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+        headers.insert(RETRY_AFTER, "120".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(120)));
+        headers.insert(RETRY_AFTER, "soon".parse().unwrap());
+        assert_eq!(retry_after(&headers), None);
+    }
+}
@@ -0,0 +1,727 @@
+// Correction proposals for invalid or outdated labels
+//
+// `fix_label` rewrites the parts `validate_label` complains about: obsolete keywords
+// are replaced by their `useInsteadAbbrName`, a wrongly cased physical part is
+// corrected, repeated keywords are dropped and unknown abbreviations are swapped for
+// the closest existing ones. Every proposal lists its changes and a diff.
+use crate::keyword::Keyword;
+use crate::label::{self, LabelError, LabelPart};
+use crate::rules::RuleSet;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Combinations of part fixes kept while building proposals.
+const BEAM_WIDTH: usize = 64;
+/// Alternatives tried for one unknown abbreviation.
+const MAX_NEAR_MATCHES: usize = 3;
+
+// Cost of keeping a part as is when it is broken or outdated; fixes are cheaper
+const KEEP_BROKEN: u32 = 10;
+const KEEP_OBSOLETE: u32 = 2;
+const DROP_UNKNOWN: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FixKind {
+    /// Obsolete keyword replaced by its `useInsteadAbbrName`.
+    UseInstead,
+    Casing,
+    DuplicateRemoved,
+    /// Unknown abbreviation replaced by a similar or same-named keyword.
+    NearMatch,
+    UnknownRemoved,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelChange {
+    pub part: LabelPart,
+    pub from: String,
+    /// `None` if the part was removed.
+    pub to: Option<String>,
+    pub kind: FixKind,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffOp {
+    Equal,
+    Removed,
+    Added,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffSegment {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixProposal {
+    pub label: String,
+    /// Whether the rewritten label passes validation.
+    pub valid: bool,
+    /// Errors the rewritten label still has.
+    pub errors: Vec<LabelError>,
+    pub changes: Vec<LabelChange>,
+    /// Old label to new label, part by part.
+    pub diff: Vec<DiffSegment>,
+}
+
+// One way to write a label part; an empty text drops the part
+#[derive(Debug, Clone)]
+struct Candidate {
+    text: String,
+    change: Option<LabelChange>,
+    cost: u32,
+}
+
+#[derive(Debug)]
+struct Slot {
+    part: LabelPart,
+    original: String,
+    candidates: Vec<Candidate>,
+}
+
+impl Slot {
+    fn new(part: LabelPart, original: &str, keep_cost: u32) -> Slot {
+        Slot {
+            part,
+            original: original.to_string(),
+            candidates: vec![Candidate {
+                text: original.to_string(),
+                change: None,
+                cost: keep_cost,
+            }],
+        }
+    }
+
+    fn propose(&mut self, to: &str, kind: FixKind, reason: String, cost: u32) {
+        if to == self.original || self.candidates.iter().any(|c| c.text == to) {
+            return;
+        }
+        self.candidates.push(Candidate {
+            text: to.to_string(),
+            change: Some(LabelChange {
+                part: self.part,
+                from: self.original.clone(),
+                to: (!to.is_empty()).then(|| to.to_string()),
+                kind,
+                reason,
+            }),
+            cost,
+        });
+    }
+}
+
+/// Up to `limit` rewrites of `label`, valid ones and those with fewer changes first.
+///
+/// Labels that cannot be split into `<Id>_<pp><DescriptiveName>_<Ex>` get no proposals.
+pub fn fix_label(
+    label: &str,
+    keywords: &[Keyword],
+    rules: &RuleSet,
+    limit: usize,
+) -> Vec<FixProposal> {
+    let label = label.trim();
+    let split = label::split_label(label);
+    if split.is_invalid {
+        return Vec::new();
+    }
+
+    // Like `validate_label_with`, a rule set without a required physical part reads
+    // anything else as the first descriptive word
+    let has_physical = rules.required_parts.physical
+        || keywords
+            .iter()
+            .any(|k| k.abbr_name == split.pp && rules.is_physical(k));
+    let (mut slots, mut seen, descriptive) = if has_physical {
+        (
+            vec![physical_slot(&split.pp, keywords, rules)],
+            vec![split.pp.as_str()],
+            split.descriptive(),
+        )
+    } else {
+        (Vec::new(), Vec::new(), split.keywords.as_slice())
+    };
+    for part in descriptive {
+        let slot = if seen.contains(&part.as_str()) {
+            let mut slot = Slot::new(LabelPart::Descriptive, part, KEEP_BROKEN);
+            slot.propose(
+                "",
+                FixKind::DuplicateRemoved,
+                format!("{} is already used in this label", part),
+                1,
+            );
+            slot
+        } else {
+            descriptive_slot(part, keywords, rules)
+        };
+        seen.push(part);
+        slots.push(slot);
+    }
+    if !split.ex.is_empty() {
+        slots.push(extension_slot(&split.ex, keywords, rules));
+    }
+
+    let mut proposals = Vec::new();
+    let mut labels = HashSet::new();
+    for (cost, choice) in combinations(&slots) {
+        let changes: Vec<LabelChange> = choice
+            .iter()
+            .filter_map(|candidate| candidate.change.clone())
+            .collect();
+        if changes.is_empty() {
+            continue;
+        }
+        let fixed = assemble(&split.id, choice.iter().map(|c| c.text.as_str()), &slots);
+        if !labels.insert(fixed.clone()) {
+            continue;
+        }
+        let report = label::validate_label_with(&fixed, keywords, rules);
+        proposals.push((
+            cost,
+            FixProposal {
+                diff: diff(&split.id, &slots, &choice),
+                label: fixed,
+                valid: report.valid,
+                errors: report.errors,
+                changes,
+            },
+        ));
+    }
+    proposals.sort_by(|(a_cost, a), (b_cost, b)| {
+        b.valid
+            .cmp(&a.valid)
+            .then_with(|| a.errors.len().cmp(&b.errors.len()))
+            .then_with(|| a_cost.cmp(b_cost))
+            .then_with(|| a.changes.len().cmp(&b.changes.len()))
+    });
+    proposals
+        .into_iter()
+        .take(limit)
+        .map(|(_, proposal)| proposal)
+        .collect()
+}
+
+fn physical_slot(pp: &str, keywords: &[Keyword], rules: &RuleSet) -> Slot {
+    let is_physical = |k: &Keyword| rules.is_physical(k);
+    let exact: Vec<&Keyword> = keywords
+        .iter()
+        .filter(|k| k.abbr_name == pp && is_physical(k))
+        .collect();
+    if !exact.is_empty() {
+        return replace_obsolete(LabelPart::Physical, pp, &exact, keywords);
+    }
+
+    let mut slot = Slot::new(LabelPart::Physical, pp, KEEP_BROKEN);
+    for keyword in keywords
+        .iter()
+        .filter(|k| is_physical(k) && !k.is_obsolete())
+    {
+        if keyword.abbr_name.eq_ignore_ascii_case(pp) {
+            slot.propose(
+                &keyword.abbr_name,
+                FixKind::Casing,
+                format!(
+                    "The physical part is written {} ({})",
+                    keyword.abbr_name,
+                    keyword.long_name_en.as_deref().unwrap_or("-")
+                ),
+                1,
+            );
+        }
+    }
+    // "tcoolt": the physical part swallowed a descriptive keyword written in lowercase
+    for (index, _) in pp.char_indices().skip(1) {
+        let (prefix, rest) = pp.split_at(index);
+        let has_prefix = keywords
+            .iter()
+            .any(|k| k.abbr_name == prefix && is_physical(k) && !k.is_obsolete());
+        let descriptive = keywords.iter().find(|k| {
+            k.abbr_name.eq_ignore_ascii_case(rest) && rules.is_descriptive(k) && !k.is_obsolete()
+        });
+        if has_prefix && let Some(descriptive) = descriptive {
+            slot.propose(
+                &format!("{}{}", prefix, descriptive.abbr_name),
+                FixKind::Casing,
+                format!(
+                    "{} is the physical part, {} starts the descriptive name",
+                    prefix, descriptive.abbr_name
+                ),
+                1,
+            );
+        }
+    }
+    for (keyword, distance) in near_matches(pp, keywords, is_physical) {
+        slot.propose(
+            &keyword.abbr_name,
+            FixKind::NearMatch,
+            format!(
+                "{} is not a physical keyword, {} ({}) is similar",
+                pp,
+                keyword.abbr_name,
+                keyword.long_name_en.as_deref().unwrap_or("-")
+            ),
+            2 + distance as u32,
+        );
+    }
+    slot
+}
+
+fn descriptive_slot(part: &str, keywords: &[Keyword], rules: &RuleSet) -> Slot {
+    let is_descriptive = |k: &Keyword| rules.is_descriptive(k);
+    let exact: Vec<&Keyword> = keywords
+        .iter()
+        .filter(|k| k.abbr_name == part && !rules.is_extension(k))
+        .collect();
+    if !exact.is_empty() {
+        return replace_obsolete(LabelPart::Descriptive, part, &exact, keywords);
+    }
+
+    let mut slot = Slot::new(LabelPart::Descriptive, part, KEEP_BROKEN);
+    for keyword in keywords
+        .iter()
+        .filter(|k| is_descriptive(k) && !k.is_obsolete())
+    {
+        let named = [&keyword.long_name_en, &keyword.long_name_de]
+            .into_iter()
+            .flatten()
+            .any(|name| name.eq_ignore_ascii_case(part));
+        if keyword.abbr_name.eq_ignore_ascii_case(part) {
+            slot.propose(
+                &keyword.abbr_name,
+                FixKind::Casing,
+                format!("The keyword is written {}", keyword.abbr_name),
+                1,
+            );
+        } else if named {
+            slot.propose(
+                &keyword.abbr_name,
+                FixKind::NearMatch,
+                format!("{} is the abbreviation of {}", keyword.abbr_name, part),
+                2,
+            );
+        }
+    }
+    for (keyword, distance) in near_matches(part, keywords, is_descriptive) {
+        slot.propose(
+            &keyword.abbr_name,
+            FixKind::NearMatch,
+            format!(
+                "{} is not available, {} ({}) is similar",
+                part,
+                keyword.abbr_name,
+                keyword.long_name_en.as_deref().unwrap_or("-")
+            ),
+            2 + distance as u32,
+        );
+    }
+    slot.propose(
+        "",
+        FixKind::UnknownRemoved,
+        format!("{} is not available", part),
+        DROP_UNKNOWN,
+    );
+    slot
+}
+
+fn extension_slot(ex: &str, keywords: &[Keyword], rules: &RuleSet) -> Slot {
+    let exact: Vec<&Keyword> = keywords
+        .iter()
+        .filter(|k| k.abbr_name == ex && rules.is_extension(k))
+        .collect();
+    if !exact.is_empty() {
+        return replace_obsolete(LabelPart::Extension, ex, &exact, keywords);
+    }
+
+    let mut slot = Slot::new(LabelPart::Extension, ex, KEEP_BROKEN);
+    for (keyword, distance) in near_matches(ex, keywords, |k| rules.is_extension(k)) {
+        let kind = if keyword.abbr_name.eq_ignore_ascii_case(ex) {
+            FixKind::Casing
+        } else {
+            FixKind::NearMatch
+        };
+        slot.propose(
+            &keyword.abbr_name,
+            kind,
+            format!(
+                "{} is not an extension, {} ({}) is similar",
+                ex,
+                keyword.abbr_name,
+                keyword.long_name_en.as_deref().unwrap_or("-")
+            ),
+            1 + distance as u32,
+        );
+    }
+    slot.propose(
+        "",
+        FixKind::UnknownRemoved,
+        format!("{} is not an extension", ex),
+        DROP_UNKNOWN,
+    );
+    slot
+}
+
+// Keep a known part, or replace it if every entry with this abbreviation is obsolete
+fn replace_obsolete(
+    part: LabelPart,
+    abbr: &str,
+    entries: &[&Keyword],
+    keywords: &[Keyword],
+) -> Slot {
+    if entries.iter().any(|k| !k.is_obsolete()) {
+        return Slot::new(part, abbr, 0);
+    }
+    let mut slot = Slot::new(part, abbr, KEEP_OBSOLETE);
+    for entry in entries {
+        let Some(replacement) = entry.use_instead_abbr_name.as_deref() else {
+            continue;
+        };
+        let long_name = keywords
+            .iter()
+            .find(|k| k.abbr_name == replacement && !k.is_obsolete())
+            .and_then(|k| k.long_name_en.as_deref())
+            .unwrap_or("-");
+        slot.propose(
+            replacement,
+            FixKind::UseInstead,
+            format!(
+                "{} is obsolete, use {} ({}) instead",
+                abbr, replacement, long_name
+            ),
+            1,
+        );
+    }
+    slot
+}
+
+// Keywords whose abbreviation is within a few edits of `abbr`, closest first.
+// Short abbreviations are one edit away from too many others, they only match
+// ignoring case.
+fn near_matches<'a>(
+    abbr: &str,
+    keywords: &'a [Keyword],
+    accept: impl Fn(&Keyword) -> bool,
+) -> Vec<(&'a Keyword, usize)> {
+    let wanted = abbr.to_lowercase();
+    let length = abbr.chars().count();
+    let max_distance = if length < 3 { 0 } else { (length / 3).max(1) };
+    let mut matches: Vec<(&Keyword, usize)> = keywords
+        .iter()
+        .filter(|k| accept(k) && !k.is_obsolete())
+        .map(|k| (k, edit_distance(&wanted, &k.abbr_name.to_lowercase())))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .collect();
+    matches.sort_by(|(a, a_distance), (b, b_distance)| {
+        a_distance
+            .cmp(b_distance)
+            .then_with(|| a.abbr_name.len().cmp(&b.abbr_name.len()))
+            .then_with(|| a.abbr_name.cmp(&b.abbr_name))
+    });
+    let mut seen = HashSet::new();
+    matches.retain(|(k, _)| seen.insert(k.abbr_name.as_str()));
+    matches.truncate(MAX_NEAR_MATCHES);
+    matches
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for j in 1..=b.len() {
+            let above = row[j];
+            row[j] = (diagonal + usize::from(ca != b[j - 1]))
+                .min(above + 1)
+                .min(row[j - 1] + 1);
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+// Cheapest choices of one candidate per slot, cheapest first
+fn combinations(slots: &[Slot]) -> Vec<(u32, Vec<&Candidate>)> {
+    let mut beam: Vec<(u32, Vec<&Candidate>)> = vec![(0, Vec::new())];
+    for slot in slots {
+        let mut next = Vec::new();
+        for (cost, choice) in &beam {
+            for candidate in &slot.candidates {
+                let mut choice = choice.clone();
+                choice.push(candidate);
+                next.push((cost + candidate.cost, choice));
+            }
+        }
+        next.sort_by_key(|(cost, _)| *cost);
+        next.truncate(BEAM_WIDTH);
+        beam = next;
+    }
+    beam
+}
+
+fn assemble<'a>(id: &str, texts: impl Iterator<Item = &'a str>, slots: &[Slot]) -> String {
+    let mut label = format!("{}_", id);
+    for (text, slot) in texts.zip(slots) {
+        if slot.part == LabelPart::Extension && !text.is_empty() {
+            label.push('_');
+        }
+        label.push_str(text);
+    }
+    label
+}
+
+fn diff(id: &str, slots: &[Slot], choice: &[&Candidate]) -> Vec<DiffSegment> {
+    let mut segments: Vec<DiffSegment> = Vec::new();
+    let mut push = |op: DiffOp, text: String| {
+        if text.is_empty() {
+            return;
+        }
+        match segments.last_mut() {
+            Some(last) if last.op == op => last.text.push_str(&text),
+            _ => segments.push(DiffSegment { op, text }),
+        }
+    };
+    push(DiffOp::Equal, format!("{}_", id));
+    for (slot, candidate) in slots.iter().zip(choice) {
+        let separator = if slot.part == LabelPart::Extension {
+            "_"
+        } else {
+            ""
+        };
+        let render = |text: &str| {
+            if text.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", separator, text)
+            }
+        };
+        if candidate.text == slot.original {
+            push(DiffOp::Equal, render(&slot.original));
+        } else {
+            push(DiffOp::Removed, render(&slot.original));
+            push(DiffOp::Added, render(&candidate.text));
+        }
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyword::test_keyword;
+    use crate::rules::RequiredParts;
+
+    fn keywords() -> Vec<Keyword> {
+        vec![
+            test_keyword("t", "Physical", "temperature"),
+            test_keyword("n", "Physical", "speed"),
+            test_keyword("Eng", "Descriptive", "engine"),
+            test_keyword("Oil", "Descriptive", "oil"),
+            test_keyword("Max", "Descriptive", "maximum"),
+            test_keyword("Olt", "Descriptive", "oil (old)").replaced_by("Oil"),
+            test_keyword("C", "Extension", "calibration"),
+        ]
+    }
+
+    fn fix(label: &str) -> Vec<FixProposal> {
+        fix_label(label, &keywords(), &RuleSet::default(), 5)
+    }
+
+    fn segment(op: DiffOp, text: &str) -> DiffSegment {
+        DiffSegment {
+            op,
+            text: text.to_string(),
+        }
+    }
+
+    fn kinds(proposal: &FixProposal) -> Vec<FixKind> {
+        proposal.changes.iter().map(|change| change.kind).collect()
+    }
+
+    #[test]
+    fn replaces_obsolete_keywords_with_use_instead() {
+        let proposals = fix("RB_tOltMax_C");
+        let best = &proposals[0];
+        assert_eq!(best.label, "RB_tOilMax_C");
+        assert!(best.valid);
+        assert_eq!(
+            best.changes,
+            vec![LabelChange {
+                part: LabelPart::Descriptive,
+                from: "Olt".to_string(),
+                to: Some("Oil".to_string()),
+                kind: FixKind::UseInstead,
+                reason: "Olt is obsolete, use Oil (oil) instead".to_string(),
+            }]
+        );
+        assert_eq!(
+            best.diff,
+            vec![
+                segment(DiffOp::Equal, "RB_t"),
+                segment(DiffOp::Removed, "Olt"),
+                segment(DiffOp::Added, "Oil"),
+                segment(DiffOp::Equal, "Max_C"),
+            ]
+        );
+    }
+
+    #[test]
+    fn corrects_the_casing_of_the_physical_part() {
+        let best = &fix("RB_TEngMax")[0];
+        assert_eq!(best.label, "RB_tEngMax");
+        assert_eq!(kinds(best), vec![FixKind::Casing]);
+        assert_eq!(best.changes[0].part, LabelPart::Physical);
+        assert_eq!(
+            best.diff,
+            vec![
+                segment(DiffOp::Equal, "RB_"),
+                segment(DiffOp::Removed, "T"),
+                segment(DiffOp::Added, "t"),
+                segment(DiffOp::Equal, "EngMax"),
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_a_descriptive_keyword_off_the_physical_part() {
+        let best = &fix("RB_toilMax")[0];
+        assert_eq!(best.label, "RB_tOilMax");
+        assert!(best.valid);
+        assert_eq!(
+            best.changes[0].reason,
+            "t is the physical part, Oil starts the descriptive name"
+        );
+    }
+
+    #[test]
+    fn drops_repeated_keywords() {
+        let best = &fix("RB_tEngEngMax")[0];
+        assert_eq!(best.label, "RB_tEngMax");
+        assert_eq!(kinds(best), vec![FixKind::DuplicateRemoved]);
+        assert_eq!(best.changes[0].to, None);
+        assert_eq!(
+            best.diff,
+            vec![
+                segment(DiffOp::Equal, "RB_tEng"),
+                segment(DiffOp::Removed, "Eng"),
+                segment(DiffOp::Equal, "Max"),
+            ]
+        );
+    }
+
+    #[test]
+    fn replaces_unknown_abbreviations_with_near_matches() {
+        let proposals = fix("RB_tEngMxx");
+        assert_eq!(proposals[0].label, "RB_tEngMax");
+        assert_eq!(kinds(&proposals[0]), vec![FixKind::NearMatch]);
+        assert_eq!(
+            proposals[0].changes[0].reason,
+            "Mxx is not available, Max (maximum) is similar"
+        );
+        // Dropping the unknown part costs more than a close keyword
+        let dropped = proposals.iter().position(|p| p.label == "RB_tEng").unwrap();
+        assert!(dropped > 0);
+        assert_eq!(kinds(&proposals[dropped]), vec![FixKind::UnknownRemoved]);
+
+        // A long name written out is replaced by its abbreviation
+        let best = &fix("RB_tEngineMax")[0];
+        assert_eq!(best.label, "RB_tEngMax");
+        assert_eq!(best.changes[0].reason, "Eng is the abbreviation of Engine");
+    }
+
+    #[test]
+    fn short_unknown_abbreviations_only_match_ignoring_case() {
+        let labels: Vec<String> = fix("RB_tEngMx").into_iter().map(|p| p.label).collect();
+        assert_eq!(labels, vec!["RB_tEng"]);
+        let best = &fix("RB_tEngMax_c")[0];
+        assert_eq!(best.label, "RB_tEngMax_C");
+        assert_eq!(kinds(best), vec![FixKind::Casing]);
+        assert_eq!(best.changes[0].part, LabelPart::Extension);
+        assert_eq!(
+            best.diff,
+            vec![
+                segment(DiffOp::Equal, "RB_tEngMax"),
+                segment(DiffOp::Removed, "_c"),
+                segment(DiffOp::Added, "_C"),
+            ]
+        );
+    }
+
+    #[test]
+    fn combines_fixes_of_several_parts() {
+        let best = &fix("RB_TOltEngEng_c")[0];
+        assert_eq!(best.label, "RB_tOilEng_C");
+        assert!(best.valid);
+        assert_eq!(
+            kinds(best),
+            vec![
+                FixKind::Casing,
+                FixKind::UseInstead,
+                FixKind::DuplicateRemoved,
+                FixKind::Casing,
+            ]
+        );
+        assert_eq!(
+            best.diff,
+            vec![
+                segment(DiffOp::Equal, "RB_"),
+                segment(DiffOp::Removed, "T"),
+                segment(DiffOp::Added, "t"),
+                segment(DiffOp::Removed, "Olt"),
+                segment(DiffOp::Added, "Oil"),
+                segment(DiffOp::Equal, "Eng"),
+                segment(DiffOp::Removed, "Eng_c"),
+                segment(DiffOp::Added, "_C"),
+            ]
+        );
+    }
+
+    #[test]
+    fn valid_proposals_come_first() {
+        let proposals = fix("RB_tOltMxx");
+        assert!(proposals.len() > 1);
+        let first_invalid = proposals.iter().position(|p| !p.valid);
+        if let Some(first_invalid) = first_invalid {
+            assert!(proposals[first_invalid..].iter().all(|p| !p.valid));
+        }
+        assert!(proposals.iter().all(|p| !p.changes.is_empty()));
+        assert_eq!(
+            fix_label("RB_tOltMxx", &keywords(), &RuleSet::default(), 1).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn no_proposals_for_valid_or_unsplittable_labels() {
+        assert!(fix("RB_tEngMax_C").is_empty());
+        assert!(fix("no label").is_empty());
+        assert!(fix("RB__tEng").is_empty());
+    }
+
+    #[test]
+    fn optional_physical_part_is_read_as_descriptive() {
+        let rules = RuleSet {
+            required_parts: RequiredParts {
+                physical: false,
+                ..Default::default()
+            },
+            ..RuleSet::default()
+        };
+        assert!(fix_label("RB_EngMax", &keywords(), &rules, 5).is_empty());
+        let best = &fix_label("RB_EngMxx", &keywords(), &rules, 5)[0];
+        assert_eq!(best.label, "RB_EngMax");
+        assert_eq!(best.changes[0].part, LabelPart::Descriptive);
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("max", "max"), 0);
+        assert_eq!(edit_distance("mxx", "max"), 1);
+        assert_eq!(edit_distance("eng", "engine"), 3);
+        assert_eq!(edit_distance("", "oil"), 3);
+    }
+}
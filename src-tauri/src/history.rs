@@ -0,0 +1,696 @@
+// Keyword database history
+//
+//   history/index.json          snapshots, oldest first: id, server version, fetch time, count
+//   history/<sha256>.json       keyword list of a snapshot
+//
+// Every keyword set stored in the cache is kept as a dated snapshot. Snapshot files are
+// named by the SHA-256 of their content, so fetching the same data again adds nothing.
+// Two snapshots are compared keyword by keyword, matched by `ubkId`, to show reviewers
+// which abbreviations were added, deprecated or removed and which had their life cycle
+// state, replacement or names changed.
+use crate::cache;
+use crate::error::{AppError, AppResult};
+use crate::keyword::{self, Keyword};
+use crate::sync;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const HISTORY_DIR: &str = "history";
+const INDEX_FILE: &str = "index.json";
+/// The oldest snapshots are dropped beyond this number.
+const MAX_SNAPSHOTS: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    /// SHA-256 of the keyword list.
+    pub id: String,
+    /// `updatedDate` the versions endpoint reported for this download.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_date: Option<String>,
+    pub fetched_at: String,
+    pub count: usize,
+}
+
+impl Snapshot {
+    /// Name for headings, e.g. `DB: 13.06.2025`.
+    pub fn version(&self) -> String {
+        match &self.updated_date {
+            Some(updated_date) => sync::format_version_date(updated_date),
+            None => format!(
+                "fetched {}",
+                self.fetched_at.get(..10).unwrap_or(&self.fetched_at)
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeKind {
+    Added,
+    /// The life cycle state changed from valid to obsolete or removed.
+    Deprecated,
+    Changed,
+    /// The keyword is no longer in the feed.
+    Removed,
+}
+
+impl ChangeKind {
+    fn name(self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Deprecated => "deprecated",
+            ChangeKind::Changed => "changed",
+            ChangeKind::Removed => "removed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    /// Field name as in the feed, e.g. `lifeCycleState`.
+    pub field: &'static str,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordChange {
+    pub kind: ChangeKind,
+    pub abbr_name: String,
+    pub long_name_en: Option<String>,
+    /// State in the newer snapshot, in the older one for removed keywords.
+    pub life_cycle_state: Option<String>,
+    /// Changed fields, empty for added and removed keywords.
+    pub fields: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSummary {
+    pub added: usize,
+    pub deprecated: usize,
+    pub changed: usize,
+    pub removed: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordDiff {
+    pub from: Snapshot,
+    pub to: Snapshot,
+    pub summary: ChangeSummary,
+    /// Sorted by kind, then abbreviation.
+    pub changes: Vec<KeywordChange>,
+}
+
+fn history_dir(dir: &Path) -> PathBuf {
+    dir.join(HISTORY_DIR)
+}
+
+fn snapshot_path(dir: &Path, id: &str) -> PathBuf {
+    history_dir(dir).join(format!("{}.json", id))
+}
+
+/// The snapshots in the app data directory `dir`, oldest first.
+pub fn list(dir: &Path) -> AppResult<Vec<Snapshot>> {
+    let path = history_dir(dir).join(INDEX_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let json = fs::read_to_string(&path)
+        .map_err(|e| AppError::CacheIo(format!("Failed to read {}: {}", path.display(), e)))?;
+    serde_json::from_str(&json)
+        .map_err(|e| AppError::Parse(format!("Failed to parse {}: {}", path.display(), e)))
+}
+
+fn write_index(dir: &Path, snapshots: &[Snapshot]) -> AppResult<()> {
+    let json = serde_json::to_string_pretty(snapshots)
+        .map_err(|e| AppError::Parse(format!("Failed to serialize keyword history: {}", e)))?;
+    cache::write_atomic(&history_dir(dir).join(INDEX_FILE), json.as_bytes())
+}
+
+/// Keep the cached keywords.json as a snapshot unless the latest one has the same content.
+///
+/// Returns the new snapshot, `None` when the keywords did not change.
+pub fn record(dir: &Path) -> AppResult<Option<Snapshot>> {
+    let path = cache::keywords_path(dir);
+    let bytes = fs::read(&path)
+        .map_err(|e| AppError::CacheIo(format!("Failed to read {}: {}", path.display(), e)))?;
+    let id = cache::sha256_hex(&bytes);
+    let mut snapshots = list(dir)?;
+    if snapshots.last().is_some_and(|latest| latest.id == id) {
+        return Ok(None);
+    }
+
+    let manifest = cache::manifest(dir).filter(|manifest| manifest.sha256 == id);
+    let count = match &manifest {
+        Some(manifest) => manifest.count,
+        None => {
+            let parsed = keyword::parse_keywords(&bytes).map_err(AppError::Parse)?;
+            parsed.keywords.len() + parsed.issues.len()
+        }
+    };
+    let fetched_at = manifest
+        .as_ref()
+        .map(|manifest| manifest.fetched_at.clone())
+        .filter(|fetched_at| !fetched_at.is_empty())
+        .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
+    let snapshot = Snapshot {
+        id,
+        updated_date: manifest.and_then(|manifest| manifest.updated_date),
+        fetched_at,
+        count,
+    };
+
+    let history = history_dir(dir);
+    fs::create_dir_all(&history)
+        .map_err(|e| AppError::CacheIo(format!("Failed to create {}: {}", history.display(), e)))?;
+    // Data that changed back to an earlier version reuses its file
+    let file = snapshot_path(dir, &snapshot.id);
+    if !file.exists() {
+        cache::write_atomic(&file, &bytes)?;
+    }
+    snapshots.push(snapshot.clone());
+    let dropped: Vec<Snapshot> = snapshots
+        .drain(..snapshots.len().saturating_sub(MAX_SNAPSHOTS))
+        .collect();
+    write_index(dir, &snapshots)?;
+    for old in dropped {
+        if !snapshots.iter().any(|snapshot| snapshot.id == old.id) {
+            fs::remove_file(snapshot_path(dir, &old.id)).ok();
+        }
+    }
+    log::info!(
+        "Kept keyword snapshot {} ({}, {} entries)",
+        snapshot.id,
+        snapshot.version(),
+        snapshot.count
+    );
+    Ok(Some(snapshot))
+}
+
+fn load(dir: &Path, id: &str) -> AppResult<Vec<Keyword>> {
+    let path = snapshot_path(dir, id);
+    let bytes = fs::read(&path)
+        .map_err(|e| AppError::CacheIo(format!("Failed to read {}: {}", path.display(), e)))?;
+    let parsed = keyword::parse_keywords(&bytes)
+        .map_err(|e| AppError::Parse(format!("{}: {}", path.display(), e)))?;
+    Ok(parsed.keywords)
+}
+
+/// Compare the snapshots `from` and `to`, by default the latest one and the one before it.
+///
+/// Returns `None` while there are not two snapshots to compare.
+pub fn diff(dir: &Path, from: Option<&str>, to: Option<&str>) -> AppResult<Option<KeywordDiff>> {
+    let snapshots = list(dir)?;
+    let find = |id: &str| {
+        snapshots
+            .iter()
+            .rposition(|snapshot| snapshot.id == id)
+            .ok_or_else(|| AppError::Validation(format!("Unknown keyword snapshot {}", id)))
+    };
+    let to = match to {
+        Some(id) => find(id)?,
+        None if snapshots.len() < 2 => return Ok(None),
+        None => snapshots.len() - 1,
+    };
+    let from = match from {
+        Some(id) => find(id)?,
+        None if to == 0 => return Ok(None),
+        None => to - 1,
+    };
+    let (from, to) = (snapshots[from].clone(), snapshots[to].clone());
+    let (summary, changes) = compare(&load(dir, &from.id)?, &load(dir, &to.id)?);
+    Ok(Some(KeywordDiff {
+        from,
+        to,
+        summary,
+        changes,
+    }))
+}
+
+/// Identity of a keyword across snapshots: its `ubkId`, or short and abbreviated name
+/// for entries without one.
+fn key(keyword: &Keyword) -> String {
+    match keyword.extra.get("ubkId") {
+        Some(serde_json::Value::String(id)) => id.clone(),
+        Some(serde_json::Value::Number(id)) => id.to_string(),
+        _ => format!(
+            "{}/{}",
+            keyword.short_name.as_deref().unwrap_or_default(),
+            keyword.abbr_name
+        ),
+    }
+}
+
+/// Fields whose changes are reported, descriptions are left out as they change often.
+fn tracked_fields(keyword: &Keyword) -> [(&'static str, Option<String>); 7] {
+    let classifications =
+        (!keyword.rb_classifications.is_empty()).then(|| keyword.rb_classifications.join(", "));
+    [
+        ("abbrName", Some(keyword.abbr_name.clone())),
+        ("longNameEn", keyword.long_name_en.clone()),
+        ("lifeCycleState", keyword.life_cycle_state.clone()),
+        ("state", keyword.state.clone()),
+        ("useInstead", keyword.use_instead.clone()),
+        ("useInsteadAbbrName", keyword.use_instead_abbr_name.clone()),
+        ("rbClassifications", classifications),
+    ]
+}
+
+fn is_valid(life_cycle_state: Option<&str>) -> bool {
+    life_cycle_state.is_none_or(|state| state.eq_ignore_ascii_case("valid"))
+}
+
+fn change(kind: ChangeKind, keyword: &Keyword, fields: Vec<FieldChange>) -> KeywordChange {
+    KeywordChange {
+        kind,
+        abbr_name: keyword.abbr_name.clone(),
+        long_name_en: keyword.long_name_en.clone(),
+        life_cycle_state: keyword.life_cycle_state.clone(),
+        fields,
+    }
+}
+
+/// Keywords by `key`. Entries sharing a key are numbered in feed order, `key#2` and
+/// so on, so each of them is still compared instead of the later one replacing the first.
+fn keyed(keywords: &[Keyword]) -> BTreeMap<String, &Keyword> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut keyed = BTreeMap::new();
+    for keyword in keywords {
+        let key = key(keyword);
+        let count = seen.entry(key.clone()).or_default();
+        *count += 1;
+        if *count == 1 {
+            keyed.insert(key, keyword);
+        } else {
+            log::warn!(
+                "Keyword {} appears {} times, entries are matched in feed order",
+                key,
+                count
+            );
+            keyed.insert(format!("{}#{}", key, count), keyword);
+        }
+    }
+    keyed
+}
+
+fn compare(old: &[Keyword], new: &[Keyword]) -> (ChangeSummary, Vec<KeywordChange>) {
+    let old = keyed(old);
+    let new = keyed(new);
+    let mut changes = Vec::new();
+    for (key, keyword) in &new {
+        let Some(previous) = old.get(key) else {
+            changes.push(change(ChangeKind::Added, keyword, Vec::new()));
+            continue;
+        };
+        let fields: Vec<FieldChange> = tracked_fields(previous)
+            .into_iter()
+            .zip(tracked_fields(keyword))
+            .filter(|((_, old), (_, new))| old != new)
+            .map(|((field, old), (_, new))| FieldChange { field, old, new })
+            .collect();
+        if fields.is_empty() {
+            continue;
+        }
+        let kind = if is_valid(previous.life_cycle_state.as_deref())
+            && !is_valid(keyword.life_cycle_state.as_deref())
+        {
+            ChangeKind::Deprecated
+        } else {
+            ChangeKind::Changed
+        };
+        changes.push(change(kind, keyword, fields));
+    }
+    for (key, keyword) in &old {
+        if !new.contains_key(key) {
+            changes.push(change(ChangeKind::Removed, keyword, Vec::new()));
+        }
+    }
+    changes.sort_by_cached_key(|change| (change.kind, change.abbr_name.to_lowercase()));
+
+    let mut summary = ChangeSummary::default();
+    for change in &changes {
+        *match change.kind {
+            ChangeKind::Added => &mut summary.added,
+            ChangeKind::Deprecated => &mut summary.deprecated,
+            ChangeKind::Changed => &mut summary.changed,
+            ChangeKind::Removed => &mut summary.removed,
+        } += 1;
+    }
+    (summary, changes)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangelogFormat {
+    Markdown,
+    Csv,
+}
+
+impl ChangelogFormat {
+    pub fn parse(name: &str) -> Result<ChangelogFormat, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(ChangelogFormat::Markdown),
+            "csv" => Ok(ChangelogFormat::Csv),
+            _ => Err(format!(
+                "Unknown changelog format '{}', expected markdown or csv",
+                name
+            )),
+        }
+    }
+
+    /// File extension used when saving the changelog.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ChangelogFormat::Markdown => "md",
+            ChangelogFormat::Csv => "csv",
+        }
+    }
+}
+
+/// Render `diff` as a Markdown changelog or as CSV with a row per changed field.
+pub fn render(diff: &KeywordDiff, format: ChangelogFormat) -> String {
+    match format {
+        ChangelogFormat::Markdown => markdown(diff),
+        ChangelogFormat::Csv => csv(diff),
+    }
+}
+
+fn field_changes(fields: &[FieldChange]) -> String {
+    fields
+        .iter()
+        .map(|change| {
+            format!(
+                "{}: {} → {}",
+                change.field,
+                change.old.as_deref().unwrap_or("–"),
+                change.new.as_deref().unwrap_or("–")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+// Pipes end a table cell and line breaks the row
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+fn markdown(diff: &KeywordDiff) -> String {
+    let summary = &diff.summary;
+    let mut out = format!(
+        "# Keyword changes from {} to {}\n\n{} added, {} deprecated, {} changed, {} removed\n",
+        diff.from.version(),
+        diff.to.version(),
+        summary.added,
+        summary.deprecated,
+        summary.changed,
+        summary.removed
+    );
+    for (kind, title) in [
+        (ChangeKind::Added, "Added"),
+        (ChangeKind::Deprecated, "Deprecated"),
+        (ChangeKind::Changed, "Changed"),
+        (ChangeKind::Removed, "Removed"),
+    ] {
+        let changes: Vec<&KeywordChange> = diff
+            .changes
+            .iter()
+            .filter(|change| change.kind == kind)
+            .collect();
+        if changes.is_empty() {
+            continue;
+        }
+        // Added and removed keywords have no field changes to list
+        let with_fields = matches!(kind, ChangeKind::Deprecated | ChangeKind::Changed);
+        let _ = write!(
+            out,
+            "\n## {}\n\n| Abbreviation | Long name | Life cycle state |",
+            title
+        );
+        out.push_str(if with_fields {
+            " Changes |\n| --- | --- | --- | --- |\n"
+        } else {
+            "\n| --- | --- | --- |\n"
+        });
+        for change in changes {
+            let _ = write!(
+                out,
+                "| {} | {} | {} |",
+                markdown_cell(&change.abbr_name),
+                markdown_cell(change.long_name_en.as_deref().unwrap_or_default()),
+                markdown_cell(change.life_cycle_state.as_deref().unwrap_or_default())
+            );
+            if with_fields {
+                let _ = write!(out, " {} |", markdown_cell(&field_changes(&change.fields)));
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+// RFC 4180: fields with separators, quotes or line breaks are quoted, quotes doubled
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn csv(diff: &KeywordDiff) -> String {
+    let mut out = String::from("change,abbrName,longNameEn,lifeCycleState,field,old,new\r\n");
+    let mut row = |change: &KeywordChange, field: Option<&FieldChange>| {
+        let cells = [
+            change.kind.name(),
+            change.abbr_name.as_str(),
+            change.long_name_en.as_deref().unwrap_or_default(),
+            change.life_cycle_state.as_deref().unwrap_or_default(),
+            field.map(|field| field.field).unwrap_or_default(),
+            field
+                .and_then(|field| field.old.as_deref())
+                .unwrap_or_default(),
+            field
+                .and_then(|field| field.new.as_deref())
+                .unwrap_or_default(),
+        ];
+        let cells: Vec<String> = cells.iter().map(|cell| csv_field(cell)).collect();
+        out.push_str(&cells.join(","));
+        out.push_str("\r\n");
+    };
+    for change in &diff.changes {
+        if change.fields.is_empty() {
+            row(change, None);
+        }
+        for field in &change.fields {
+            row(change, Some(field));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn keywords(value: serde_json::Value) -> Vec<Keyword> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn snapshot(updated_date: &str) -> Snapshot {
+        Snapshot {
+            id: updated_date.to_string(),
+            updated_date: Some(updated_date.to_string()),
+            fetched_at: updated_date.to_string(),
+            count: 0,
+        }
+    }
+
+    fn diff(old: serde_json::Value, new: serde_json::Value) -> KeywordDiff {
+        let (summary, changes) = compare(&keywords(old), &keywords(new));
+        KeywordDiff {
+            from: snapshot("2025-06-13T06:52:11Z"),
+            to: snapshot("2025-07-01T08:00:00Z"),
+            summary,
+            changes,
+        }
+    }
+
+    fn kinds(diff: &KeywordDiff) -> Vec<(ChangeKind, &str)> {
+        diff.changes
+            .iter()
+            .map(|change| (change.kind, change.abbr_name.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn reports_added_deprecated_changed_and_removed_keywords() {
+        let diff = diff(
+            json!([
+                {"ubkId": 1, "abbrName": "Eng", "longNameEn": "Engine", "lifeCycleState": "valid"},
+                {"ubkId": 2, "abbrName": "Olt", "longNameEn": "Oil", "lifeCycleState": "valid"},
+                {"ubkId": 3, "abbrName": "Temp", "longNameEn": "Temperature"},
+                {"ubkId": 4, "abbrName": "Max", "longNameEn": "Maximum"}
+            ]),
+            json!([
+                {"ubkId": 1, "abbrName": "Eng", "longNameEn": "Engine", "lifeCycleState": "valid"},
+                {"ubkId": 2, "abbrName": "Olt", "longNameEn": "Oil", "lifeCycleState": "obsolete",
+                 "useInsteadAbbrName": "Oil"},
+                {"ubkId": 3, "abbrName": "Temp", "longNameEn": "Temperature value"},
+                {"ubkId": 5, "abbrName": "Oil", "longNameEn": "Oil"}
+            ]),
+        );
+        assert_eq!(
+            kinds(&diff),
+            [
+                (ChangeKind::Added, "Oil"),
+                (ChangeKind::Deprecated, "Olt"),
+                (ChangeKind::Changed, "Temp"),
+                (ChangeKind::Removed, "Max"),
+            ]
+        );
+        assert_eq!(
+            diff.summary,
+            ChangeSummary {
+                added: 1,
+                deprecated: 1,
+                changed: 1,
+                removed: 1
+            }
+        );
+        assert_eq!(
+            diff.changes[1].fields,
+            [
+                FieldChange {
+                    field: "lifeCycleState",
+                    old: Some("valid".to_string()),
+                    new: Some("obsolete".to_string()),
+                },
+                FieldChange {
+                    field: "useInsteadAbbrName",
+                    old: None,
+                    new: Some("Oil".to_string()),
+                },
+            ]
+        );
+        assert_eq!(diff.changes[2].fields[0].field, "longNameEn");
+        assert!(diff.changes[0].fields.is_empty() && diff.changes[3].fields.is_empty());
+    }
+
+    #[test]
+    fn matches_keywords_by_ubk_id_across_renames() {
+        let diff = diff(
+            json!([{"ubkId": "7", "abbrName": "Tmp"}]),
+            json!([{"ubkId": "7", "abbrName": "Temp"}]),
+        );
+        assert_eq!(kinds(&diff), [(ChangeKind::Changed, "Temp")]);
+        assert_eq!(diff.changes[0].fields[0].old.as_deref(), Some("Tmp"));
+    }
+
+    #[test]
+    fn obsolete_keyword_becoming_removed_is_a_change() {
+        let diff = diff(
+            json!([{"ubkId": 1, "abbrName": "Olt", "lifeCycleState": "obsolete"}]),
+            json!([{"ubkId": 1, "abbrName": "Olt", "lifeCycleState": "removed"}]),
+        );
+        assert_eq!(kinds(&diff), [(ChangeKind::Changed, "Olt")]);
+    }
+
+    #[test]
+    fn entries_sharing_a_fallback_key_are_all_compared() {
+        let diff = diff(
+            json!([
+                {"shortName": "Temp", "abbrName": "Temp", "longNameEn": "Temperature"},
+                {"shortName": "Temp", "abbrName": "Temp", "longNameEn": "Temporary"}
+            ]),
+            json!([
+                {"shortName": "Temp", "abbrName": "Temp", "longNameEn": "Temperature"},
+                {"shortName": "Temp", "abbrName": "Temp", "longNameEn": "Temporary",
+                 "lifeCycleState": "obsolete"},
+                {"shortName": "Temp", "abbrName": "Temp", "longNameEn": "Template"}
+            ]),
+        );
+        assert_eq!(
+            kinds(&diff),
+            [
+                (ChangeKind::Added, "Temp"),
+                (ChangeKind::Deprecated, "Temp")
+            ]
+        );
+        assert_eq!(diff.changes[0].long_name_en.as_deref(), Some("Template"));
+        assert_eq!(diff.changes[1].long_name_en.as_deref(), Some("Temporary"));
+    }
+
+    #[test]
+    fn markdown_lists_each_kind_in_its_own_table() {
+        let diff = diff(
+            json!([
+                {"ubkId": 1, "abbrName": "Olt", "longNameEn": "Oil", "lifeCycleState": "valid"},
+                {"ubkId": 2, "abbrName": "Max", "longNameEn": "Maximum"}
+            ]),
+            json!([
+                {"ubkId": 1, "abbrName": "Olt", "longNameEn": "Oil", "lifeCycleState": "obsolete"},
+                {"ubkId": 3, "abbrName": "Oil", "longNameEn": "Oil"}
+            ]),
+        );
+        assert_eq!(
+            markdown(&diff),
+            "# Keyword changes from DB: 13.06.2025 to DB: 01.07.2025\n\n\
+             1 added, 1 deprecated, 0 changed, 1 removed\n\
+             \n## Added\n\n\
+             | Abbreviation | Long name | Life cycle state |\n\
+             | --- | --- | --- |\n\
+             | Oil | Oil |  |\n\
+             \n## Deprecated\n\n\
+             | Abbreviation | Long name | Life cycle state | Changes |\n\
+             | --- | --- | --- | --- |\n\
+             | Olt | Oil | obsolete | lifeCycleState: valid → obsolete |\n\
+             \n## Removed\n\n\
+             | Abbreviation | Long name | Life cycle state |\n\
+             | --- | --- | --- |\n\
+             | Max | Maximum |  |\n"
+        );
+    }
+
+    #[test]
+    fn markdown_escapes_pipes_and_line_breaks() {
+        let diff = diff(
+            json!([]),
+            json!([{"ubkId": 1, "abbrName": "Sw", "longNameEn": "On|Off\r\nswitch"}]),
+        );
+        assert!(markdown(&diff).contains("| Sw | On\\|Off  switch |  |\n"));
+    }
+
+    #[test]
+    fn csv_has_a_row_per_changed_field() {
+        let diff = diff(
+            json!([{"ubkId": 1, "abbrName": "Olt", "longNameEn": "Oil", "lifeCycleState": "valid"}]),
+            json!([{"ubkId": 1, "abbrName": "Olt", "longNameEn": "Oil, old",
+                    "lifeCycleState": "obsolete"}]),
+        );
+        assert_eq!(
+            csv(&diff),
+            "change,abbrName,longNameEn,lifeCycleState,field,old,new\r\n\
+             deprecated,Olt,\"Oil, old\",obsolete,longNameEn,Oil,\"Oil, old\"\r\n\
+             deprecated,Olt,\"Oil, old\",obsolete,lifeCycleState,valid,obsolete\r\n"
+        );
+    }
+
+    #[test]
+    fn csv_quotes_fields_as_in_rfc_4180() {
+        assert_eq!(csv_field("Oil"), "Oil");
+        assert_eq!(csv_field("Oil, old"), "\"Oil, old\"");
+        assert_eq!(csv_field("the \"old\" oil"), "\"the \"\"old\"\" oil\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(csv_field("two\r\nlines"), "\"two\r\nlines\"");
+    }
+}
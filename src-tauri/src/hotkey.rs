@@ -0,0 +1,84 @@
+// System-wide shortcut validating the clipboard
+//
+// Copy a label in any tool, e.g. an A2L editor, and press the shortcut: the backend reads
+// the clipboard and shows the verdict either in the main window or, in the compact mode,
+// as a notification without opening any window.
+use crate::error::{AppError, AppResult};
+use crate::label::LabelReport;
+use crate::settings;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tauri_plugin_notification::NotificationExt;
+
+pub const DEFAULT_HOTKEY: &str = "CommandOrControl+Alt+Shift+L";
+
+/// Where the result of a shortcut validation is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HotkeyMode {
+    /// The main window, positioned like after startup, with the label filled in.
+    #[default]
+    Window,
+    /// A notification with the verdict.
+    Notification,
+}
+
+/// Parse a shortcut like `CommandOrControl+Alt+Shift+L`.
+pub fn parse(hotkey: &str) -> AppResult<Shortcut> {
+    hotkey
+        .parse()
+        .map_err(|e| AppError::Validation(format!("Invalid shortcut '{}': {}", hotkey, e)))
+}
+
+/// Register the shortcut from the settings in place of the previous one, `on_press`
+/// runs whenever it is pressed.
+pub fn register(app: &AppHandle, on_press: fn(&AppHandle)) -> AppResult<()> {
+    let shortcuts = app.global_shortcut();
+    shortcuts
+        .unregister_all()
+        .map_err(|e| AppError::Internal(format!("Failed to release the shortcut: {}", e)))?;
+    let Some(hotkey) = settings::current(app).hotkey else {
+        log::info!("No validation shortcut configured");
+        return Ok(());
+    };
+    let shortcut = parse(&hotkey)?;
+    shortcuts
+        .on_shortcut(shortcut, move |app, _, event| {
+            if event.state == ShortcutState::Pressed {
+                on_press(app);
+            }
+        })
+        .map_err(|e| {
+            AppError::Validation(format!(
+                "Failed to register shortcut {}, another application may use it: {}",
+                hotkey, e
+            ))
+        })?;
+    log::info!("Validating the clipboard on {}", hotkey);
+    Ok(())
+}
+
+/// Show a notification, used for the verdict in the compact mode.
+pub fn notify(app: &AppHandle, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::error!("Failed to show notification: {}", e);
+    }
+}
+
+/// Title and text of the notification for a validated label.
+pub fn verdict(report: &LabelReport) -> (String, String) {
+    let title = if !report.valid {
+        format!("✖ {} is invalid", report.label)
+    } else if !report.warnings.is_empty() {
+        format!("⚠ {} is valid with warnings", report.label)
+    } else {
+        format!("✔ {} is valid", report.label)
+    };
+    let body = if report.message.is_empty() {
+        "No findings".to_string()
+    } else {
+        report.message.clone()
+    };
+    (title, body)
+}
@@ -0,0 +1,255 @@
+// Typed model of the UBK keyword feed
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+
+/// One entry of the `ubk-keywords` feed.
+///
+/// Fields not used by the application are kept in `extra` so that the data
+/// handed to the frontend and written to `keywords.json` stays complete.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Keyword {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub short_name: Option<String>,
+    pub abbr_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub long_name_en: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub long_name_de: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_en: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description_de: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, deserialize_with = "string_or_list")]
+    pub rb_classifications: Vec<String>,
+    #[serde(default, deserialize_with = "string_or_list")]
+    pub autosar_classifications: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub life_cycle_state: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_instead: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_instead_abbr_name: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+// The feed is not consistent: classifications come as a list, a single string or null
+fn string_or_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrList {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match Option::<StringOrList>::deserialize(deserializer)? {
+        Some(StringOrList::One(s)) => vec![s],
+        Some(StringOrList::Many(list)) => list,
+        None => Vec::new(),
+    })
+}
+
+impl Keyword {
+    pub fn has_classification(&self, name: &str) -> bool {
+        self.rb_classifications.iter().any(|c| c == name)
+    }
+
+    /// `String(rbClassifications).toLowerCase() === 'extension'` in the frontend,
+    /// i.e. the keyword is classified as Extension and nothing else.
+    pub fn is_extension_only(&self) -> bool {
+        self.rb_classifications.join(",").to_lowercase() == "extension"
+    }
+
+    pub fn is_obsolete(&self) -> bool {
+        self.life_cycle_state
+            .as_deref()
+            .is_some_and(|s| s.eq_ignore_ascii_case("obsolete"))
+    }
+}
+
+/// A feed entry that could not be turned into a [`Keyword`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordIssue {
+    /// Position of the entry in the feed array.
+    pub index: usize,
+    /// `abbrName` of the entry, if it had a readable one.
+    pub abbr_name: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParsedKeywords {
+    pub keywords: Vec<Keyword>,
+    pub issues: Vec<KeywordIssue>,
+}
+
+/// Parse a keyword feed.
+///
+/// Fails only if the payload is not a JSON array; malformed entries are
+/// skipped and reported in `issues` with their index.
+pub fn parse_keywords(bytes: &[u8]) -> Result<ParsedKeywords, String> {
+    let entries = match serde_json::from_slice::<Value>(bytes) {
+        Ok(Value::Array(entries)) => entries,
+        Ok(_) => return Err("Keyword payload is not a JSON array".to_string()),
+        Err(e) => return Err(format!("Failed to parse keyword JSON: {}", e)),
+    };
+
+    let mut parsed = ParsedKeywords::default();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let abbr_name = entry
+            .get("abbrName")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        match serde_json::from_value::<Keyword>(entry) {
+            Ok(keyword) => parsed.keywords.push(keyword),
+            Err(e) => parsed.issues.push(KeywordIssue {
+                index,
+                abbr_name,
+                message: e.to_string(),
+            }),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Read and parse a keyword file such as the cached `keywords.json`.
+pub fn read_keywords_file(path: &Path) -> Result<ParsedKeywords, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse_keywords(&bytes)
+}
+
+/// Log every malformed entry of a parsed feed.
+pub fn log_issues(source: &str, issues: &[KeywordIssue]) {
+    if issues.is_empty() {
+        return;
+    }
+    log::warn!("{} malformed keyword entries in {}", issues.len(), source);
+    for issue in issues {
+        log::warn!(
+            "Skipping keyword #{} ({}) from {}: {}",
+            issue.index,
+            issue.abbr_name.as_deref().unwrap_or("<no abbrName>"),
+            source,
+            issue.message
+        );
+    }
+}
+
+/// A valid keyword with a single classification, for the tests of other modules.
+#[cfg(test)]
+pub(crate) fn test_keyword(abbr_name: &str, classification: &str, long_name_en: &str) -> Keyword {
+    serde_json::from_value(serde_json::json!({
+        "abbrName": abbr_name,
+        "longNameEn": long_name_en,
+        "rbClassifications": [classification],
+        "lifeCycleState": "valid",
+    }))
+    .unwrap()
+}
+
+#[cfg(test)]
+impl Keyword {
+    /// This test keyword as obsolete, to be replaced by `abbr_name`.
+    pub(crate) fn replaced_by(mut self, abbr_name: &str) -> Keyword {
+        self.life_cycle_state = Some("obsolete".to_string());
+        self.use_instead = Some(abbr_name.to_string());
+        self.use_instead_abbr_name = Some(abbr_name.to_string());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_malformed_entries_by_index() {
+        let feed = br#"[
+            {"abbrName": "Eng", "longNameEn": "engine"},
+            {"abbrName": 42},
+            {"longNameEn": "no abbreviation"},
+            {"abbrName": "Oil", "rbClassifications": {"not": "a list"}},
+            {"abbrName": "Spd"}
+        ]"#;
+        let parsed = parse_keywords(feed).unwrap();
+        let kept: Vec<&str> = parsed
+            .keywords
+            .iter()
+            .map(|k| k.abbr_name.as_str())
+            .collect();
+        assert_eq!(kept, vec!["Eng", "Spd"]);
+        let issues: Vec<(usize, Option<&str>)> = parsed
+            .issues
+            .iter()
+            .map(|issue| (issue.index, issue.abbr_name.as_deref()))
+            .collect();
+        assert_eq!(issues, vec![(1, None), (2, None), (3, Some("Oil"))]);
+        assert!(parsed.issues.iter().all(|issue| !issue.message.is_empty()));
+    }
+
+    #[test]
+    fn rejects_payloads_that_are_not_an_array() {
+        assert!(parse_keywords(br#"{"error": "maintenance"}"#).is_err());
+        assert!(parse_keywords(b"<html>").is_err());
+        assert!(parse_keywords(b"[]").unwrap().keywords.is_empty());
+    }
+
+    #[test]
+    fn classifications_may_be_a_string_a_list_or_null() {
+        let feed = br#"[
+            {"abbrName": "t", "rbClassifications": "Physical"},
+            {"abbrName": "n", "rbClassifications": ["Physical", "Logical"]},
+            {"abbrName": "Eng", "rbClassifications": null},
+            {"abbrName": "Oil"}
+        ]"#;
+        let parsed = parse_keywords(feed).unwrap();
+        assert!(parsed.issues.is_empty());
+        let classifications: Vec<&Vec<String>> = parsed
+            .keywords
+            .iter()
+            .map(|k| &k.rb_classifications)
+            .collect();
+        assert_eq!(
+            classifications,
+            vec![
+                &vec!["Physical".to_string()],
+                &vec!["Physical".to_string(), "Logical".to_string()],
+                &Vec::new(),
+                &Vec::new(),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_fields() {
+        let parsed = parse_keywords(br#"[{"abbrName": "Eng", "owner": "BBM"}]"#).unwrap();
+        let keyword = &parsed.keywords[0];
+        assert_eq!(keyword.extra.get("owner"), Some(&Value::from("BBM")));
+        let json = serde_json::to_value(keyword).unwrap();
+        assert_eq!(json["owner"], "BBM");
+    }
+
+    #[test]
+    fn extension_only_and_obsolete() {
+        let parsed = parse_keywords(
+            br#"[
+                {"abbrName": "C", "rbClassifications": "Extension", "lifeCycleState": "Obsolete"},
+                {"abbrName": "Max", "rbClassifications": ["Extension", "Descriptive"]}
+            ]"#,
+        )
+        .unwrap();
+        assert!(parsed.keywords[0].is_extension_only());
+        assert!(parsed.keywords[0].is_obsolete());
+        assert!(!parsed.keywords[1].is_extension_only());
+        assert!(!parsed.keywords[1].is_obsolete());
+    }
+}
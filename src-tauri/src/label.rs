@@ -0,0 +1,755 @@
+// AUTOSAR label validation: <Id>_<pp><DescriptiveName>_<Ex>
+//
+// Port of `splitLabel` / `getLabelRows` from the frontend's validation.ts so the
+// same rules can run in the backend and outside the webview.
+use crate::keyword::Keyword;
+use crate::rules::{RuleSet, Severity};
+use crate::waiver::WaivedFinding;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::fmt;
+
+/// Maximum number of characters allowed in a label by the default rule set.
+pub const MAX_LABEL_LENGTH: usize = 27;
+
+/// Validation errors, declared in the same order as `ERROR_RANK` in the frontend.
+/// The declaration order is the ranking: lower discriminant = more important.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LabelError {
+    NoLabel,
+    IdNotAllowed,
+    PhysicalNotAvailable,
+    PhysicalMissing,
+    DescriptiveMissing,
+    DuplicateKeywords,
+    DescriptiveNotAvailable,
+    DescriptiveInvalid,
+    ExtensionMissing,
+    ExtensionNotAvailable,
+    ExtensionInvalid,
+    /// Carries the length limit of the rule set.
+    TooLong(usize),
+}
+
+/// Rule identifiers used for the severities of a rule set, see [`LabelError::rule`].
+pub const RULES: [&str; 12] = [
+    "noLabel",
+    "idNotAllowed",
+    "physicalNotAvailable",
+    "physicalMissing",
+    "descriptiveMissing",
+    "duplicateKeywords",
+    "descriptiveNotAvailable",
+    "descriptiveInvalid",
+    "extensionMissing",
+    "extensionNotAvailable",
+    "extensionInvalid",
+    "tooLong",
+];
+
+impl LabelError {
+    /// Identifier of the rule that reports this error.
+    pub fn rule(&self) -> &'static str {
+        match self {
+            LabelError::NoLabel => "noLabel",
+            LabelError::IdNotAllowed => "idNotAllowed",
+            LabelError::PhysicalNotAvailable => "physicalNotAvailable",
+            LabelError::PhysicalMissing => "physicalMissing",
+            LabelError::DescriptiveMissing => "descriptiveMissing",
+            LabelError::DuplicateKeywords => "duplicateKeywords",
+            LabelError::DescriptiveNotAvailable => "descriptiveNotAvailable",
+            LabelError::DescriptiveInvalid => "descriptiveInvalid",
+            LabelError::ExtensionMissing => "extensionMissing",
+            LabelError::ExtensionNotAvailable => "extensionNotAvailable",
+            LabelError::ExtensionInvalid => "extensionInvalid",
+            LabelError::TooLong(_) => "tooLong",
+        }
+    }
+
+    pub fn message(&self) -> Cow<'static, str> {
+        match self {
+            LabelError::NoLabel => "No Label".into(),
+            LabelError::IdNotAllowed => "ID prefix <Id> is not allowed".into(),
+            LabelError::PhysicalNotAvailable => "Abbreviation of <pp> not available".into(),
+            LabelError::PhysicalMissing => "Physical part <pp> is missing".into(),
+            LabelError::DescriptiveMissing => "DescriptiveName part <dd> is missing".into(),
+            LabelError::DuplicateKeywords => "Duplicate keywords used".into(),
+            LabelError::DescriptiveNotAvailable => "Abbreviation of <dd> not available".into(),
+            LabelError::DescriptiveInvalid => "DescriptiveName part <dd> is invalid".into(),
+            LabelError::ExtensionMissing => "Extension part <Ex> is missing".into(),
+            LabelError::ExtensionNotAvailable => "Extension <Ex> not available".into(),
+            LabelError::ExtensionInvalid => "Extension part <Ex> is invalid".into(),
+            LabelError::TooLong(limit) => format!("Label exceeds {} characters", limit).into(),
+        }
+    }
+}
+
+impl fmt::Display for LabelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message())
+    }
+}
+
+// Serialized as the plain message so the frontend can keep comparing against ERROR_RANK
+impl Serialize for LabelError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.message())
+    }
+}
+
+/// Which part of the label a row describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LabelPart {
+    Id,
+    Physical,
+    Descriptive,
+    Extension,
+}
+
+/// Result of splitting a label into `<Id>_<pp><DescriptiveName>_<Ex>`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitLabel {
+    pub id: String,
+    pub pp: String,
+    /// `pp` followed by the camel case words of the descriptive name.
+    pub keywords: Vec<String>,
+    pub ex: String,
+    pub is_invalid: bool,
+}
+
+impl SplitLabel {
+    /// The descriptive name keywords, i.e. `keywords` without the physical part.
+    pub fn descriptive(&self) -> &[String] {
+        if self.pp.is_empty() {
+            &self.keywords
+        } else {
+            &self.keywords[1..]
+        }
+    }
+}
+
+/// One row of the label result table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelRow {
+    pub part: LabelPart,
+    pub abbr_name: String,
+    pub rb_classifications: Vec<String>,
+    pub long_name_en: String,
+    pub long_name_de: String,
+    pub domain_name: String,
+    pub life_cycle_state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_instead: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_instead_abbr_name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConsolidatedMessage {
+    pub text: String,
+    pub color: String,
+}
+
+/// Full validation outcome for one label, shaped like the object returned by `getLabelRows`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelReport {
+    pub label: String,
+    pub valid: bool,
+    /// Unique errors sorted by rank, most important first.
+    pub errors: Vec<LabelError>,
+    /// Findings of rules the rule set reports as warnings; they do not make the label invalid.
+    pub warnings: Vec<LabelError>,
+    /// Errors and warnings accepted by a waiver.
+    pub waived: Vec<WaivedFinding>,
+    pub rows: Vec<LabelRow>,
+    pub message: String,
+    pub color: String,
+    pub consolidated_messages: Vec<ConsolidatedMessage>,
+    pub life_cycle_state: Option<String>,
+}
+
+/// Split a label into its components.
+///
+/// Mirrors the frontend regex
+/// `^([A-Za-z0-9]+)_([a-zA-Z][a-z0-9]*)([A-Za-z0-9]*)(?:_([A-Za-z0-9]+))?$`.
+pub fn split_label(label: &str) -> SplitLabel {
+    let invalid = SplitLabel {
+        is_invalid: true,
+        ..SplitLabel::default()
+    };
+    let is_alnum = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric());
+
+    let parts: Vec<&str> = label.split('_').collect();
+    let (id, middle, ex) = match parts.as_slice() {
+        [id, middle] => (*id, *middle, ""),
+        [id, middle, ex] if is_alnum(ex) => (*id, *middle, *ex),
+        _ => return invalid,
+    };
+    if !is_alnum(id) || !is_alnum(middle) || !middle.starts_with(|c: char| c.is_ascii_alphabetic())
+    {
+        return invalid;
+    }
+
+    // pp is the first letter followed by lowercase letters and digits
+    let pp_len = 1 + middle[1..]
+        .find(|c: char| !(c.is_ascii_lowercase() || c.is_ascii_digit()))
+        .unwrap_or(middle.len() - 1);
+    let (pp, descriptive) = middle.split_at(pp_len);
+
+    let mut keywords = vec![pp.to_string()];
+    keywords.extend(camel_case_words(descriptive));
+
+    SplitLabel {
+        id: id.to_string(),
+        pp: pp.to_string(),
+        keywords,
+        ex: ex.to_string(),
+        is_invalid: false,
+    }
+}
+
+// Equivalent of `descriptivePart.match(/[A-Z][a-z0-9]*/g)`
+fn camel_case_words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current: Option<String> = None;
+    for c in s.chars() {
+        if c.is_ascii_uppercase() {
+            if let Some(word) = current.take() {
+                words.push(word);
+            }
+            current = Some(c.to_string());
+        } else if let Some(word) = current
+            .as_mut()
+            .filter(|_| c.is_ascii_lowercase() || c.is_ascii_digit())
+        {
+            word.push(c);
+        }
+    }
+    words.extend(current);
+    words
+}
+
+// Case sensitive abbreviation lookup (`filterData2`)
+fn find_abbr<'a>(keywords: &'a [Keyword], abbr: &'a str) -> impl Iterator<Item = &'a Keyword> {
+    keywords
+        .iter()
+        .filter(move |k| !abbr.is_empty() && k.abbr_name == abbr)
+}
+
+fn keyword_row(part: LabelPart, abbr: &str, keyword: Option<&Keyword>) -> LabelRow {
+    let field = |value: Option<&Option<String>>| {
+        value
+            .and_then(|v| v.clone())
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let rb_classifications = match keyword {
+        Some(k) if !k.rb_classifications.is_empty() => k.rb_classifications.clone(),
+        _ => vec!["-".to_string()],
+    };
+    LabelRow {
+        part,
+        abbr_name: keyword.map_or(abbr, |k| &k.abbr_name).to_string(),
+        rb_classifications,
+        long_name_en: field(keyword.map(|k| &k.long_name_en)),
+        long_name_de: field(keyword.map(|k| &k.long_name_de)),
+        domain_name: field(keyword.map(|k| &k.domain_name)),
+        life_cycle_state: field(keyword.map(|k| &k.life_cycle_state)),
+        use_instead: None,
+        use_instead_abbr_name: None,
+    }
+}
+
+/// A label is only considered if it has no leading/trailing or embedded special
+/// characters, at most three underscore separated parts and starts uppercase.
+pub fn is_label_candidate(label: &str) -> bool {
+    let only_label_chars = label.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let trimmed = label.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+    only_label_chars
+        && trimmed.len() == label.len()
+        && label.split('_').count() <= 3
+        && label.starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+/// Validate a label against the keyword database with the default rule set.
+pub fn validate_label(label: &str, keywords: &[Keyword]) -> LabelReport {
+    validate_label_with(label, keywords, &RuleSet::default())
+}
+
+/// Validate a label against the keyword database with the rules of `rules`.
+pub fn validate_label_with(label: &str, keywords: &[Keyword], rules: &RuleSet) -> LabelReport {
+    if !is_label_candidate(label) {
+        let error = LabelError::NoLabel;
+        return LabelReport {
+            label: label.to_string(),
+            valid: false,
+            errors: vec![error],
+            warnings: Vec::new(),
+            waived: Vec::new(),
+            rows: Vec::new(),
+            message: error.to_string(),
+            color: "red".to_string(),
+            consolidated_messages: vec![ConsolidatedMessage {
+                text: error.to_string(),
+                color: "red".to_string(),
+            }],
+            life_cycle_state: None,
+        };
+    }
+
+    let split = split_label(label);
+    let mut findings = Vec::new();
+    let mut rows = Vec::new();
+
+    if !split.id.is_empty() {
+        if !rules.allows_id(&split.id) {
+            findings.push(LabelError::IdNotAllowed);
+        }
+        rows.push(LabelRow {
+            part: LabelPart::Id,
+            abbr_name: split.id.clone(),
+            rb_classifications: vec!["Id".to_string()],
+            long_name_en: "-".to_string(),
+            long_name_de: "-".to_string(),
+            domain_name: "-".to_string(),
+            life_cycle_state: "valid".to_string(),
+            use_instead: None,
+            use_instead_abbr_name: None,
+        });
+    }
+
+    // Physical part: must be a keyword of one of the physical classifications. When the
+    // rule set does not require one, anything else is the first descriptive word.
+    let pp_keyword = find_abbr(keywords, &split.pp).find(|k| rules.is_physical(k));
+    let pp = if pp_keyword.is_some() || rules.required_parts.physical {
+        split.pp.as_str()
+    } else {
+        ""
+    };
+    if pp.is_empty() {
+        // An empty <pp> is reported as not available, as it was before rule sets
+        if rules.required_parts.physical {
+            findings.push(LabelError::PhysicalNotAvailable);
+        }
+    } else {
+        if pp_keyword.is_none() {
+            findings.push(LabelError::PhysicalNotAvailable);
+        }
+        rows.push(keyword_row(LabelPart::Physical, pp, pp_keyword));
+    }
+
+    // Descriptive name: every part must exist and must not have an excluded classification
+    let descriptive = if pp.is_empty() {
+        &split.keywords
+    } else {
+        split.descriptive()
+    };
+    let mut seen: Vec<&str> = Vec::new();
+    if !pp.is_empty() {
+        seen.push(pp);
+    }
+    if descriptive.is_empty() && rules.required_parts.descriptive {
+        findings.push(LabelError::DescriptiveMissing);
+    }
+    for part in descriptive {
+        if seen.contains(&part.as_str()) {
+            findings.push(LabelError::DuplicateKeywords);
+        }
+        seen.push(part);
+
+        match find_abbr(keywords, part).find(|k| !rules.is_extension(k)) {
+            None => findings.push(LabelError::DescriptiveNotAvailable),
+            Some(k) if rules.is_excluded_from_descriptive(k) => {
+                findings.push(LabelError::DescriptiveInvalid)
+            }
+            Some(_) => {}
+        }
+    }
+    let mut unique_descriptive: Vec<&String> = Vec::new();
+    for part in descriptive {
+        if !unique_descriptive.contains(&part) {
+            unique_descriptive.push(part);
+        }
+    }
+    for part in unique_descriptive {
+        rows.push(keyword_row(
+            LabelPart::Descriptive,
+            part,
+            find_abbr(keywords, part).next(),
+        ));
+    }
+
+    if label.len() > rules.max_length {
+        findings.push(LabelError::TooLong(rules.max_length));
+    }
+
+    // Extension: must be a keyword classified as extension only
+    if split.ex.is_empty() {
+        if rules.required_parts.extension {
+            findings.push(LabelError::ExtensionMissing);
+        }
+    } else {
+        let ex_keyword = find_abbr(keywords, &split.ex).find(|k| rules.is_extension(k));
+        if ex_keyword.is_none() {
+            findings.push(LabelError::ExtensionNotAvailable);
+        }
+        rows.push(keyword_row(LabelPart::Extension, &split.ex, ex_keyword));
+    }
+
+    findings.sort();
+    findings.dedup();
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    for finding in findings {
+        match rules.severity(finding) {
+            Severity::Error => errors.push(finding),
+            Severity::Warning => warnings.push(finding),
+            Severity::Off => {}
+        }
+    }
+
+    // Attach replacement information to obsolete rows
+    for row in rows.iter_mut() {
+        if !row.life_cycle_state.eq_ignore_ascii_case("obsolete") {
+            continue;
+        }
+        if let Some(k) = find_abbr(keywords, &row.abbr_name).find(|k| k.is_obsolete()) {
+            row.use_instead = k.use_instead.clone();
+            row.use_instead_abbr_name = k.use_instead_abbr_name.clone();
+        }
+    }
+
+    let life_cycle_state = rows
+        .iter()
+        .find(|r| r.life_cycle_state.eq_ignore_ascii_case("obsolete"))
+        .or(rows.first())
+        .map(|r| r.life_cycle_state.clone());
+
+    let mut report = LabelReport {
+        label: label.to_string(),
+        valid: false,
+        errors,
+        warnings,
+        waived: Vec::new(),
+        rows,
+        message: String::new(),
+        color: String::new(),
+        consolidated_messages: Vec::new(),
+        life_cycle_state,
+    };
+    report.update_verdict();
+    report
+}
+
+impl LabelReport {
+    /// Derive validity and the consolidated message from the errors and warnings.
+    pub fn update_verdict(&mut self) {
+        // The most important error, or the most important warning of a valid label
+        let consolidated = self
+            .errors
+            .first()
+            .map(|e| (e.to_string(), "red"))
+            .or(self.warnings.first().map(|w| (w.to_string(), "orange")))
+            .or(self
+                .waived
+                .first()
+                .map(|w| (format!("{} (waived)", w.error), "navy")));
+        self.valid = self.errors.is_empty();
+        self.consolidated_messages = consolidated
+            .map(|(text, color)| ConsolidatedMessage {
+                text,
+                color: color.to_string(),
+            })
+            .into_iter()
+            .collect();
+        let first = self.consolidated_messages.first();
+        self.message = first.map(|m| m.text.clone()).unwrap_or_default();
+        self.color = first.map(|m| m.color.clone()).unwrap_or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyword::test_keyword;
+    use crate::rules::RequiredParts;
+
+    fn keywords() -> Vec<Keyword> {
+        vec![
+            test_keyword("t", "Physical", "temperature"),
+            test_keyword("n", "Logical", "speed"),
+            test_keyword("Eng", "Descriptive", "engine"),
+            test_keyword("Oil", "Descriptive", "oil"),
+            test_keyword("Max", "Descriptive", "maximum"),
+            test_keyword("Tmp", "Physical", "temperature (old)"),
+            test_keyword("C", "Extension", "calibration"),
+            test_keyword("Olt", "Descriptive", "oil (old)").replaced_by("Oil"),
+        ]
+    }
+
+    fn errors(label: &str) -> Vec<LabelError> {
+        validate_label(label, &keywords()).errors
+    }
+
+    #[test]
+    fn splits_label_into_parts() {
+        let split = split_label("Eng_tOilMax_C");
+        assert_eq!(split.id, "Eng");
+        assert_eq!(split.pp, "t");
+        assert_eq!(split.keywords, vec!["t", "Oil", "Max"]);
+        assert_eq!(split.descriptive(), ["Oil", "Max"]);
+        assert_eq!(split.ex, "C");
+        assert!(!split.is_invalid);
+
+        let split = split_label("Eng_t2OilTemp");
+        assert_eq!(split.pp, "t2");
+        assert_eq!(split.keywords, vec!["t2", "Oil", "Temp"]);
+        assert_eq!(split.ex, "");
+    }
+
+    #[test]
+    fn uppercase_physical_part_takes_the_first_word() {
+        let split = split_label("Eng_OilTemp");
+        assert_eq!(split.pp, "Oil");
+        assert_eq!(split.keywords, vec!["Oil", "Temp"]);
+    }
+
+    #[test]
+    fn rejects_labels_not_matching_the_pattern() {
+        for label in [
+            "Eng",
+            "Eng__tOil",
+            "_tOil",
+            "Eng_tOil_",
+            "Eng_tOil_C_D",
+            "Eng_1Oil",
+            "Eng-x_tOil",
+        ] {
+            assert!(split_label(label).is_invalid, "{}", label);
+        }
+    }
+
+    #[test]
+    fn label_candidates() {
+        assert!(is_label_candidate("Eng_tOil"));
+        assert!(is_label_candidate("Eng_tOil_C"));
+        assert!(is_label_candidate("Eng"));
+        assert!(!is_label_candidate("_Eng_tOil"));
+        assert!(!is_label_candidate("Eng_tOil_"));
+        assert!(!is_label_candidate("Eng_tOil_C_D"));
+        assert!(!is_label_candidate("eng_tOil"));
+        assert!(!is_label_candidate("Eng tOil"));
+        assert!(!is_label_candidate("Eng-tOil"));
+        assert!(!is_label_candidate(""));
+    }
+
+    #[test]
+    fn error_rank_follows_declaration_order() {
+        let mut errors = vec![
+            LabelError::TooLong(27),
+            LabelError::ExtensionInvalid,
+            LabelError::DuplicateKeywords,
+            LabelError::NoLabel,
+            LabelError::PhysicalNotAvailable,
+            LabelError::DescriptiveNotAvailable,
+        ];
+        errors.sort();
+        assert_eq!(
+            errors,
+            vec![
+                LabelError::NoLabel,
+                LabelError::PhysicalNotAvailable,
+                LabelError::DuplicateKeywords,
+                LabelError::DescriptiveNotAvailable,
+                LabelError::ExtensionInvalid,
+                LabelError::TooLong(27),
+            ]
+        );
+        // RULES lists the rules in the same order
+        let positions: Vec<Option<usize>> = errors
+            .iter()
+            .map(|error| RULES.iter().position(|rule| *rule == error.rule()))
+            .collect();
+        assert!(positions.is_sorted() && positions.iter().all(Option::is_some));
+    }
+
+    #[test]
+    fn valid_label() {
+        let report = validate_label("Eng_tOilMax_C", &keywords());
+        assert!(report.valid);
+        assert!(report.errors.is_empty());
+        assert_eq!(report.message, "");
+        let parts: Vec<LabelPart> = report.rows.iter().map(|row| row.part).collect();
+        assert_eq!(
+            parts,
+            vec![
+                LabelPart::Id,
+                LabelPart::Physical,
+                LabelPart::Descriptive,
+                LabelPart::Descriptive,
+                LabelPart::Extension,
+            ]
+        );
+        assert_eq!(report.rows[1].long_name_en, "temperature");
+        assert_eq!(report.life_cycle_state.as_deref(), Some("valid"));
+    }
+
+    #[test]
+    fn errors_are_sorted_by_rank_and_unique() {
+        let report = validate_label("Eng_xFooFoo_X", &keywords());
+        assert!(!report.valid);
+        assert_eq!(
+            report.errors,
+            vec![
+                LabelError::PhysicalNotAvailable,
+                LabelError::DuplicateKeywords,
+                LabelError::DescriptiveNotAvailable,
+                LabelError::ExtensionNotAvailable,
+            ]
+        );
+        assert_eq!(report.message, "Abbreviation of <pp> not available");
+        assert_eq!(report.color, "red");
+        assert_eq!(report.consolidated_messages.len(), 1);
+    }
+
+    #[test]
+    fn missing_parts() {
+        assert_eq!(
+            errors("Eng"),
+            vec![
+                LabelError::PhysicalNotAvailable,
+                LabelError::DescriptiveMissing
+            ]
+        );
+        assert_eq!(errors("Eng_t"), vec![LabelError::DescriptiveMissing]);
+
+        let rules = RuleSet {
+            required_parts: RequiredParts {
+                extension: true,
+                ..Default::default()
+            },
+            ..RuleSet::default()
+        };
+        let report = validate_label_with("Eng_tOil", &keywords(), &rules);
+        assert_eq!(report.errors, vec![LabelError::ExtensionMissing]);
+    }
+
+    #[test]
+    fn optional_physical_part_starts_the_descriptive_name() {
+        assert_eq!(errors("Eng_OilMax"), vec![LabelError::PhysicalNotAvailable]);
+
+        let rules = RuleSet {
+            required_parts: RequiredParts {
+                physical: false,
+                ..Default::default()
+            },
+            ..RuleSet::default()
+        };
+        let report = validate_label_with("Eng_OilMax", &keywords(), &rules);
+        assert!(report.valid);
+        let parts: Vec<(LabelPart, &str)> = report
+            .rows
+            .iter()
+            .map(|row| (row.part, row.abbr_name.as_str()))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                (LabelPart::Id, "Eng"),
+                (LabelPart::Descriptive, "Oil"),
+                (LabelPart::Descriptive, "Max"),
+            ]
+        );
+        // A physical keyword is still read as the physical part
+        let report = validate_label_with("Eng_tOil", &keywords(), &rules);
+        assert!(report.valid);
+        assert_eq!(report.rows[1].part, LabelPart::Physical);
+        // Anything else must then be a descriptive keyword
+        let report = validate_label_with("Eng_xOil", &keywords(), &rules);
+        assert_eq!(report.errors, vec![LabelError::DescriptiveNotAvailable]);
+    }
+
+    #[test]
+    fn unavailable_and_invalid_parts() {
+        assert_eq!(errors("Eng_xOil"), vec![LabelError::PhysicalNotAvailable]);
+        assert_eq!(
+            errors("Eng_tFoo"),
+            vec![LabelError::DescriptiveNotAvailable]
+        );
+        // Physical and Extension keywords are not allowed in the descriptive name
+        assert_eq!(errors("Eng_tTmp"), vec![LabelError::DescriptiveInvalid]);
+        assert_eq!(errors("Eng_tC"), vec![LabelError::DescriptiveNotAvailable]);
+        // A descriptive keyword is no extension
+        assert_eq!(
+            errors("Eng_tOil_Oil"),
+            vec![LabelError::ExtensionNotAvailable]
+        );
+        assert_eq!(
+            errors("Eng_tOil_X"),
+            vec![LabelError::ExtensionNotAvailable]
+        );
+    }
+
+    #[test]
+    fn duplicate_keywords_get_one_row() {
+        let report = validate_label("Eng_tOilOil", &keywords());
+        assert_eq!(report.errors, vec![LabelError::DuplicateKeywords]);
+        let descriptive = report
+            .rows
+            .iter()
+            .filter(|row| row.part == LabelPart::Descriptive)
+            .count();
+        assert_eq!(descriptive, 1);
+    }
+
+    #[test]
+    fn too_long_uses_the_rule_set_limit() {
+        assert_eq!(
+            errors("Eng_tOilMaxOilMaxOilMaxOilMax"),
+            vec![LabelError::DuplicateKeywords, LabelError::TooLong(27)]
+        );
+        let rules = RuleSet {
+            max_length: 8,
+            ..RuleSet::default()
+        };
+        let report = validate_label_with("Eng_tOil_C", &keywords(), &rules);
+        assert_eq!(report.errors, vec![LabelError::TooLong(8)]);
+        assert_eq!(report.message, "Label exceeds 8 characters");
+        assert!(validate_label_with("Eng_tOil", &keywords(), &rules).valid);
+    }
+
+    #[test]
+    fn obsolete_rows_name_their_replacement() {
+        let report = validate_label("Eng_tOlt", &keywords());
+        assert!(report.valid);
+        let row = report
+            .rows
+            .iter()
+            .find(|row| row.abbr_name == "Olt")
+            .unwrap();
+        assert_eq!(row.life_cycle_state, "obsolete");
+        assert_eq!(row.use_instead.as_deref(), Some("Oil"));
+        assert_eq!(row.use_instead_abbr_name.as_deref(), Some("Oil"));
+        assert_eq!(report.life_cycle_state.as_deref(), Some("obsolete"));
+        // Other rows have no replacement
+        assert!(
+            report
+                .rows
+                .iter()
+                .filter(|row| row.abbr_name != "Olt")
+                .all(|row| row.use_instead.is_none())
+        );
+    }
+
+    #[test]
+    fn lowercase_start_is_no_label() {
+        let report = validate_label("eng_tOil", &keywords());
+        assert!(!report.valid);
+        assert_eq!(report.errors, vec![LabelError::NoLabel]);
+        assert_eq!(report.message, "No Label");
+        assert!(report.rows.is_empty());
+    }
+}
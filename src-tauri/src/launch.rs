@@ -0,0 +1,201 @@
+// Labels and keywords opened from links and the command line
+//
+//   bbm-label://validate?label=<label>    validate a label
+//   bbm-label://keyword/<abbr>            show the keywords with an abbreviation
+//   bbm-label-explorer <label>            validate a label
+//
+// The OS opens a link by starting the app with the URL as argument. A second instance
+// hands its arguments to the running one, which shows the main window with the label or
+// keyword filled in; a first start keeps the request until the main window asks for it.
+// macOS does not start a second instance but sends the running app an open URL event.
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const SCHEME: &str = "bbm-label";
+
+/// What the main window should show.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "value", rename_all = "camelCase")]
+pub enum OpenRequest {
+    /// Validate this label.
+    Validate(String),
+    /// Search keywords for this abbreviation.
+    Keyword(String),
+}
+
+/// The request of the latest link or argument the main window has not taken yet.
+pub type PendingOpen = Mutex<Option<OpenRequest>>;
+
+/// The request in the arguments of a start, without the program name.
+///
+/// The first `bbm-label://` URL wins, otherwise the first argument that is not an option
+/// is a label. Invalid URLs are logged and ignored.
+pub fn from_args(args: &[String]) -> Option<OpenRequest> {
+    if let Some(url) = args.iter().find(|arg| has_scheme(arg)) {
+        return parse_url(url)
+            .map_err(|e| log::warn!("Ignoring link {}: {}", url, e))
+            .ok();
+    }
+    args.iter()
+        .map(|arg| arg.trim())
+        .find(|arg| !arg.is_empty() && !arg.starts_with('-'))
+        .map(|label| OpenRequest::Validate(label.to_string()))
+}
+
+fn has_scheme(arg: &str) -> bool {
+    arg.get(..SCHEME.len())
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case(SCHEME))
+        && arg[SCHEME.len()..].starts_with(':')
+}
+
+/// Parse a `bbm-label://` URL.
+pub fn parse_url(url: &str) -> Result<OpenRequest, String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| e.to_string())?;
+    let segments: Vec<String> = parsed
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| percent_decode(segment).trim().to_string())
+        .collect();
+    let query = |name: &str| {
+        parsed
+            .query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let action = parsed.host_str().unwrap_or_default().to_ascii_lowercase();
+    match action.as_str() {
+        "validate" => query("label")
+            .or_else(|| segments.first().cloned())
+            .map(OpenRequest::Validate)
+            .ok_or_else(|| "no label given, expected validate?label=<label>".to_string()),
+        "keyword" => segments
+            .first()
+            .cloned()
+            .or_else(|| query("abbr"))
+            .map(OpenRequest::Keyword)
+            .ok_or_else(|| "no abbreviation given, expected keyword/<abbr>".to_string()),
+        _ => Err(format!(
+            "unknown action '{}', expected validate or keyword",
+            action
+        )),
+    }
+}
+
+/// Decode the `%xx` escapes of a URL path segment, `+` stays as it is.
+pub fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Keep `request` for the main window and tell it to pick it up.
+pub fn open(app: &AppHandle, request: OpenRequest) {
+    log::info!("Opening {:?}", request);
+    *app.state::<PendingOpen>().lock().unwrap() = Some(request);
+    app.emit_to("main", "open-request", ()).ok();
+}
+
+/// The request not yet shown, if any.
+pub fn take(app: &AppHandle) -> Option<OpenRequest> {
+    app.state::<PendingOpen>().lock().unwrap().take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_validate_links() {
+        assert_eq!(
+            parse_url("bbm-label://validate?label=RB_tMaxLim_C"),
+            Ok(OpenRequest::Validate("RB_tMaxLim_C".to_string()))
+        );
+        assert_eq!(
+            parse_url("bbm-label://validate?label=%20RB_tMax%20"),
+            Ok(OpenRequest::Validate("RB_tMax".to_string()))
+        );
+        assert_eq!(
+            parse_url("BBM-Label://Validate/RB_tMax"),
+            Ok(OpenRequest::Validate("RB_tMax".to_string()))
+        );
+        assert!(parse_url("bbm-label://validate?label=").is_err());
+    }
+
+    #[test]
+    fn parses_keyword_links() {
+        assert_eq!(
+            parse_url("bbm-label://keyword/T%C3%BCr"),
+            Ok(OpenRequest::Keyword("Tür".to_string()))
+        );
+        assert_eq!(
+            parse_url("bbm-label://keyword?abbr=Eng"),
+            Ok(OpenRequest::Keyword("Eng".to_string()))
+        );
+        assert!(parse_url("bbm-label://keyword/").is_err());
+    }
+
+    #[test]
+    fn refuses_unknown_actions() {
+        assert_eq!(
+            parse_url("bbm-label://delete/Eng"),
+            Err("unknown action 'delete', expected validate or keyword".to_string())
+        );
+        assert!(parse_url("not a url").is_err());
+    }
+
+    #[test]
+    fn takes_the_first_link_or_label_from_the_arguments() {
+        assert_eq!(
+            from_args(&args(&[
+                "--minimized",
+                "RB_tMax",
+                "bbm-label://keyword/Eng"
+            ])),
+            Some(OpenRequest::Keyword("Eng".to_string()))
+        );
+        assert_eq!(
+            from_args(&args(&["--minimized", "-v", " ", " RB_tMax ", "RB_tMin"])),
+            Some(OpenRequest::Validate("RB_tMax".to_string()))
+        );
+        assert_eq!(
+            from_args(&args(&["bbm-label://delete/Eng", "RB_tMax"])),
+            None
+        );
+        assert_eq!(from_args(&args(&["--minimized"])), None);
+        assert_eq!(from_args(&[]), None);
+    }
+
+    #[test]
+    fn decodes_percent_escapes() {
+        assert_eq!(percent_decode("RB_tMax"), "RB_tMax");
+        assert_eq!(percent_decode("T%c3%bcr%2FT%C3%BCr"), "Tür/Tür");
+        assert_eq!(percent_decode("a+b%20c"), "a+b c");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz%4"), "%zz%4");
+    }
+}
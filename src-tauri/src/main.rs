@@ -1,16 +1,48 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+mod api;
+mod batch;
+mod cache;
+mod cli;
+mod config;
+mod config_file;
+mod diff;
+mod error;
+mod extract;
+mod fetch;
+mod fix;
+mod history;
+mod hotkey;
+mod keyword;
+mod label;
+mod launch;
+mod report;
+mod rules;
+mod scan;
+mod semantic;
+mod settings;
+mod store;
+mod suggest;
+mod sync;
+mod tray;
+mod waiver;
+mod watch;
+
+use error::{AppError, AppResult};
 use reqwest;
 use serde_json::Value;
 use std::fs;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use sysinfo::System;
 use tauri::WebviewWindowBuilder;
 use tauri::async_runtime::spawn;
-use tauri::menu::{MenuBuilder, MenuItemBuilder};
 use tauri::tray::TrayIconBuilder;
 use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_dialog::DialogExt;
 use tauri_plugin_log;
 
 struct SetupState {
@@ -23,40 +55,60 @@ async fn set_complete(
     app: AppHandle,
     state: State<'_, Arc<Mutex<SetupState>>>,
     task: String,
-) -> Result<(), ()> {
+) -> AppResult<()> {
     let start = Instant::now();
     log::info!("Task completion reported: {}", task);
-    let mut state_lock = state.lock().unwrap();
-    match task.as_str() {
-        "frontend" => {
-            log::info!("Frontend initialization completed");
-            state_lock.frontend_task = true;
-        }
-        "backend" => {
-            log::info!("Backend initialization completed");
-            state_lock.backend_task = true;
-        }
-        _ => {
-            log::error!("Invalid task completion reported: {}", task);
-            panic!("invalid task completed!");
+    let ready = {
+        let mut state_lock = state.lock().unwrap();
+        match task.as_str() {
+            "frontend" => {
+                log::info!("Frontend initialization completed");
+                state_lock.frontend_task = true;
+            }
+            "backend" => {
+                log::info!("Backend initialization completed");
+                state_lock.backend_task = true;
+            }
+            _ => {
+                log::error!("Invalid task completion reported: {}", task);
+                return Err(AppError::Validation(format!(
+                    "Unknown initialization task '{}'",
+                    task
+                )));
+            }
         }
-    }
-    log::debug!(
-        "State after set_complete: frontend_task={}, backend_task={}",
-        state_lock.frontend_task,
-        state_lock.backend_task
-    );
-    if state_lock.backend_task && state_lock.frontend_task {
+        log::debug!(
+            "State after set_complete: frontend_task={}, backend_task={}",
+            state_lock.frontend_task,
+            state_lock.backend_task
+        );
+        state_lock.backend_task && state_lock.frontend_task
+    };
+    if ready {
         log::info!("All initialization tasks completed, transitioning from splash to main window");
-        let splash_window = app.get_webview_window("splashscreen").unwrap();
-        let main_window = app.get_webview_window("main").unwrap();
-        splash_window.close().unwrap();
-        // Move main window to right bottom before showing
-        if let Ok(monitor) = main_window.current_monitor() {
-            if let Some(monitor) = monitor {
+        show_main_window(&app)?;
+    }
+    log::debug!("set_complete finished in {:?}", start.elapsed());
+    Ok(())
+}
+
+// Close the splash screen and show the main window in the bottom-right corner
+fn show_main_window(app: &AppHandle) -> AppResult<()> {
+    let main_window = app
+        .get_webview_window("main")
+        .ok_or_else(|| AppError::Window("Main window not found".to_string()))?;
+    // Both initialization paths end up here, the splash screen may be gone already
+    if let Some(splash_window) = app.get_webview_window("splashscreen") {
+        splash_window
+            .close()
+            .map_err(|e| AppError::Window(format!("Failed to close splash screen: {}", e)))?;
+    }
+    // Move main window to right bottom before showing
+    match main_window.current_monitor() {
+        Ok(Some(monitor)) => match main_window.outer_size() {
+            Ok(window_size) => {
                 log::info!("Positioning main window at bottom-right corner of screen");
                 let monitor_size = monitor.size();
-                let window_size = main_window.outer_size().unwrap();
                 let x = monitor_size.width as i32 - window_size.width as i32;
                 let taskbar_height = 40; // Height in pixels to offset above the taskbar
                 let y = monitor_size.height as i32 - window_size.height as i32 - taskbar_height;
@@ -70,17 +122,84 @@ async fn set_complete(
                     window_size.height
                 );
                 let _ = main_window.set_position(tauri::PhysicalPosition { x, y });
-            } else {
-                log::warn!("Failed to get monitor information for window positioning");
             }
-        } else {
-            log::warn!("Failed to get current monitor for window positioning");
+            Err(e) => log::warn!("Failed to get main window size for positioning: {}", e),
+        },
+        Ok(None) => log::warn!("Failed to get monitor information for window positioning"),
+        Err(_) => log::warn!("Failed to get current monitor for window positioning"),
+    }
+    log::info!("Showing main window");
+    main_window
+        .show()
+        .map_err(|e| AppError::Window(format!("Failed to show main window: {}", e)))
+}
+
+// Global shortcut: validate the label on the clipboard, in the main window or as a notification
+fn validate_clipboard(app: &AppHandle) {
+    let text = match app.clipboard().read_text() {
+        Ok(text) => text,
+        Err(e) => {
+            log::warn!("Failed to read clipboard text: {}", e);
+            hotkey::notify(app, "Nothing to validate", "The clipboard holds no text");
+            return;
+        }
+    };
+    let Some(label) = text.lines().map(str::trim).find(|line| !line.is_empty()) else {
+        hotkey::notify(app, "Nothing to validate", "The clipboard is empty");
+        return;
+    };
+    log::info!("Validating clipboard label {}", label);
+    match settings::current(app).hotkey_mode {
+        hotkey::HotkeyMode::Window => {
+            open_in_main_window(app, launch::OpenRequest::Validate(label.to_string()))
+        }
+        hotkey::HotkeyMode::Notification => match validate_label(app.clone(), label.to_string()) {
+            Ok(report) => {
+                let (title, body) = hotkey::verdict(&report);
+                hotkey::notify(app, &title, &body);
+                tray::record(app, &report);
+            }
+            Err(e) => hotkey::notify(app, "Validation failed", e.message()),
+        },
+    }
+}
+
+// Clipboard watch: validate a copied label and show the verdict in the tray
+fn check_clipboard_label(app: &AppHandle, label: &str) {
+    log::info!("Checking copied label {}", label);
+    match validate_label(app.clone(), label.to_string()) {
+        Ok(report) => tray::record(app, &report),
+        Err(e) => log::warn!("Failed to check copied label {}: {}", label, e),
+    }
+}
+
+// Show the main window positioned like after startup, filled in with `request`
+fn open_in_main_window(app: &AppHandle, request: launch::OpenRequest) {
+    launch::open(app, request);
+    raise_main_window(app);
+}
+
+// Show, restore and focus the main window; it is hidden when closed
+fn raise_main_window(app: &AppHandle) {
+    let ready = {
+        let state = app.state::<Arc<Mutex<SetupState>>>();
+        let state = state.lock().unwrap();
+        state.frontend_task && state.backend_task
+    };
+    // During startup the main window is shown once loading is done
+    if ready {
+        if let Err(e) = show_main_window(app) {
+            log::error!("{}", e);
+        }
+        if let Some(window) = app.get_webview_window("main") {
+            if let Err(e) = window.unminimize() {
+                log::error!("Failed to restore main window: {}", e);
+            }
+            if let Err(e) = window.set_focus() {
+                log::error!("Failed to set focus on main window: {}", e);
+            }
         }
-        log::info!("Showing main window");
-        main_window.show().unwrap();
     }
-    log::debug!("set_complete finished in {:?}", start.elapsed());
-    Ok(())
 }
 
 // Fix: avoid moving State into async task, use Arc<Mutex<SetupState>>
@@ -88,61 +207,52 @@ async fn set_complete(
 async fn start_keyword_loading(
     app: AppHandle,
     state: State<'_, Arc<Mutex<SetupState>>>,
-) -> Result<(), ()> {
+) -> AppResult<()> {
     let start = Instant::now();
     log::info!("start_keyword_loading called");
 
     // Send initial progress message to ensure the loading bar is visible
     app.emit("keyword-loading-progress", "Initializing...").ok();
+    tray::set_loader(&app, tray::LoaderStatus::Loading);
 
     let app_handle = app.clone();
     let state_arc = state.inner().clone();
     spawn(async move {
         log::debug!("Keyword loading background task started");
-        // Hardcoded API URL - no config file needed
-        let api_url = "https://si0vmc0854.de.bosch.com/swap-prod/api/ubk-keywords".to_string();
+        let backend_settings = settings::current(&app_handle);
 
         // Try to load keywords from local storage first
-        let mut use_local_file = false;
         let mut local_json_data = None;
         if let Ok(app_data_dir) = app_handle.path().app_local_data_dir() {
-            let json_path = app_data_dir.join("keywords.json");
-            log::info!("Checking for cached keywords at {}", json_path.display());
-            if json_path.exists() {
-                match fs::read_to_string(&json_path) {
-                    Ok(json_string) => {
-                        match serde_json::from_str::<serde_json::Value>(&json_string) {
-                            Ok(json) => {
-                                if let Some(arr) = json.as_array() {
-                                    log::info!("Found cached keywords with {} entries", arr.len());
-                                    local_json_data = Some(json);
-                                    use_local_file = true;
-
-                                    // Show loading from cache progress
-                                    app_handle
-                                        .emit(
-                                            "keyword-loading-progress",
-                                            "Loading keywords from cache...",
-                                        )
-                                        .ok();
-
-                                    // Notify frontend that we're using cached keywords (offline mode)
-                                    app_handle.emit("using-cached-keywords", ()).ok();
-
-                                    tokio::time::sleep(tokio::time::Duration::from_millis(500))
-                                        .await;
-                                } else {
-                                    log::info!("Cached JSON is not an array, will fetch from API");
-                                }
-                            }
-                            Err(e) => log::warn!(
-                                "Failed to parse cached JSON: {}, will fetch from API",
-                                e
-                            ),
-                        }
+            log::info!(
+                "Checking for cached keywords at {}",
+                cache::keywords_path(&app_data_dir).display()
+            );
+            if cache::exists(&app_data_dir) {
+                match cache::load_keywords(&app_data_dir) {
+                    Ok(parsed) => {
+                        log::info!(
+                            "Found cached keywords with {} entries",
+                            parsed.keywords.len()
+                        );
+                        keyword::log_issues("cached keywords.json", &parsed.issues);
+                        warm_up_semantic_index(&app_handle, parsed.keywords.clone());
+                        // Keywords cached before the history existed become its first snapshot
+                        sync::record_snapshot(&app_handle, &app_data_dir);
+                        local_json_data = Some(parsed.keywords);
+
+                        // Show loading from cache progress
+                        app_handle
+                            .emit("keyword-loading-progress", "Loading keywords from cache...")
+                            .ok();
+
+                        // Notify frontend that we're using cached keywords (offline mode)
+                        app_handle.emit("using-cached-keywords", ()).ok();
+
+                        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
                     }
                     Err(e) => {
-                        log::warn!("Failed to read cached keywords: {}, will fetch from API", e)
+                        log::warn!("Failed to load cached keywords: {}, will fetch from API", e)
                     }
                 }
             } else {
@@ -151,25 +261,16 @@ async fn start_keyword_loading(
         }
 
         // Choose between cached data and API fetch
-        if use_local_file {
+        if let Some(keywords) = local_json_data {
             log::info!("Using cached keywords file");
-            // Clear localStorage before adding cached data
-            app_handle.emit("keyword-clear-localstorage", ()).ok();
 
             // Emit offline mode status notification
             app_handle.emit("using-cached-keywords", ()).ok();
             log::info!("Emitted using-cached-keywords event to indicate offline mode");
 
             // Use the cached data directly
-            let json = local_json_data.unwrap();
-            if let Some(arr) = json.as_array() {
-                log::info!("Using cached JSON entries: {}", arr.len());
-                // Store the array in localStorage via frontend event
-                let json_string = serde_json::to_string_pretty(&arr).unwrap();
-                app_handle
-                    .emit("keyword-store-localstorage", json_string.clone())
-                    .ok();
-            }
+            log::info!("Using cached JSON entries: {}", keywords.len());
+            store::replace(&app_handle, keywords);
 
             app_handle
                 .emit("keyword-loading-progress", "Parsing keywords...")
@@ -177,11 +278,34 @@ async fn start_keyword_loading(
             // Simulate parsing
             tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
             app_handle.emit("keyword-loading-progress", "done").ok();
+
+            if backend_settings.work_offline {
+                log::info!("Working offline, not checking for fresher keywords");
+                tray::set_loader(&app_handle, tray::LoaderStatus::Offline);
+            } else {
+                // Look for fresher keywords while the cached ones are in use
+                spawn(sync::refresh_keywords(app_handle.clone(), false));
+            }
+        } else if backend_settings.work_offline {
+            // Working offline only the cache may be used, there is nothing to show
+            let e = AppError::CacheIo(
+                "Working offline, but no cached keywords are available".to_string(),
+            );
+            error::emit(&app_handle, "keyword-cache", &e);
+            tray::set_loader(
+                &app_handle,
+                tray::LoaderStatus::Error(e.message().to_string()),
+            );
+            app_handle
+                .emit(
+                    "keyword-loading-progress",
+                    "Working offline and no cached keywords available",
+                )
+                .ok();
+            return;
         } else {
             log::info!("Fetching keywords from API");
             // Fetch from API since we couldn't load from cache
-            let client = reqwest::Client::new();
-
             app_handle
                 .emit(
                     "keyword-loading-progress",
@@ -189,162 +313,63 @@ async fn start_keyword_loading(
                 )
                 .ok();
 
-            // Clear localStorage before fetching new data
-            app_handle.emit("keyword-clear-localstorage", ()).ok();
-
-            // Define retry parameters
-            const MAX_RETRIES: usize = 3;
-            const BASE_DELAY_MS: u64 = 1000; // Start with 1 second delay
-
-            let mut response = None;
+            // Remember the server version so later starts can skip the download
+            let server_version = match sync::fetch_updated_date(&backend_settings).await {
+                Ok(updated_date) => Some(updated_date),
+                Err(e) => {
+                    log::warn!("Failed to get keyword version: {}", e);
+                    None
+                }
+            };
 
-            for attempt in 0..=MAX_RETRIES {
-                log::debug!("API fetch attempt {}", attempt);
-                if attempt > 0 {
-                    let delay = BASE_DELAY_MS * 2u64.pow(attempt as u32 - 1); // Exponential backoff
+            let result = sync::download_and_store(
+                &app_handle,
+                &backend_settings,
+                server_version.as_deref(),
+                |retry, max_retries, error, delay| {
+                    log::warn!("Retrying keyword download in {:?} after: {}", delay, error);
                     app_handle
                         .emit(
                             "keyword-loading-progress",
-                            format!("Retry attempt {} of {}...", attempt, MAX_RETRIES),
+                            format!("Retry attempt {} of {}...", retry, max_retries),
                         )
                         .ok();
-                    tokio::time::sleep(tokio::time::Duration::from_millis(delay)).await;
-                }
+                },
+            )
+            .await;
 
-                match client.get(&api_url).send().await {
-                    Ok(resp) => {
-                        log::info!("API call succeeded: {}", api_url);
-                        response = Some(resp);
-                        break;
-                    }
-                    Err(e) => {
-                        log::error!("API call failed: {} - {}", api_url, e);
-                        if attempt == MAX_RETRIES {
-                            app_handle
-                                .emit(
-                                    "keyword-loading-progress",
-                                    "Failed to fetch keywords after all retries",
-                                )
-                                .ok();
-
-                            // Try to use cached data if available
-                            if let Ok(app_data_dir) = app_handle.path().app_local_data_dir() {
-                                let json_path = app_data_dir.join("keywords.json");
-                                log::info!(
-                                    "Falling back to cached keywords at {}",
-                                    json_path.display()
-                                );
-
-                                if json_path.exists() {
-                                    match fs::read_to_string(&json_path) {
-                                        Ok(json_string) => {
-                                            match serde_json::from_str::<serde_json::Value>(
-                                                &json_string,
-                                            ) {
-                                                Ok(json) => {
-                                                    if let Some(arr) = json.as_array() {
-                                                        log::info!(
-                                                            "Found cached keywords with {} entries",
-                                                            arr.len()
-                                                        );
-
-                                                        // Emit offline mode status notification
-                                                        app_handle
-                                                            .emit("using-cached-keywords", ())
-                                                            .ok();
-                                                        log::info!(
-                                                            "Emitted using-cached-keywords event to indicate offline mode"
-                                                        );
-
-                                                        // Store the array in localStorage via frontend event
-                                                        let json_string =
-                                                            serde_json::to_string_pretty(&arr)
-                                                                .unwrap();
-                                                        app_handle
-                                                            .emit(
-                                                                "keyword-store-localstorage",
-                                                                json_string,
-                                                            )
-                                                            .ok();
-
-                                                        app_handle
-                                                            .emit(
-                                                                "keyword-loading-progress",
-                                                                "done",
-                                                            )
-                                                            .ok();
-                                                        return ();
-                                                    }
-                                                }
-                                                Err(e) => log::error!(
-                                                    "Failed to parse cached JSON after API failure: {}",
-                                                    e
-                                                ),
-                                            }
-                                        }
-                                        Err(e) => log::error!(
-                                            "Failed to read cached keywords after API failure: {}",
-                                            e
-                                        ),
-                                    }
-                                }
-                            }
-
-                            // If we get here, both API and cache failed
-                            app_handle
-                                .emit(
-                                    "keyword-loading-progress",
-                                    "Failed to load keywords from API and no cache available",
-                                )
-                                .ok();
-                            return ();
-                        }
-                    }
+            match result {
+                Ok(count) => {
+                    log::info!("JSON entries: {}", count);
+                    tray::set_loader(&app_handle, tray::LoaderStatus::Online);
                 }
-            }
-
-            let response = response.unwrap(); // Safe to unwrap as we only get here if we have a response
-            let stream = response;
-            // To print number of entries, buffer the full body and parse JSON
-            let body = match stream.bytes().await {
-                Ok(bytes) => bytes,
                 Err(e) => {
-                    log::error!("Failed to read response body: {}", e);
-                    return;
-                }
-            };
-            match serde_json::from_slice::<serde_json::Value>(&body) {
-                Ok(json) => {
-                    if let Some(arr) = json.as_array() {
-                        log::info!("JSON entries: {}", arr.len());
-                        // Store the array in localStorage via frontend event
-                        let json_string = serde_json::to_string_pretty(&arr).unwrap();
+                    error::emit(&app_handle, "keyword-loading", &e);
+                    app_handle
+                        .emit(
+                            "keyword-loading-progress",
+                            "Failed to fetch keywords after all retries",
+                        )
+                        .ok();
+
+                    // Try to use cached data if available
+                    if let Err(e) = load_cached_fallback(&app_handle) {
+                        // If we get here, both API and cache failed
+                        error::emit(&app_handle, "keyword-cache", &e);
+                        tray::set_loader(
+                            &app_handle,
+                            tray::LoaderStatus::Error(e.message().to_string()),
+                        );
                         app_handle
-                            .emit("keyword-store-localstorage", json_string.clone())
+                            .emit(
+                                "keyword-loading-progress",
+                                "Failed to load keywords from API and no cache available",
+                            )
                             .ok();
-
-                        // Save the response to keywords.json file
-                        if let Ok(app_data_dir) = app_handle.path().app_local_data_dir() {
-                            let json_path = app_data_dir.join("keywords.json");
-                            log::info!("Saving keywords to {}", json_path.display());
-                            if let Err(e) = fs::write(&json_path, json_string) {
-                                log::error!("Failed to write keywords.json: {}", e);
-                            } else {
-                                log::info!(
-                                    "Successfully saved keywords to {}",
-                                    json_path.display()
-                                );
-                            }
-                        } else {
-                            log::error!(
-                                "Could not determine app data directory to save keywords.json"
-                            );
-                        }
-                    } else {
-                        log::warn!("JSON is not an array");
+                        return;
                     }
+                    tray::set_loader(&app_handle, tray::LoaderStatus::Offline);
                 }
-                Err(e) => log::error!("Failed to parse JSON: {}", e),
             }
             app_handle
                 .emit("keyword-loading-progress", "Parsing keywords...")
@@ -355,25 +380,12 @@ async fn start_keyword_loading(
         } // Close the else block for API fetching
 
         // Directly update state and handle splash/main window logic
-        let mut state_lock = state_arc.lock().unwrap();
-        state_lock.backend_task = true;
+        state_arc.lock().unwrap().backend_task = true;
 
         // Immediately close splash and show main window after keywords are loaded
-        let splash_window = app_handle.get_webview_window("splashscreen").unwrap();
-        let main_window = app_handle.get_webview_window("main").unwrap();
-        splash_window.close().unwrap();
-        // Move main window to right bottom before showing
-        if let Ok(monitor) = main_window.current_monitor() {
-            if let Some(monitor) = monitor {
-                let monitor_size = monitor.size();
-                let window_size = main_window.outer_size().unwrap();
-                let x = monitor_size.width as i32 - window_size.width as i32;
-                let taskbar_height = 40; // Height in pixels to offset above the taskbar
-                let y = monitor_size.height as i32 - window_size.height as i32 - taskbar_height;
-                let _ = main_window.set_position(tauri::PhysicalPosition { x, y });
-            }
+        if let Err(e) = show_main_window(&app_handle) {
+            error::emit(&app_handle, "keyword-loading", &e);
         }
-        main_window.show().unwrap();
         log::debug!(
             "Keyword loading background task finished in {:?}",
             start.elapsed()
@@ -382,85 +394,85 @@ async fn start_keyword_loading(
     Ok(())
 }
 
-#[tauri::command]
-async fn get_version_info() -> Result<String, String> {
-    log::info!("get_version_info called");
+// Serve the cached keywords after the API failed
+fn load_cached_fallback(app_handle: &AppHandle) -> AppResult<()> {
+    let app_data_dir = app_data_dir(app_handle)?;
+    log::info!(
+        "Falling back to cached keywords at {}",
+        cache::keywords_path(&app_data_dir).display()
+    );
+    if !cache::exists(&app_data_dir) {
+        return Err(AppError::CacheIo(
+            "No cached keywords available".to_string(),
+        ));
+    }
+    let parsed = cache::load_keywords(&app_data_dir)?;
+    log::info!(
+        "Found cached keywords with {} entries",
+        parsed.keywords.len()
+    );
+    keyword::log_issues("cached keywords.json", &parsed.issues);
 
-    // Create a reqwest client
-    let client = reqwest::Client::new();
+    // Emit offline mode status notification
+    app_handle.emit("using-cached-keywords", ()).ok();
+    log::info!("Emitted using-cached-keywords event to indicate offline mode");
+    store::replace(app_handle, parsed.keywords);
+    Ok(())
+}
 
-    // API URL for version information
-    let api_url = "https://si0vmc0854.de.bosch.com/swap-prod/api/versions/bbm-keywords";
+// Tray: load the keywords again, from the start when the first loading failed
+fn reload_keywords(app: &AppHandle, force: bool) {
+    if store::current(app).is_loaded() {
+        spawn(sync::refresh_keywords(app.clone(), force));
+    } else {
+        let state = app.state::<Arc<Mutex<SetupState>>>();
+        tauri::async_runtime::block_on(start_keyword_loading(app.clone(), state)).ok();
+    }
+}
 
-    // Make the API call
-    match client.get(api_url).send().await {
-        Ok(response) => {
-            // Check if the request was successful
-            if response.status().is_success() {
-                // Parse the JSON response by first getting the bytes and then parsing
-                let bytes = match response.bytes().await {
-                    Ok(bytes) => bytes,
-                    Err(e) => {
-                        let error_msg = format!("Failed to read response body: {}", e);
-                        log::error!("{}", error_msg);
-                        return Err(error_msg);
-                    }
-                };
+// Tray: switch between the cached keywords.json and syncing with the keyword API
+fn set_work_offline(app: &AppHandle, work_offline: bool) {
+    log::info!("Work offline set to {} from tray", work_offline);
+    let mut backend_settings = settings::current(app);
+    backend_settings.work_offline = work_offline;
+    if let Err(e) = settings::save(app, backend_settings) {
+        error::emit(app, "settings", &e);
+        tray::refresh(app);
+        return;
+    }
+    if !work_offline {
+        reload_keywords(app, false);
+        return;
+    }
+    match load_cached_fallback(app) {
+        Ok(()) => tray::set_loader(app, tray::LoaderStatus::Offline),
+        Err(e) => {
+            error::emit(app, "keyword-cache", &e);
+            tray::set_loader(app, tray::LoaderStatus::Error(e.message().to_string()));
+        }
+    }
+}
 
-                match serde_json::from_slice::<serde_json::Value>(&bytes) {
-                    Ok(json) => {
-                        // Extract the updatedDate field
-                        if let Some(updated_date) = json.get("updatedDate").and_then(|v| v.as_str())
-                        {
-                            log::info!("Version API returned updatedDate: {}", updated_date);
-
-                            // Format the date (assuming format like "2025-06-13T06:52:11Z")
-                            let formatted_date = if updated_date.len() >= 10 {
-                                // Extract just the date part (YYYY-MM-DD)
-                                let date_part = &updated_date[0..10];
-
-                                // Try to parse and reformat the date
-                                if date_part.len() == 10
-                                    && date_part.chars().nth(4) == Some('-')
-                                    && date_part.chars().nth(7) == Some('-')
-                                {
-                                    // Extract components
-                                    let year = &date_part[0..4];
-                                    let month = &date_part[5..7];
-                                    let day = &date_part[8..10];
-
-                                    // Format as DD.MM.YYYY
-                                    format!("DB: {}.{}.{}", day, month, year)
-                                } else {
-                                    format!("DB: {}", updated_date) // Fallback if date format unexpected
-                                }
-                            } else {
-                                format!("DB: {}", updated_date) // Fallback if string too short
-                            };
+// Version of the cached keywords, e.g. `DB: 13.06.2025`
+fn cached_version_info(app: &AppHandle) -> Option<String> {
+    let updated_date = cache::manifest(&app_data_dir(app).ok()?)?.updated_date?;
+    Some(sync::format_version_date(&updated_date))
+}
 
-                            Ok(formatted_date)
-                        } else {
-                            let error_msg = "updatedDate field not found in the response";
-                            log::error!("{}", error_msg);
-                            Err(error_msg.to_string())
-                        }
-                    }
-                    Err(e) => {
-                        let error_msg = format!("Failed to parse JSON response: {}", e);
-                        log::error!("{}", error_msg);
-                        Err(error_msg)
-                    }
-                }
-            } else {
-                let error_msg = format!("API request failed with status: {}", response.status());
-                log::error!("{}", error_msg);
-                Err(error_msg)
-            }
-        }
+fn app_data_dir(app: &AppHandle) -> AppResult<PathBuf> {
+    app.path()
+        .app_local_data_dir()
+        .map_err(|e| AppError::CacheIo(format!("Unable to determine app data directory: {}", e)))
+}
+
+#[tauri::command]
+async fn get_version_info(app: AppHandle) -> AppResult<String> {
+    log::info!("get_version_info called");
+    match sync::fetch_updated_date(&settings::current(&app)).await {
+        Ok(updated_date) => Ok(sync::format_version_date(&updated_date)),
         Err(e) => {
-            let error_msg = format!("Failed to make API request: {}", e);
-            log::error!("{}", error_msg);
-            Err(error_msg)
+            log::error!("{}", e);
+            Err(e)
         }
     }
 }
@@ -508,7 +520,7 @@ fn get_tauri_config_urls(_app: AppHandle) -> Vec<String> {
 }
 
 #[tauri::command]
-fn get_log_file_path(app: AppHandle) -> Result<String, String> {
+fn get_log_file_path(app: AppHandle) -> AppResult<String> {
     log::info!("get_log_file_path called");
     let result = match app.path().config_dir() {
         Ok(config_dir) => {
@@ -517,24 +529,25 @@ fn get_log_file_path(app: AppHandle) -> Result<String, String> {
             if log_file.exists() {
                 Ok(log_file.to_string_lossy().into_owned())
             } else {
-                Err(format!(
+                Err(AppError::CacheIo(format!(
                     "Log file not found at expected location: {}",
                     log_file.display()
-                ))
+                )))
             }
         }
-        Err(_) => Err("Unable to determine config directory".to_string()),
+        Err(_) => Err(AppError::CacheIo(
+            "Unable to determine config directory".to_string(),
+        )),
     };
     log::debug!("Log file path result: {:?}", result);
     result
 }
 
 #[tauri::command]
-fn get_app_config_urls() -> Vec<String> {
+fn get_app_config_urls(app: AppHandle) -> Vec<String> {
     log::info!("get_app_config_urls called");
-    // Hardcoded URLs instead of reading from a config file
     let urls = vec![
-        "https://si0vmc0854.de.bosch.com/swap-prod/api/ubk-keywords".to_string(),
+        settings::current(&app).keywords_url(),
         "/index.html".to_string(),
         "/settings.html".to_string(),
     ];
@@ -542,27 +555,619 @@ fn get_app_config_urls() -> Vec<String> {
     urls
 }
 
+// Non-secret part of the environment configuration
+#[tauri::command]
+fn get_app_config() -> config::AppConfig {
+    log::info!("get_app_config called");
+    config::get_config().clone()
+}
+
+#[tauri::command]
+fn get_backend_settings(app: AppHandle) -> settings::BackendSettings {
+    log::info!("get_backend_settings called");
+    settings::current(&app)
+}
+
+#[tauri::command]
+fn save_backend_settings(app: AppHandle, mut settings: settings::BackendSettings) -> AppResult<()> {
+    log::info!("save_backend_settings called: {:?}", settings);
+    let previous = settings::current(&app);
+    // The Settings window keeps the settings it loaded when it opened; the rule set, the
+    // waiver file, the local API and working offline have their own commands and the tray,
+    // so the saved values of those win over the window's copy
+    settings.keep_separately_set(&previous);
+    let hotkey_changed = previous.hotkey != settings.hotkey;
+    let watch_changed = (previous.clipboard_watch, &previous.clipboard_pattern)
+        != (settings.clipboard_watch, &settings.clipboard_pattern);
+    settings::save(&app, settings).map_err(|e| {
+        log::error!("Failed to save backend settings: {}", e);
+        e
+    })?;
+    if hotkey_changed {
+        hotkey::register(&app, validate_clipboard)?;
+    }
+    if watch_changed {
+        watch::restart(&app, check_clipboard_label);
+    }
+    Ok(())
+}
+
+// Used by the Settings window; accepts the API base URL or the full keywords URL
+#[tauri::command]
+fn save_settings_api_url(app: AppHandle, url: String) -> AppResult<()> {
+    log::info!("save_settings_api_url called: {}", url);
+    let mut new_settings = settings::current(&app);
+    let url = url.trim();
+    new_settings.api_base_url = if url.is_empty() {
+        settings::BackendSettings::default().api_base_url
+    } else {
+        url.strip_suffix(new_settings.keywords_endpoint.as_str())
+            .unwrap_or(url)
+            .to_string()
+    };
+    settings::save(&app, new_settings).map_err(|e| {
+        log::error!("Failed to save API URL: {}", e);
+        e
+    })
+}
+
+// Keywords held by the store, read from the cache if the loader has not stored any yet
+fn keyword_store(app: &AppHandle) -> AppResult<Arc<store::KeywordStore>> {
+    let current = store::current(app);
+    if current.is_loaded() {
+        return Ok(current);
+    }
+    let parsed = cache::load_keywords(&app_data_dir(app)?)?;
+    keyword::log_issues("cached keywords.json", &parsed.issues);
+    store::replace(app, parsed.keywords);
+    Ok(store::current(app))
+}
+
+#[tauri::command]
+fn validate_label(app: AppHandle, label: String) -> AppResult<label::LabelReport> {
+    log::info!("validate_label called for: {}", label);
+    let store = keyword_store(&app).map_err(|e| {
+        log::error!("{}", e);
+        e
+    })?;
+    let mut report =
+        label::validate_label_with(label.trim(), store.keywords(), &rules::current(&app));
+    waiver::current(&app).apply(&mut report, waiver::today());
+    log::debug!("Label validation result: {:?}", report.errors);
+    Ok(report)
+}
+
+// Main window: add a label the user settled on to the recent labels in the tray
+#[tauri::command]
+fn remember_label(app: AppHandle, label: String) -> AppResult<()> {
+    log::debug!("remember_label called for: {}", label);
+    let report = validate_label(app.clone(), label)?;
+    tray::record(&app, &report);
+    Ok(())
+}
+
+// Rewrites of an invalid or outdated label, each with its changes and a diff
+#[tauri::command]
+fn fix_label(
+    app: AppHandle,
+    label: String,
+    limit: Option<usize>,
+) -> AppResult<Vec<fix::FixProposal>> {
+    log::info!("fix_label called for: {}", label);
+    let store = keyword_store(&app)?;
+    let proposals = fix::fix_label(
+        &label,
+        store.keywords(),
+        &rules::current(&app),
+        limit.unwrap_or(5).min(50),
+    );
+    log::debug!("fix_label found {} proposals", proposals.len());
+    Ok(proposals)
+}
+
+// Candidate labels for a plain-language description of the signal
+#[tauri::command]
+fn suggest_labels(
+    app: AppHandle,
+    id: String,
+    description: String,
+    extension: Option<String>,
+    limit: Option<usize>,
+) -> AppResult<Vec<suggest::LabelSuggestion>> {
+    log::info!(
+        "suggest_labels called: {} / {} / {:?}",
+        id,
+        description,
+        extension
+    );
+    let id = id.trim();
+    if id.is_empty()
+        || !id.starts_with(|c: char| c.is_ascii_uppercase())
+        || !id.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        return Err(AppError::Validation(format!(
+            "ID prefix '{}' must be alphanumeric and start with an uppercase letter",
+            id
+        )));
+    }
+    let rule_set = rules::current(&app);
+    if !rule_set.allows_id(id) {
+        return Err(AppError::Validation(format!(
+            "ID prefix '{}' is not allowed by rule set '{}'",
+            id, rule_set.name
+        )));
+    }
+    if description.trim().is_empty() {
+        return Err(AppError::Validation("Description is empty".to_string()));
+    }
+    let store = keyword_store(&app)?;
+    let extension = extension
+        .as_deref()
+        .map(str::trim)
+        .filter(|e| !e.is_empty());
+    if let Some(extension) = extension
+        && !store
+            .get_by_abbr(extension)
+            .iter()
+            .any(|k| rule_set.is_extension(k))
+    {
+        return Err(AppError::Validation(format!(
+            "'{}' is not an Extension keyword",
+            extension
+        )));
+    }
+    let suggestions = suggest::suggest_labels(
+        id,
+        &description,
+        extension,
+        store.keywords(),
+        &rule_set,
+        limit.unwrap_or(5).min(50),
+    );
+    log::debug!("suggest_labels found {} candidates", suggestions.len());
+    Ok(suggestions)
+}
+
+// Paged search over abbreviations and long names, `query` holds the expanded search tokens
+#[tauri::command]
+fn search_keywords(
+    app: AppHandle,
+    query: String,
+    page: Option<usize>,
+    page_size: Option<usize>,
+) -> AppResult<store::KeywordPage> {
+    log::info!("search_keywords called: {}", query);
+    let store = keyword_store(&app)?;
+    let result = store.search(
+        &query,
+        page.unwrap_or(0),
+        page_size.unwrap_or(store::DEFAULT_PAGE_SIZE),
+    );
+    log::debug!("search_keywords found {} matches", result.total);
+    Ok(result)
+}
+
+#[tauri::command]
+fn get_keyword_by_abbr(app: AppHandle, abbr_name: String) -> AppResult<Vec<keyword::Keyword>> {
+    log::info!("get_keyword_by_abbr called: {}", abbr_name);
+    let store = keyword_store(&app)?;
+    Ok(store.get_by_abbr(&abbr_name).into_iter().cloned().collect())
+}
+
+#[tauri::command]
+fn list_by_classification(
+    app: AppHandle,
+    classification: String,
+    page: Option<usize>,
+    page_size: Option<usize>,
+) -> AppResult<store::KeywordPage> {
+    log::info!("list_by_classification called: {}", classification);
+    let store = keyword_store(&app)?;
+    Ok(store.list_by_classification(
+        &classification,
+        page.unwrap_or(0),
+        page_size.unwrap_or(store::DEFAULT_PAGE_SIZE),
+    ))
+}
+
+// Load or build the semantic index in the background so the first query is fast
+fn warm_up_semantic_index(app: &AppHandle, keywords: Vec<keyword::Keyword>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(e) = semantic::index(&app, || Ok(keywords)) {
+            error::emit(&app, "semantic-index", &e);
+        }
+    });
+}
+
+// Natural language search over keyword long names and descriptions
+#[tauri::command]
+async fn semantic_search(
+    app: AppHandle,
+    query: String,
+    limit: Option<usize>,
+) -> AppResult<Vec<semantic::SemanticMatch>> {
+    log::info!("semantic_search called: {}", query);
+    let limit = limit.unwrap_or(20).min(200);
+    tauri::async_runtime::spawn_blocking(move || {
+        let index = semantic::index(&app, || Ok(keyword_store(&app)?.keywords().to_vec()))?;
+        Ok(index.search(&query, limit))
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Semantic search failed: {}", e)))?
+}
+
+// Validate every label of A2L/ARXML/text files; without paths a file picker is shown
+#[tauri::command]
+async fn import_label_files(
+    app: AppHandle,
+    paths: Option<Vec<String>>,
+) -> AppResult<Option<batch::BatchReport>> {
+    log::info!("import_label_files called with {:?}", paths);
+    let paths: Vec<PathBuf> = match paths {
+        Some(paths) => paths.into_iter().map(PathBuf::from).collect(),
+        None => {
+            let picked = app
+                .dialog()
+                .file()
+                .set_title("Import labels")
+                .add_filter("Label sources", &["a2l", "arxml", "txt"])
+                .blocking_pick_files();
+            match picked {
+                Some(files) => files
+                    .into_iter()
+                    .filter_map(|file| file.into_path().ok())
+                    .collect(),
+                None => {
+                    log::info!("Label import cancelled");
+                    return Ok(None);
+                }
+            }
+        }
+    };
+
+    let store = keyword_store(&app)?;
+    let rule_set = rules::current(&app);
+    let waivers = waiver::current(&app);
+    let report = tauri::async_runtime::spawn_blocking(move || {
+        let mut labels = Vec::new();
+        for path in &paths {
+            labels.extend(extract::extract_file(path)?);
+        }
+        let files = paths.iter().map(|p| p.display().to_string()).collect();
+        Ok::<_, AppError>(batch::validate_labels(
+            files,
+            labels,
+            store.keywords(),
+            &rule_set,
+            &waivers,
+        ))
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Label import task failed: {}", e)))?
+    .map_err(|e| {
+        log::error!("{}", e);
+        e
+    })?;
+    log::info!(
+        "Imported {} labels: {} valid, {} invalid",
+        report.summary.total,
+        report.summary.valid,
+        report.summary.invalid
+    );
+    *app.state::<report::ReportState>().lock().unwrap() = Some(Arc::new(report.clone()));
+    Ok(Some(report))
+}
+
+// Scan a C/C++ source folder for labels; without a path a folder dialog is shown
+#[tauri::command]
+async fn scan_source_folder(
+    app: AppHandle,
+    path: Option<String>,
+) -> AppResult<Option<batch::BatchReport>> {
+    log::info!("scan_source_folder called with {:?}", path);
+    let root = match path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let picked = app
+                .dialog()
+                .file()
+                .set_title("Scan C/C++ sources")
+                .blocking_pick_folder();
+            match picked.and_then(|folder| folder.into_path().ok()) {
+                Some(folder) => folder,
+                None => {
+                    log::info!("Source scan cancelled");
+                    return Ok(None);
+                }
+            }
+        }
+    };
+
+    let store = keyword_store(&app)?;
+    let rule_set = rules::current(&app);
+    let waivers = waiver::current(&app);
+    let cache_dir = app_data_dir(&app)?.join(scan::CACHE_DIR);
+    let report = tauri::async_runtime::spawn_blocking(move || {
+        let config = scan::config_for(&root)?;
+        let scanned = scan::scan(&root, &config, Some(&cache_dir), false)?;
+        Ok::<_, AppError>(batch::validate_labels(
+            vec![root.display().to_string()],
+            scanned.labels,
+            store.keywords(),
+            &rule_set,
+            &waivers,
+        ))
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Source scan task failed: {}", e)))?
+    .map_err(|e| {
+        log::error!("{}", e);
+        e
+    })?;
+    log::info!(
+        "Scanned {} labels: {} valid, {} invalid",
+        report.summary.total,
+        report.summary.valid,
+        report.summary.invalid
+    );
+    *app.state::<report::ReportState>().lock().unwrap() = Some(Arc::new(report.clone()));
+    Ok(Some(report))
+}
+
+// Write the last imported report as JSON, SARIF or JUnit XML; without a path a save dialog is shown
+#[tauri::command]
+async fn export_report(
+    app: AppHandle,
+    format: String,
+    path: Option<String>,
+) -> AppResult<Option<String>> {
+    log::info!("export_report called: {} {:?}", format, path);
+    let format = report::ReportFormat::parse(&format).map_err(AppError::Validation)?;
+    let last = app
+        .state::<report::ReportState>()
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| {
+            AppError::Validation(
+                "There is no report to export, import label files first".to_string(),
+            )
+        })?;
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let picked = app
+                .dialog()
+                .file()
+                .set_title("Export report")
+                .set_file_name(format!("label-report.{}", format.extension()))
+                .add_filter("Report", &[format.extension()])
+                .blocking_save_file();
+            match picked.and_then(|file| file.into_path().ok()) {
+                Some(path) => path,
+                None => {
+                    log::info!("Report export cancelled");
+                    return Ok(None);
+                }
+            }
+        }
+    };
+    let rendered = report::render(&last, format).map_err(AppError::Internal)?;
+    fs::write(&path, rendered)
+        .map_err(|e| AppError::CacheIo(format!("Failed to write {}: {}", path.display(), e)))?;
+    log::info!("Exported report to {}", path.display());
+    Ok(Some(path.display().to_string()))
+}
+
+// Snapshots of the keyword database, newest first
+#[tauri::command]
+fn list_keyword_snapshots(app: AppHandle) -> AppResult<Vec<history::Snapshot>> {
+    log::info!("list_keyword_snapshots called");
+    let mut snapshots = history::list(&app_data_dir(&app)?)?;
+    snapshots.reverse();
+    Ok(snapshots)
+}
+
+// Keyword changes between two snapshots, by default the latest update
+#[tauri::command]
+fn diff_keyword_snapshots(
+    app: AppHandle,
+    from: Option<String>,
+    to: Option<String>,
+) -> AppResult<Option<history::KeywordDiff>> {
+    log::info!("diff_keyword_snapshots called: {:?} {:?}", from, to);
+    history::diff(&app_data_dir(&app)?, from.as_deref(), to.as_deref())
+}
+
+#[tauri::command]
+async fn export_keyword_changes(
+    app: AppHandle,
+    from: Option<String>,
+    to: Option<String>,
+    format: String,
+    path: Option<String>,
+) -> AppResult<Option<String>> {
+    log::info!("export_keyword_changes called: {} {:?}", format, path);
+    let format = history::ChangelogFormat::parse(&format).map_err(AppError::Validation)?;
+    let diff =
+        history::diff(&app_data_dir(&app)?, from.as_deref(), to.as_deref())?.ok_or_else(|| {
+            AppError::Validation(
+                "There are no keyword changes yet, the keyword database was fetched only once"
+                    .to_string(),
+            )
+        })?;
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let picked = app
+                .dialog()
+                .file()
+                .set_title("Export keyword changes")
+                .set_file_name(format!("keyword-changes.{}", format.extension()))
+                .add_filter("Changelog", &[format.extension()])
+                .blocking_save_file();
+            match picked.and_then(|file| file.into_path().ok()) {
+                Some(path) => path,
+                None => {
+                    log::info!("Keyword changes export cancelled");
+                    return Ok(None);
+                }
+            }
+        }
+    };
+    fs::write(&path, history::render(&diff, format))
+        .map_err(|e| AppError::CacheIo(format!("Failed to write {}: {}", path.display(), e)))?;
+    log::info!("Exported keyword changes to {}", path.display());
+    Ok(Some(path.display().to_string()))
+}
+
+#[tauri::command]
+fn list_rule_sets(app: AppHandle) -> AppResult<Vec<rules::RuleSetInfo>> {
+    log::info!("list_rule_sets called");
+    rules::list(&app)
+}
+
+// `file` is a file name from `list_rule_sets`, `None` selects the built-in rules
+#[tauri::command]
+fn set_active_rule_set(app: AppHandle, file: Option<String>) -> AppResult<rules::RuleSetInfo> {
+    log::info!("set_active_rule_set called: {:?}", file);
+    rules::activate(&app, file).map_err(|e| {
+        log::error!("Failed to activate rule set: {}", e);
+        e
+    })
+}
+
+// Copy a rule set file into the rules folder; without a path a file picker is shown
+#[tauri::command]
+async fn import_rule_set(app: AppHandle, path: Option<String>) -> AppResult<Option<String>> {
+    log::info!("import_rule_set called with {:?}", path);
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let picked = app
+                .dialog()
+                .file()
+                .set_title("Import rule set")
+                .add_filter("Rule sets", &["toml", "json"])
+                .blocking_pick_file();
+            match picked.and_then(|file| file.into_path().ok()) {
+                Some(path) => path,
+                None => {
+                    log::info!("Rule set import cancelled");
+                    return Ok(None);
+                }
+            }
+        }
+    };
+    rules::import(&app, &path).map(Some)
+}
+
+#[tauri::command]
+fn get_waiver_file(app: AppHandle) -> AppResult<Option<waiver::WaiverFileInfo>> {
+    log::info!("get_waiver_file called");
+    Ok(waiver::info(&app))
+}
+
+// Use a waiver file in place; without a path a file picker is shown. `clear` drops the waivers
+#[tauri::command]
+async fn set_waiver_file(
+    app: AppHandle,
+    path: Option<String>,
+    clear: Option<bool>,
+) -> AppResult<Option<waiver::WaiverFileInfo>> {
+    log::info!("set_waiver_file called with {:?}", path);
+    if clear.unwrap_or(false) {
+        return waiver::activate(&app, None);
+    }
+    let path = match path {
+        Some(path) => path,
+        None => {
+            let picked = app
+                .dialog()
+                .file()
+                .set_title("Select waiver file")
+                .add_filter("Waiver files", &["toml", "json"])
+                .blocking_pick_file();
+            match picked.and_then(|file| file.into_path().ok()) {
+                Some(path) => path.display().to_string(),
+                None => {
+                    log::info!("Waiver file selection cancelled");
+                    return Ok(None);
+                }
+            }
+        }
+    };
+    waiver::activate(&app, Some(path)).map_err(|e| {
+        log::error!("Failed to use waiver file: {}", e);
+        e
+    })
+}
+
+// Label or keyword from a link or the command line, taken once by the main window
+#[tauri::command]
+fn take_open_request(app: AppHandle) -> AppResult<Option<launch::OpenRequest>> {
+    let request = launch::take(&app);
+    log::info!("take_open_request called, returning {:?}", request);
+    Ok(request)
+}
+
+#[tauri::command]
+fn get_local_api(app: AppHandle) -> AppResult<api::ApiInfo> {
+    log::info!("get_local_api called");
+    Ok(api::info(&app))
+}
+
+// Enable or disable the local API; `port` moves it, `new_token` invalidates the old token
+#[tauri::command]
+fn set_local_api(
+    app: AppHandle,
+    enabled: bool,
+    port: Option<u16>,
+    new_token: Option<bool>,
+) -> AppResult<api::ApiInfo> {
+    log::info!(
+        "set_local_api called with enabled={}, port={:?}",
+        enabled,
+        port
+    );
+    api::configure(&app, enabled, port, new_token.unwrap_or(false)).map_err(|e| {
+        log::error!("Failed to configure local API: {}", e);
+        e
+    })
+}
+
 // Removed deprecated function since we're using hardcoded values now
 
 fn main() {
+    // Command line mode (e.g. `validate labels.txt`) runs without starting the GUI
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
     log::info!("Application starting up");
+    log::info!(
+        "Using {:?} environment configuration",
+        config::get_config().environment
+    );
     tauri::Builder::default()
-        .plugin(tauri_plugin_log::Builder::new().build())
+        // Must be the first plugin, so a second instance exits before any other plugin starts
         .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
             log::info!("Another application instance detected");
             log::debug!("New instance arguments: {:?}", args);
-            match app.get_webview_window("main") {
-                Some(window) => {
-                    log::info!("Focusing existing main window");
-                    if let Err(e) = window.set_focus() {
-                        log::error!("Failed to set focus on main window: {}", e);
-                    }
-                }
-                None => {
-                    log::error!("Failed to find main window to focus");
-                }
+            // Links start a second instance with the URL as argument, after the program name
+            match launch::from_args(args.get(1..).unwrap_or_default()) {
+                Some(request) => open_in_main_window(app, request),
+                None => raise_main_window(app),
             }
         }))
+        .plugin(tauri_plugin_log::Builder::new().build())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(
             tauri_plugin_log::Builder::new()
                 .targets([
@@ -572,12 +1177,17 @@ fn main() {
                     }),
                     tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Webview),
                 ])
-                .level(log::LevelFilter::Trace)
+                .level(
+                    config::get_config()
+                        .log_level_filter()
+                        .unwrap_or(log::LevelFilter::Trace),
+                )
                 .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepAll)
                 .timezone_strategy(tauri_plugin_log::TimezoneStrategy::UseLocal)
                 .max_file_size(1 /* MB */ * 1024 * 1024)
                 .build(),
         )
+        .manage(launch::PendingOpen::new(launch::from_args(&args)))
         .manage(Arc::new(Mutex::new(SetupState {
             frontend_task: false,
             backend_task: false,
@@ -589,11 +1199,56 @@ fn main() {
             get_tauri_config_urls,
             get_app_config_urls,
             get_log_file_path,
-            get_version_info
+            get_version_info,
+            validate_label,
+            remember_label,
+            suggest_labels,
+            fix_label,
+            import_label_files,
+            scan_source_folder,
+            list_rule_sets,
+            set_active_rule_set,
+            import_rule_set,
+            get_waiver_file,
+            set_waiver_file,
+            get_local_api,
+            set_local_api,
+            take_open_request,
+            export_report,
+            list_keyword_snapshots,
+            diff_keyword_snapshots,
+            export_keyword_changes,
+            get_backend_settings,
+            save_backend_settings,
+            save_settings_api_url,
+            get_app_config,
+            semantic_search,
+            search_keywords,
+            get_keyword_by_abbr,
+            list_by_classification
         ])
         .setup(|app| {
             log::info!("Setting up application UI components");
 
+            // Installers register the link scheme, development builds do it on start
+            #[cfg(debug_assertions)]
+            if let Err(e) = app.deep_link().register_all() {
+                log::error!("Failed to register {} links: {}", launch::SCHEME, e);
+            }
+            // macOS delivers links to the running app as an event instead of a second
+            // instance. Windows and Linux pass them as arguments of a second instance,
+            // handled by the single instance plugin above; its `deep-link` feature stays
+            // off so those links are not opened twice.
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    match launch::parse_url(url.as_str()) {
+                        Ok(request) => open_in_main_window(&handle, request),
+                        Err(e) => log::warn!("Ignoring link {}: {}", url, e),
+                    }
+                }
+            });
+
             // Create logs directory if it doesn't exist
             if let Ok(config_dir) = app.path().config_dir() {
                 let log_dir = config_dir.join("logs");
@@ -608,18 +1263,16 @@ fn main() {
                 log::warn!("Unable to determine config directory for log files");
             }
 
-            let quit = MenuItemBuilder::new("Quit").id("quit").build(app).unwrap();
-            let settings = MenuItemBuilder::new("Settings")
-                .id("settings")
-                .build(app)
-                .unwrap();
-            let menu = MenuBuilder::new(app)
-                .items(&[&quit, &settings])
-                .build()
-                .unwrap();
+            app.manage(Mutex::new(settings::load(app.handle())));
+            app.manage(tray::TrayState::default());
+            let menu = tray::menu(app.handle())?;
+            let icon = app
+                .default_window_icon()
+                .cloned()
+                .ok_or_else(|| AppError::Window("No default window icon".to_string()))?;
             log::info!("Creating system tray icon and menu");
-            let _ = TrayIconBuilder::new()
-                .icon(app.default_window_icon().unwrap().clone())
+            let _ = TrayIconBuilder::with_id(tray::TRAY_ID)
+                .icon(icon)
                 .menu(&menu)
                 .on_menu_event(|app, event| match event.id().as_ref() {
                     "quit" => {
@@ -670,6 +1323,17 @@ fn main() {
                             }
                         }
                     }
+                    tray::REFRESH_ID => {
+                        log::info!("Keyword refresh requested from tray");
+                        reload_keywords(app, true);
+                    }
+                    tray::OFFLINE_ID => set_work_offline(app, !settings::current(app).work_offline),
+                    id if id.starts_with(tray::RECENT_PREFIX) => {
+                        if let Some(label) = tray::recent_label(app, id) {
+                            log::info!("Recent label {} clicked from tray", label);
+                            open_in_main_window(app, launch::OpenRequest::Validate(label));
+                        }
+                    }
                     _ => {}
                 })
                 .on_tray_icon_event(|tray_icon, event| match event {
@@ -684,9 +1348,39 @@ fn main() {
                             }
                         }
                     }
+                    // Keep the cache age in the menu current
+                    tauri::tray::TrayIconEvent::Enter { .. } => {
+                        tray::refresh(tray_icon.app_handle())
+                    }
                     _ => {}
                 })
                 .build(app);
+            if let Some(version) = cached_version_info(app.handle()) {
+                tray::set_db_version(app.handle(), &version);
+            }
+
+            app.manage(semantic::SemanticState::default());
+            app.manage(store::KeywordState::default());
+            app.manage(report::ReportState::default());
+            let rule_set = rules::load(app.handle()).unwrap_or_else(|e| {
+                log::error!("{}, using the built-in rules", e);
+                rules::RuleSet::default()
+            });
+            app.manage(rules::RuleState::new(Arc::new(rule_set)));
+            let waivers = waiver::load(app.handle()).unwrap_or_else(|e| {
+                log::error!("{}, continuing without waivers", e);
+                waiver::WaiverSet::default()
+            });
+            app.manage(waiver::WaiverState::new(Arc::new(waivers)));
+            app.manage(api::ApiState::default());
+            if let Err(e) = api::restart(app.handle()) {
+                error::emit(app.handle(), "local-api", &e);
+            }
+            if let Err(e) = hotkey::register(app.handle(), validate_clipboard) {
+                error::emit(app.handle(), "hotkey", &e);
+            }
+            app.manage(watch::WatchState::default());
+            watch::restart(app.handle(), check_clipboard_label);
 
             log::info!("Starting backend initialization process");
             let app_handle = app.handle().clone();
@@ -706,9 +1400,17 @@ fn main() {
             );
 
             tauri::async_runtime::spawn(async move {
-                match get_version_info().await {
+                let version = if settings::current(&app_clone).work_offline {
+                    cached_version_info(&app_clone).ok_or_else(|| {
+                        AppError::CacheIo("Cached keywords have no version information".to_string())
+                    })
+                } else {
+                    get_version_info(app_clone.clone()).await
+                };
+                match version {
                     Ok(date) => {
                         log::info!("BBM Keywords updated date: {}", date);
+                        tray::set_db_version(&app_clone, &date);
                         // Emit an event to the frontend to display the date
                         app_clone.emit("version-info", date).ok();
                     }
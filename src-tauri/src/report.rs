@@ -0,0 +1,593 @@
+// Machine-readable batch reports for CI dashboards
+//
+// The same `BatchReport` is written as JSON (everything, including the per-part rows),
+// SARIF 2.1.0 (one result per error or warning, located at the label's file and line;
+// waived findings carry a suppression) or JUnit XML (one test case per label, one test
+// suite per file).
+use crate::batch::{BatchReport, LabelResult};
+use crate::label::LabelError;
+use crate::waiver::Waiver;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+const TOOL_NAME: &str = "BBM Label Explorer";
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+/// Test suite name for labels read from stdin.
+const NO_FILE: &str = "stdin";
+
+/// Last imported report, kept for the GUI's "Export report".
+pub type ReportState = Mutex<Option<Arc<BatchReport>>>;
+
+#[derive(Debug, Clone, Serialize)]
+struct Tool {
+    name: &'static str,
+    version: &'static str,
+}
+
+const TOOL: Tool = Tool {
+    name: TOOL_NAME,
+    version: env!("CARGO_PKG_VERSION"),
+};
+
+/// Document written for [`ReportFormat::Json`].
+#[derive(Debug, Clone, Serialize)]
+struct JsonReport<'a> {
+    tool: Tool,
+    report: &'a BatchReport,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Sarif,
+    Junit,
+}
+
+impl ReportFormat {
+    pub fn parse(name: &str) -> Result<ReportFormat, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Ok(ReportFormat::Json),
+            "sarif" => Ok(ReportFormat::Sarif),
+            "junit" | "xml" => Ok(ReportFormat::Junit),
+            _ => Err(format!(
+                "Unknown report format '{}', expected json, sarif or junit",
+                name
+            )),
+        }
+    }
+
+    /// Format matching a file name, e.g. `report.sarif`.
+    pub fn from_path(path: &Path) -> Option<ReportFormat> {
+        let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+        if name.ends_with(".sarif") || name.ends_with(".sarif.json") {
+            Some(ReportFormat::Sarif)
+        } else if name.ends_with(".xml") {
+            Some(ReportFormat::Junit)
+        } else if name.ends_with(".json") {
+            Some(ReportFormat::Json)
+        } else {
+            None
+        }
+    }
+
+    /// File extension used when saving the report.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Json => "json",
+            ReportFormat::Sarif => "sarif",
+            ReportFormat::Junit => "xml",
+        }
+    }
+}
+
+/// Render `report` in the given format.
+pub fn render(report: &BatchReport, format: ReportFormat) -> Result<String, String> {
+    match format {
+        ReportFormat::Json => serde_json::to_string_pretty(&JsonReport { tool: TOOL, report })
+            .map_err(|e| format!("Failed to serialize report: {}", e)),
+        ReportFormat::Sarif => serde_json::to_string_pretty(&sarif(report))
+            .map_err(|e| format!("Failed to serialize SARIF report: {}", e)),
+        ReportFormat::Junit => Ok(junit(report)),
+    }
+}
+
+fn sarif(report: &BatchReport) -> serde_json::Value {
+    // Rules are listed once each, results refer to them by index
+    let mut rules: BTreeMap<LabelError, usize> = BTreeMap::new();
+    for result in &report.results {
+        let waived = result.report.waived.iter().map(|w| &w.error);
+        for error in result
+            .report
+            .errors
+            .iter()
+            .chain(&result.report.warnings)
+            .chain(waived)
+        {
+            rules.entry(*error).or_default();
+        }
+    }
+    for (index, position) in rules.values_mut().enumerate() {
+        *position = index;
+    }
+
+    let mut results = Vec::new();
+    for result in &report.results {
+        let findings = result
+            .report
+            .errors
+            .iter()
+            .map(|e| (e, "error", None))
+            .chain(result.report.warnings.iter().map(|w| (w, "warning", None)))
+            .chain(result.report.waived.iter().map(|w| {
+                let level = if w.was_error { "error" } else { "warning" };
+                (&w.error, level, Some(w))
+            }));
+        for (error, level, waived) in findings {
+            let mut location = json!({
+                "logicalLocations": [{ "name": result.source.name, "kind": result.source.kind }],
+            });
+            if let Some(file) = &result.source.file {
+                location["physicalLocation"] = json!({
+                    "artifactLocation": { "uri": file_uri(file) },
+                    "region": { "startLine": result.source.line },
+                });
+            }
+            let mut sarif_result = json!({
+                "ruleId": error.rule(),
+                "ruleIndex": rules[error],
+                "level": level,
+                "message": { "text": format!("{}: {}", result.source.name, error) },
+                "locations": [location],
+            });
+            if let Some(waived) = waived {
+                sarif_result["suppressions"] = json!([{
+                    "kind": "external",
+                    "status": "accepted",
+                    "justification": format!("{} ({})", waived.justification, waived.author),
+                }]);
+            }
+            results.push(sarif_result);
+        }
+    }
+
+    let rules: Vec<serde_json::Value> = rules
+        .keys()
+        .map(|error| {
+            json!({
+                "id": error.rule(),
+                "shortDescription": { "text": error.to_string() },
+            })
+        })
+        .collect();
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL.name,
+                    "version": TOOL.version,
+                    "rules": rules,
+                },
+            },
+            "properties": {
+                "ruleSet": report.rule_set,
+                "waivers": report.waivers,
+            },
+            "results": results,
+        }],
+    })
+}
+
+// SARIF wants URIs: relative paths keep forward slashes, absolute ones become file URIs
+fn file_uri(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    if path.starts_with('/') {
+        format!("file://{}", path)
+    } else if path.as_bytes().get(1) == Some(&b':') {
+        format!("file:///{}", path)
+    } else {
+        path
+    }
+    .replace(' ', "%20")
+}
+
+fn junit(report: &BatchReport) -> String {
+    // One suite per file, in the order the files were given
+    let mut suites: Vec<(&str, Vec<&LabelResult>)> = Vec::new();
+    for result in &report.results {
+        let file = result.source.file.as_deref().unwrap_or(NO_FILE);
+        match suites.iter_mut().find(|(name, _)| *name == file) {
+            Some((_, results)) => results.push(result),
+            None => suites.push((file, vec![result])),
+        }
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\">",
+        TOOL_NAME,
+        report.summary.total + report.waivers.expired.len() + report.waivers.unused.len(),
+        report.summary.invalid
+    );
+    for (file, results) in suites {
+        let failures = results.iter().filter(|r| !r.report.valid).count();
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\">",
+            escape(file),
+            results.len(),
+            failures
+        );
+        let _ = writeln!(
+            xml,
+            "    <properties>\n      <property name=\"ruleSet\" value=\"{}\"/>\n    </properties>",
+            escape(&report.rule_set)
+        );
+        for result in results {
+            let _ = write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\"",
+                escape(&result.source.name),
+                escape(&result.source.kind)
+            );
+            if let Some(file) = &result.source.file {
+                let _ = write!(
+                    xml,
+                    " file=\"{}\" line=\"{}\"",
+                    escape(file),
+                    result.source.line
+                );
+            }
+            let label = &result.report;
+            if label.valid && label.warnings.is_empty() && label.waived.is_empty() {
+                xml.push_str("/>\n");
+                continue;
+            }
+            xml.push_str(">\n");
+            if let Some(first) = label.errors.first() {
+                let details: Vec<String> = label.errors.iter().map(|e| e.to_string()).collect();
+                let _ = writeln!(
+                    xml,
+                    "      <failure message=\"{}\" type=\"{}\">{}</failure>",
+                    escape(&first.to_string()),
+                    first.rule(),
+                    escape(&details.join("\n"))
+                );
+            }
+            if !label.warnings.is_empty() || !label.waived.is_empty() {
+                let details: Vec<String> = label
+                    .warnings
+                    .iter()
+                    .map(|w| format!("warning: {}", w))
+                    .chain(label.waived.iter().map(|w| {
+                        format!("waived: {} ({}, {})", w.error, w.justification, w.author)
+                    }))
+                    .collect();
+                let _ = writeln!(
+                    xml,
+                    "      <system-out>{}</system-out>",
+                    escape(&details.join("\n"))
+                );
+            }
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    junit_waivers(&mut xml, report);
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+// Expired and unused waivers as skipped test cases, so they show up without failing the build
+fn junit_waivers(xml: &mut String, report: &BatchReport) {
+    let waivers = &report.waivers;
+    let issues: Vec<(&Waiver, String)> = waivers
+        .expired
+        .iter()
+        .map(|w| {
+            let expires = w.expires.as_deref().unwrap_or_default();
+            (w, format!("Waiver expired on {}", expires))
+        })
+        .chain(
+            waivers
+                .unused
+                .iter()
+                .map(|w| (w, "Waiver matches no finding".to_string())),
+        )
+        .collect();
+    if issues.is_empty() {
+        return;
+    }
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"0\" errors=\"0\" skipped=\"{}\">",
+        escape(waivers.file.as_deref().unwrap_or("waivers")),
+        issues.len(),
+        issues.len()
+    );
+    for (waiver, message) in issues {
+        let _ = writeln!(
+            xml,
+            "    <testcase name=\"{} {}\" classname=\"waiver\">\n      <skipped message=\"{}\"/>\n    </testcase>",
+            escape(&waiver.label),
+            escape(&waiver.rule),
+            escape(&message)
+        );
+    }
+    xml.push_str("  </testsuite>\n");
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::BatchSummary;
+    use crate::extract::ExtractedLabel;
+    use crate::label::LabelReport;
+    use crate::waiver::{WaivedFinding, WaiverSummary};
+
+    fn result(
+        name: &str,
+        file: Option<&str>,
+        errors: Vec<LabelError>,
+        warnings: Vec<LabelError>,
+        waived: Vec<WaivedFinding>,
+    ) -> LabelResult {
+        let mut report = LabelReport {
+            label: name.to_string(),
+            valid: false,
+            errors,
+            warnings,
+            waived,
+            rows: Vec::new(),
+            message: String::new(),
+            color: String::new(),
+            consolidated_messages: Vec::new(),
+            life_cycle_state: None,
+        };
+        report.update_verdict();
+        LabelResult {
+            source: ExtractedLabel {
+                name: name.to_string(),
+                kind: "MEASUREMENT".to_string(),
+                file: file.map(str::to_string),
+                line: 12,
+            },
+            report,
+        }
+    }
+
+    fn waived(error: LabelError, was_error: bool) -> WaivedFinding {
+        WaivedFinding {
+            error,
+            was_error,
+            justification: "Frozen by the customer interface specification".to_string(),
+            author: "J. Doe".to_string(),
+            expires: None,
+        }
+    }
+
+    fn waiver(label: &str, expires: Option<&str>) -> Waiver {
+        Waiver {
+            label: label.to_string(),
+            rule: "*".to_string(),
+            justification: "Legacy interface".to_string(),
+            author: "J. Doe".to_string(),
+            expires: expires.map(str::to_string),
+        }
+    }
+
+    fn batch(results: Vec<LabelResult>, waivers: WaiverSummary) -> BatchReport {
+        let invalid = results.iter().filter(|r| !r.report.valid).count();
+        BatchReport {
+            files: Vec::new(),
+            rule_set: "default".to_string(),
+            summary: BatchSummary {
+                total: results.len(),
+                valid: results.len() - invalid,
+                invalid,
+                ..BatchSummary::default()
+            },
+            results,
+            waivers,
+        }
+    }
+
+    fn sarif_results(report: &serde_json::Value) -> &Vec<serde_json::Value> {
+        report["runs"][0]["results"].as_array().unwrap()
+    }
+
+    #[test]
+    fn sarif_lists_each_rule_once_and_refers_to_it_by_index() {
+        let report = sarif(&batch(
+            vec![
+                result(
+                    "RB_Max",
+                    Some("a.a2l"),
+                    vec![LabelError::PhysicalMissing],
+                    vec![],
+                    vec![],
+                ),
+                result(
+                    "RB_Min",
+                    Some("a.a2l"),
+                    vec![LabelError::PhysicalMissing],
+                    vec![LabelError::TooLong(8)],
+                    vec![],
+                ),
+            ],
+            WaiverSummary::default(),
+        ));
+        let rules: Vec<&str> = report["runs"][0]["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|rule| rule["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(rules, ["physicalMissing", "tooLong"]);
+        let results: Vec<(&str, u64, &str)> = sarif_results(&report)
+            .iter()
+            .map(|r| {
+                (
+                    r["ruleId"].as_str().unwrap(),
+                    r["ruleIndex"].as_u64().unwrap(),
+                    r["level"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            results,
+            [
+                ("physicalMissing", 0, "error"),
+                ("physicalMissing", 0, "error"),
+                ("tooLong", 1, "warning"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sarif_suppresses_waived_findings() {
+        let report = sarif(&batch(
+            vec![result(
+                "RB_Max",
+                Some("a.a2l"),
+                vec![LabelError::DescriptiveMissing],
+                vec![],
+                vec![waived(LabelError::PhysicalMissing, true)],
+            )],
+            WaiverSummary::default(),
+        ));
+        let results = sarif_results(&report);
+        assert_eq!(results.len(), 2);
+        assert!(results[0].get("suppressions").is_none());
+        assert_eq!(results[1]["ruleId"], "physicalMissing");
+        assert_eq!(results[1]["level"], "error");
+        assert_eq!(
+            results[1]["suppressions"],
+            json!([{
+                "kind": "external",
+                "status": "accepted",
+                "justification": "Frozen by the customer interface specification (J. Doe)",
+            }])
+        );
+    }
+
+    #[test]
+    fn sarif_locates_labels_in_files_and_from_stdin() {
+        let report = sarif(&batch(
+            vec![
+                result(
+                    "RB_Max",
+                    Some("src\\a b.a2l"),
+                    vec![LabelError::PhysicalMissing],
+                    vec![],
+                    vec![],
+                ),
+                result(
+                    "RB_Min",
+                    None,
+                    vec![LabelError::PhysicalMissing],
+                    vec![],
+                    vec![],
+                ),
+            ],
+            WaiverSummary::default(),
+        ));
+        let results = sarif_results(&report);
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"],
+            json!({
+                "artifactLocation": { "uri": "src/a%20b.a2l" },
+                "region": { "startLine": 12 },
+            })
+        );
+        let stdin = &results[1]["locations"][0];
+        assert!(stdin.get("physicalLocation").is_none());
+        assert_eq!(
+            stdin["logicalLocations"],
+            json!([{ "name": "RB_Min", "kind": "MEASUREMENT" }])
+        );
+    }
+
+    #[test]
+    fn file_uris() {
+        assert_eq!(file_uri("src/a.a2l"), "src/a.a2l");
+        assert_eq!(file_uri("src\\a.a2l"), "src/a.a2l");
+        assert_eq!(file_uri("/home/me/a.a2l"), "file:///home/me/a.a2l");
+        assert_eq!(file_uri("C:\\Data\\My a.a2l"), "file:///C:/Data/My%20a.a2l");
+    }
+
+    #[test]
+    fn escapes_xml() {
+        assert_eq!(
+            escape(r#"<a href="x">Tom & Jerry's</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&apos;s&lt;/a&gt;"
+        );
+        assert_eq!(escape("RB_tMaxLim_C"), "RB_tMaxLim_C");
+    }
+
+    #[test]
+    fn junit_counts_tests_failures_and_skipped_waivers() {
+        let xml = junit(&batch(
+            vec![
+                result(
+                    "RB_Max",
+                    Some("a.a2l"),
+                    vec![LabelError::PhysicalMissing],
+                    vec![],
+                    vec![],
+                ),
+                result("RB_tMax", Some("a.a2l"), vec![], vec![], vec![]),
+                result("x<y", None, vec![], vec![LabelError::TooLong(8)], vec![]),
+            ],
+            WaiverSummary {
+                file: Some("waivers.toml".to_string()),
+                waived: 0,
+                expired: vec![waiver("RB_Old*", Some("2024-01-31"))],
+                unused: vec![waiver("RB_Gone", None)],
+            },
+        ));
+        assert!(
+            xml.contains("<testsuites name=\"BBM Label Explorer\" tests=\"5\" failures=\"1\">")
+        );
+        assert!(xml.contains(
+            "<testsuite name=\"a.a2l\" tests=\"2\" failures=\"1\" errors=\"0\" skipped=\"0\">"
+        ));
+        assert!(xml.contains(
+            "<testsuite name=\"stdin\" tests=\"1\" failures=\"0\" errors=\"0\" skipped=\"0\">"
+        ));
+        assert!(xml.contains(
+            "<testsuite name=\"waivers.toml\" tests=\"2\" failures=\"0\" errors=\"0\" skipped=\"2\">"
+        ));
+        assert!(xml.contains(
+            "<failure message=\"Physical part &lt;pp&gt; is missing\" type=\"physicalMissing\">"
+        ));
+        assert!(xml.contains(
+            "<testcase name=\"RB_tMax\" classname=\"MEASUREMENT\" file=\"a.a2l\" line=\"12\"/>"
+        ));
+        assert!(xml.contains("<testcase name=\"x&lt;y\" classname=\"MEASUREMENT\">"));
+        assert!(xml.contains("<skipped message=\"Waiver expired on 2024-01-31\"/>"));
+        assert!(xml.contains("<skipped message=\"Waiver matches no finding\"/>"));
+        assert_eq!(xml.matches("<testcase ").count(), 5);
+    }
+}
@@ -0,0 +1,514 @@
+// Validation rule sets
+//
+// Projects differ in label length, ID conventions and which `rbClassifications` count
+// as Physical or Extension. A rule set describes these in a TOML or JSON file in the
+// `rules` folder of the app config directory; the active one is named in the backend
+// settings. Without a rule set the built-in rules (27 characters, any ID) apply.
+use crate::config_file;
+use crate::error::{AppError, AppResult};
+use crate::keyword::Keyword;
+use crate::label::{self, LabelError};
+use crate::settings;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+const RULES_DIR: &str = "rules";
+
+/// How a rule violation is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    /// The label is invalid.
+    Error,
+    /// Reported, the label stays valid.
+    Warning,
+    /// Not reported.
+    Off,
+}
+
+/// Parts a label must have besides the ID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct RequiredParts {
+    pub physical: bool,
+    pub descriptive: bool,
+    pub extension: bool,
+}
+
+impl Default for RequiredParts {
+    fn default() -> Self {
+        RequiredParts {
+            physical: true,
+            descriptive: true,
+            extension: false,
+        }
+    }
+}
+
+/// Which `rbClassifications` a keyword needs for each part of the label.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct Classifications {
+    /// A `<pp>` keyword needs one of these. Suggestions use them in this order.
+    pub physical: Vec<String>,
+    /// An `<Ex>` keyword must have only these.
+    pub extension: Vec<String>,
+    /// Keywords with one of these are not allowed in the descriptive name.
+    pub excluded_from_descriptive: Vec<String>,
+}
+
+impl Default for Classifications {
+    fn default() -> Self {
+        Classifications {
+            physical: vec!["Physical".to_string(), "Logical".to_string()],
+            extension: vec!["Extension".to_string()],
+            excluded_from_descriptive: vec!["Physical".to_string(), "Extension".to_string()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct RuleSet {
+    /// Shown in Settings, files without one are named after the file.
+    #[serde(default)]
+    pub name: String,
+    pub max_length: usize,
+    /// Allowed `<Id>` values, a trailing `*` matches any continuation. Empty allows any ID.
+    pub id_prefixes: Vec<String>,
+    pub required_parts: RequiredParts,
+    pub classifications: Classifications,
+    /// Severity per rule, keyed by [`LabelError::rule`]. Rules not listed are errors.
+    pub severity: BTreeMap<String, Severity>,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet {
+            name: "Default".to_string(),
+            max_length: label::MAX_LABEL_LENGTH,
+            id_prefixes: Vec::new(),
+            required_parts: RequiredParts::default(),
+            classifications: Classifications::default(),
+            severity: BTreeMap::new(),
+        }
+    }
+}
+
+/// A rule set file offered in Settings.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleSetInfo {
+    /// File name in the rules folder, `None` for the built-in rules.
+    pub file: Option<String>,
+    pub name: String,
+    pub max_length: usize,
+    pub active: bool,
+    /// Why the file cannot be used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RuleSet {
+    pub fn is_physical(&self, keyword: &Keyword) -> bool {
+        self.classifications
+            .physical
+            .iter()
+            .any(|c| keyword.has_classification(c))
+    }
+
+    /// Generalizes [`Keyword::is_extension_only`] to the configured classifications.
+    pub fn is_extension(&self, keyword: &Keyword) -> bool {
+        !keyword.rb_classifications.is_empty()
+            && keyword.rb_classifications.iter().all(|c| {
+                self.classifications
+                    .extension
+                    .iter()
+                    .any(|e| e.eq_ignore_ascii_case(c))
+            })
+    }
+
+    pub fn is_excluded_from_descriptive(&self, keyword: &Keyword) -> bool {
+        self.classifications
+            .excluded_from_descriptive
+            .iter()
+            .any(|c| keyword.has_classification(c))
+    }
+
+    /// Whether the keyword may appear in the descriptive name.
+    pub fn is_descriptive(&self, keyword: &Keyword) -> bool {
+        !self.is_extension(keyword) && !self.is_excluded_from_descriptive(keyword)
+    }
+
+    pub fn allows_id(&self, id: &str) -> bool {
+        self.id_prefixes.is_empty()
+            || self
+                .id_prefixes
+                .iter()
+                .any(|prefix| match prefix.strip_suffix('*') {
+                    Some(start) => id.starts_with(start),
+                    None => id == prefix,
+                })
+    }
+
+    pub fn severity(&self, error: LabelError) -> Severity {
+        self.severity
+            .get(error.rule())
+            .copied()
+            .unwrap_or(Severity::Error)
+    }
+
+    /// Check a rule set after it was read.
+    pub fn validate(&self) -> AppResult<()> {
+        if self.max_length == 0 {
+            return Err(AppError::Validation(
+                "maxLength must be at least 1".to_string(),
+            ));
+        }
+        for prefix in &self.id_prefixes {
+            let start = prefix.strip_suffix('*').unwrap_or(prefix);
+            if !start.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(AppError::Validation(format!(
+                    "ID prefix '{}' may only contain letters, digits and a trailing '*'",
+                    prefix
+                )));
+            }
+        }
+        if self.classifications.physical.is_empty() || self.classifications.extension.is_empty() {
+            return Err(AppError::Validation(
+                "The physical and extension classifications must not be empty".to_string(),
+            ));
+        }
+        for (rule, severity) in &self.severity {
+            if !label::RULES.contains(&rule.as_str()) {
+                return Err(AppError::Validation(format!(
+                    "Unknown rule '{}', expected one of: {}",
+                    rule,
+                    label::RULES.join(", ")
+                )));
+            }
+            // Without the candidate check nothing else can be validated
+            if rule == LabelError::NoLabel.rule() && *severity != Severity::Error {
+                return Err(AppError::Validation(
+                    "The severity of noLabel cannot be changed".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse and check a rule set, `.toml` files as TOML and everything else as JSON.
+///
+/// A rule set without a name is named after its file.
+pub fn read_rule_set(path: &Path) -> AppResult<RuleSet> {
+    let mut rules: RuleSet = config_file::read_toml_or_json(path, "rule set")?;
+    if rules.name.trim().is_empty()
+        && let Some(stem) = path.file_stem()
+    {
+        rules.name = stem.to_string_lossy().into_owned();
+    }
+    rules
+        .validate()
+        .map_err(|e| AppError::Validation(format!("Invalid rule set {}: {}", path.display(), e)))?;
+    Ok(rules)
+}
+
+fn rules_dir(app: &AppHandle) -> AppResult<PathBuf> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(RULES_DIR))
+        .map_err(|e| AppError::CacheIo(format!("Unable to determine config directory: {}", e)))
+}
+
+// Only plain file names are accepted, the files must stay inside the rules folder
+fn rule_set_path(dir: &Path, file: &str) -> AppResult<PathBuf> {
+    let name = Path::new(file);
+    if name.file_name() != Some(name.as_os_str()) {
+        return Err(AppError::Validation(format!(
+            "'{}' is not a rule set file name",
+            file
+        )));
+    }
+    Ok(dir.join(name))
+}
+
+fn is_rule_set_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml") || ext.eq_ignore_ascii_case("json"))
+}
+
+/// The built-in rules and every rule set file, sorted by file name.
+pub fn list(app: &AppHandle) -> AppResult<Vec<RuleSetInfo>> {
+    let active = settings::current(app).rule_set;
+    let default = RuleSet::default();
+    let mut infos = vec![RuleSetInfo {
+        file: None,
+        name: default.name,
+        max_length: default.max_length,
+        active: active.is_none(),
+        error: None,
+    }];
+
+    let dir = rules_dir(app)?;
+    if !dir.exists() {
+        return Ok(infos);
+    }
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|e| AppError::CacheIo(format!("Failed to read {}: {}", dir.display(), e)))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && is_rule_set_file(path))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let file = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned());
+        let info = match read_rule_set(&path) {
+            Ok(rules) => RuleSetInfo {
+                name: rules.name,
+                max_length: rules.max_length,
+                active: file == active,
+                file,
+                error: None,
+            },
+            Err(e) => RuleSetInfo {
+                name: file.clone().unwrap_or_default(),
+                max_length: 0,
+                active: file == active,
+                file,
+                error: Some(e.to_string()),
+            },
+        };
+        infos.push(info);
+    }
+    Ok(infos)
+}
+
+/// Read the rule set named in the settings, the built-in rules if there is none.
+pub fn load(app: &AppHandle) -> AppResult<RuleSet> {
+    match settings::current(app).rule_set {
+        Some(file) => read_rule_set(&rule_set_path(&rules_dir(app)?, &file)?),
+        None => Ok(RuleSet::default()),
+    }
+}
+
+/// Make a rule set file the active one (`None` for the built-in rules) and remember it.
+///
+/// Emits `rule-set-changed` so open windows validate again.
+pub fn activate(app: &AppHandle, file: Option<String>) -> AppResult<RuleSetInfo> {
+    let rules = match &file {
+        Some(file) => read_rule_set(&rule_set_path(&rules_dir(app)?, file)?)?,
+        None => RuleSet::default(),
+    };
+    let mut backend_settings = settings::current(app);
+    backend_settings.rule_set = file.clone();
+    settings::save(app, backend_settings)?;
+
+    let info = RuleSetInfo {
+        file,
+        name: rules.name.clone(),
+        max_length: rules.max_length,
+        active: true,
+        error: None,
+    };
+    replace(app, rules);
+    app.emit("rule-set-changed", &info).ok();
+    Ok(info)
+}
+
+/// Check a rule set file and copy it into the rules folder, returns its file name there.
+pub fn import(app: &AppHandle, source: &Path) -> AppResult<String> {
+    if !is_rule_set_file(source) {
+        return Err(AppError::Validation(format!(
+            "{} is not a .toml or .json file",
+            source.display()
+        )));
+    }
+    read_rule_set(source)?;
+    let file = source
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| AppError::Validation(format!("{} has no file name", source.display())))?;
+    let dir = rules_dir(app)?;
+    fs::create_dir_all(&dir)
+        .map_err(|e| AppError::CacheIo(format!("Failed to create {}: {}", dir.display(), e)))?;
+    let target = dir.join(&file);
+    fs::copy(source, &target).map_err(|e| {
+        AppError::CacheIo(format!(
+            "Failed to copy {} to {}: {}",
+            source.display(),
+            target.display(),
+            e
+        ))
+    })?;
+    log::info!(
+        "Imported rule set {} to {}",
+        source.display(),
+        target.display()
+    );
+    Ok(file)
+}
+
+/// Active rule set, swapped as a whole when another one is selected.
+pub type RuleState = Mutex<Arc<RuleSet>>;
+
+/// The rule set currently in use.
+pub fn current(app: &AppHandle) -> Arc<RuleSet> {
+    app.state::<RuleState>().lock().unwrap().clone()
+}
+
+pub fn replace(app: &AppHandle, rules: RuleSet) {
+    log::info!("Validating with rule set '{}'", rules.name);
+    *app.state::<RuleState>().lock().unwrap() = Arc::new(rules);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Tests run in parallel, every file gets a directory of its own
+    static TEMP_DIRS: AtomicUsize = AtomicUsize::new(0);
+
+    // A rule set file in a fresh directory below the temp directory
+    fn rule_set_file(name: &str, text: &str) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!(
+            "bbm-rules-{}-{}",
+            std::process::id(),
+            TEMP_DIRS.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, text).unwrap();
+        (dir, path)
+    }
+
+    fn read(name: &str, text: &str) -> AppResult<RuleSet> {
+        let (dir, path) = rule_set_file(name, text);
+        let rules = read_rule_set(&path);
+        fs::remove_dir_all(&dir).ok();
+        rules
+    }
+
+    #[test]
+    fn rule_set_path_rejects_paths_leaving_the_rules_folder() {
+        let dir = Path::new("/config/rules");
+        assert_eq!(
+            rule_set_path(dir, "strict.toml").unwrap(),
+            dir.join("strict.toml")
+        );
+        for file in [
+            "../settings.json",
+            "sub/strict.toml",
+            "/etc/passwd",
+            "..",
+            ".",
+            "",
+        ] {
+            assert!(
+                matches!(rule_set_path(dir, file), Err(AppError::Validation(_))),
+                "{} was accepted",
+                file
+            );
+        }
+    }
+
+    #[test]
+    fn reads_toml_rule_sets() {
+        let rules = read(
+            "strict.toml",
+            r#"
+maxLength = 20
+idPrefixes = ["RB", "Abc*"]
+
+[requiredParts]
+extension = true
+
+[classifications]
+physical = ["Physical"]
+
+[severity]
+tooLong = "warning"
+duplicateKeywords = "off"
+"#,
+        )
+        .unwrap();
+        // Named after the file, unset fields keep their defaults
+        assert_eq!(rules.name, "strict");
+        assert_eq!(rules.max_length, 20);
+        assert_eq!(
+            rules.required_parts,
+            RequiredParts {
+                extension: true,
+                ..RequiredParts::default()
+            }
+        );
+        assert_eq!(rules.classifications.physical, vec!["Physical"]);
+        assert_eq!(
+            rules.classifications.extension,
+            Classifications::default().extension
+        );
+        assert_eq!(rules.severity(LabelError::TooLong(20)), Severity::Warning);
+        assert_eq!(rules.severity(LabelError::DuplicateKeywords), Severity::Off);
+        assert_eq!(rules.severity(LabelError::IdNotAllowed), Severity::Error);
+        assert!(rules.allows_id("RB"));
+        assert!(rules.allows_id("AbcEng"));
+        assert!(!rules.allows_id("RBX"));
+    }
+
+    #[test]
+    fn reads_json_rule_sets() {
+        let rules = read(
+            "project.json",
+            r#"{"name": "Project X", "maxLength": 32, "severity": {"idNotAllowed": "warning"}}"#,
+        )
+        .unwrap();
+        assert_eq!(rules.name, "Project X");
+        assert_eq!(rules.max_length, 32);
+        assert!(rules.allows_id("anything"));
+        assert_eq!(rules.severity(LabelError::IdNotAllowed), Severity::Warning);
+    }
+
+    #[test]
+    fn rejects_unknown_rules_and_severities() {
+        let unknown_rule = read("a.toml", "[severity]\ntooShort = \"warning\"\n").unwrap_err();
+        assert!(
+            matches!(&unknown_rule, AppError::Validation(m) if m.contains("Unknown rule 'tooShort'"))
+        );
+
+        let unknown_severity = read("b.json", r#"{"severity": {"tooLong": "fatal"}}"#).unwrap_err();
+        assert!(matches!(unknown_severity, AppError::Parse(_)));
+
+        let unknown_field = read("c.toml", "maxLen = 20\n").unwrap_err();
+        assert!(matches!(unknown_field, AppError::Parse(_)));
+
+        let no_label = read("d.toml", "[severity]\nnoLabel = \"off\"\n").unwrap_err();
+        assert!(matches!(&no_label, AppError::Validation(m) if m.contains("noLabel")));
+    }
+
+    #[test]
+    fn rejects_inconsistent_rule_sets() {
+        assert!(read("a.toml", "maxLength = 0\n").is_err());
+        assert!(read("b.toml", "idPrefixes = [\"R_B\"]\n").is_err());
+        assert!(read("c.toml", "idPrefixes = [\"R*B\"]\n").is_err());
+        assert!(read("d.toml", "[classifications]\nextension = []\n").is_err());
+        assert!(matches!(
+            read("e.json", "{\"maxLength\": 27,"),
+            Err(AppError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn rule_set_files_are_toml_or_json() {
+        assert!(is_rule_set_file(Path::new("strict.toml")));
+        assert!(is_rule_set_file(Path::new("Strict.JSON")));
+        assert!(!is_rule_set_file(Path::new("strict.yaml")));
+        assert!(!is_rule_set_file(Path::new("toml")));
+    }
+}
@@ -0,0 +1,772 @@
+// Label extraction from C/C++ source trees
+//
+// Walks a directory for C and C++ sources and headers and extracts the identifiers that
+// are labels: the first argument of configured macros, e.g. `MEASUREMENT(Abc_nEngSpd)`,
+// and `static` variables whose name matches one of the configured patterns. The
+// configuration is read from `bbm-scan.toml` in the scanned directory when present.
+//
+// The labels found in each file are cached together with the file's size and
+// modification time, so repeated scans only read the files that changed.
+use crate::cache;
+use crate::config_file;
+use crate::error::{AppError, AppResult};
+use crate::extract::ExtractedLabel;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Configuration file looked up in the root of the scanned directory.
+pub const CONFIG_FILE: &str = "bbm-scan.toml";
+/// Folder of the app data directory holding one cache file per scanned directory.
+pub const CACHE_DIR: &str = "scan-cache";
+// Bump when the extraction changes, old caches are then ignored
+const CACHE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct ScanConfig {
+    /// Macros whose first argument is a label.
+    pub macros: Vec<String>,
+    /// Patterns for labels declared as `static` variables, e.g. `Abc_*`. Empty skips statics.
+    pub statics: Vec<String>,
+    /// Paths relative to the scanned directory that are skipped, e.g. `build/**`.
+    /// Patterns without a `/` match a file or folder name anywhere.
+    pub ignore: Vec<String>,
+    /// File extensions scanned, without the dot.
+    pub extensions: Vec<String>,
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        ScanConfig {
+            macros: ["MEASUREMENT", "CHARACTERISTIC", "AXIS_PTS"]
+                .map(String::from)
+                .to_vec(),
+            statics: vec!["*_*".to_string()],
+            ignore: vec![".git".to_string()],
+            extensions: ["c", "h", "cc", "cpp", "cxx", "hh", "hpp", "hxx"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+impl ScanConfig {
+    fn is_source(&self, path: &Path) -> bool {
+        path.extension().is_some_and(|ext| {
+            self.extensions
+                .iter()
+                .any(|e| ext.eq_ignore_ascii_case(e.as_str()))
+        })
+    }
+
+    /// Whether a path relative to the scanned directory, with `/` separators, is ignored.
+    pub fn is_ignored(&self, relative: &str) -> bool {
+        self.ignore.iter().any(|pattern| {
+            if pattern.contains('/') {
+                let pattern = pattern.trim_start_matches('/');
+                glob_match(pattern, relative) || glob_match(pattern, &format!("{}/", relative))
+            } else {
+                relative
+                    .split('/')
+                    .any(|component| glob_match(pattern, component))
+            }
+        })
+    }
+}
+
+/// Read a scan configuration, `.toml` files as TOML and everything else as JSON.
+pub fn read_scan_config(path: &Path) -> AppResult<ScanConfig> {
+    config_file::read_toml_or_json(path, "scan configuration")
+}
+
+/// The `bbm-scan.toml` of `root`, or the defaults when there is none.
+pub fn config_for(root: &Path) -> AppResult<ScanConfig> {
+    let path = root.join(CONFIG_FILE);
+    if path.is_file() {
+        read_scan_config(&path)
+    } else {
+        Ok(ScanConfig::default())
+    }
+}
+
+/// Labels found by [`scan`].
+#[derive(Debug, Clone, Default)]
+pub struct ScanResult {
+    /// Number of source files in the tree.
+    pub files: usize,
+    /// Files read in this scan because they are new or changed since the last one.
+    pub changed: Vec<String>,
+    pub labels: Vec<ExtractedLabel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedFile {
+    len: u64,
+    /// Modification time in milliseconds since the Unix epoch.
+    modified: u64,
+    /// Labels with `file` left empty, it is filled in from the scanned root.
+    labels: Vec<ExtractedLabel>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanCache {
+    version: u32,
+    /// Digest of the configuration the labels were extracted with.
+    config: String,
+    /// Keyed by path relative to the scanned root.
+    files: BTreeMap<String, CachedFile>,
+}
+
+/// Scan `root` for labels.
+///
+/// With a `cache_dir`, files whose size and modification time did not change since the
+/// last scan of `root` are not read again. `changed_only` limits the result to the labels
+/// of new and changed files.
+pub fn scan(
+    root: &Path,
+    config: &ScanConfig,
+    cache_dir: Option<&Path>,
+    changed_only: bool,
+) -> AppResult<ScanResult> {
+    if !root.is_dir() {
+        return Err(AppError::Validation(format!(
+            "{} is not a directory",
+            root.display()
+        )));
+    }
+    let fingerprint = cache::sha256_hex(
+        serde_json::to_string(config)
+            .map_err(|e| AppError::Parse(format!("Failed to serialize scan configuration: {}", e)))?
+            .as_bytes(),
+    );
+    let cache_path = cache_dir.map(|dir| cache_file(dir, root));
+    let previous = cache_path
+        .as_deref()
+        .map(read_cache)
+        .filter(|cache| cache.version == CACHE_VERSION && cache.config == fingerprint)
+        .unwrap_or_default();
+
+    let sources = source_files(root, config)?;
+    let mut result = ScanResult {
+        files: sources.len(),
+        ..ScanResult::default()
+    };
+    let mut files = BTreeMap::new();
+    for relative in sources {
+        let path = root.join(&relative);
+        let metadata = fs::metadata(&path).map_err(|e| read_error(&path, e))?;
+        let len = metadata.len();
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |time| time.as_millis() as u64);
+
+        let unchanged = previous
+            .files
+            .get(&relative)
+            .filter(|cached| cached.len == len && cached.modified == modified && modified != 0);
+        let labels = match unchanged {
+            Some(cached) => cached.labels.clone(),
+            None => {
+                let bytes = fs::read(&path).map_err(|e| read_error(&path, e))?;
+                result.changed.push(path.display().to_string());
+                extract_c(&String::from_utf8_lossy(&bytes), None, config)
+            }
+        };
+        if unchanged.is_none() || !changed_only {
+            let file = Some(path.display().to_string());
+            result
+                .labels
+                .extend(labels.iter().cloned().map(|label| ExtractedLabel {
+                    file: file.clone(),
+                    ..label
+                }));
+        }
+        files.insert(
+            relative,
+            CachedFile {
+                len,
+                modified,
+                labels,
+            },
+        );
+    }
+
+    if let Some(cache_path) = cache_path {
+        let cache = ScanCache {
+            version: CACHE_VERSION,
+            config: fingerprint,
+            files,
+        };
+        // A missing cache only costs a full scan next time
+        if let Err(e) = write_cache(&cache_path, &cache) {
+            log::warn!("{}", e);
+        }
+    }
+    log::info!(
+        "Scanned {} source files in {} ({} changed), {} labels",
+        result.files,
+        root.display(),
+        result.changed.len(),
+        result.labels.len()
+    );
+    Ok(result)
+}
+
+/// Files below `root` with one of the configured extensions that are not ignored,
+/// sorted and relative to `root` with `/` separators.
+pub fn source_files(root: &Path, config: &ScanConfig) -> AppResult<Vec<String>> {
+    let mut sources = Vec::new();
+    walk(root, "", config, &mut sources)?;
+    Ok(sources)
+}
+
+fn walk(
+    dir: &Path,
+    relative: &str,
+    config: &ScanConfig,
+    sources: &mut Vec<String>,
+) -> AppResult<()> {
+    let mut entries: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| read_error(dir, e))?
+        .filter_map(|entry| entry.ok())
+        .collect();
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let path = entry.path();
+        let child = if relative.is_empty() {
+            name
+        } else {
+            format!("{}/{}", relative, name)
+        };
+        if config.is_ignored(&child) {
+            continue;
+        }
+        // Symbolic links are not followed, they could loop
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            walk(&path, &child, config, sources)?;
+        } else if file_type.is_file() && config.is_source(&path) {
+            sources.push(child);
+        }
+    }
+    Ok(())
+}
+
+// One cache file per scanned directory, named after its canonical path
+fn cache_file(dir: &Path, root: &Path) -> PathBuf {
+    let root = fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    let digest = cache::sha256_hex(root.to_string_lossy().as_bytes());
+    dir.join(format!("{}.json", &digest[..16]))
+}
+
+fn read_cache(path: &Path) -> ScanCache {
+    fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn write_cache(path: &Path, scan_cache: &ScanCache) -> AppResult<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| AppError::CacheIo(format!("Failed to create {}: {}", dir.display(), e)))?;
+    }
+    let bytes = serde_json::to_vec(scan_cache)
+        .map_err(|e| AppError::Parse(format!("Failed to serialize scan cache: {}", e)))?;
+    cache::write_atomic(path, &bytes)
+}
+
+fn read_error(path: &Path, e: std::io::Error) -> AppError {
+    AppError::CacheIo(format!("Failed to read {}: {}", path.display(), e))
+}
+
+/// Labels of one C/C++ file: configured macro arguments and matching `static` variables.
+pub fn extract_c(text: &str, file: Option<String>, config: &ScanConfig) -> Vec<ExtractedLabel> {
+    let mut labels = Vec::new();
+    let mut tokens = CTokens::new(text).peekable();
+    while let Some((token, line)) = tokens.next() {
+        if config.macros.iter().any(|m| m == token) {
+            if tokens.next_if(|(t, _)| *t == "(").is_none() {
+                continue;
+            }
+            if let Some((name, _)) = tokens.next_if(|(t, _)| is_identifier(t)) {
+                labels.push(ExtractedLabel {
+                    name: name.to_string(),
+                    kind: token.to_string(),
+                    file: file.clone(),
+                    line,
+                });
+            }
+        } else if token == "static" && !config.statics.is_empty() {
+            for (name, line) in static_declarators(&mut tokens) {
+                if config.statics.iter().any(|p| glob_match(p, name)) {
+                    labels.push(ExtractedLabel {
+                        name: name.to_string(),
+                        kind: "static".to_string(),
+                        file: file.clone(),
+                        line,
+                    });
+                }
+            }
+        }
+    }
+    labels
+}
+
+// Variable names of the declaration following `static`, nothing for functions.
+// The declared name is the last identifier before `=`, `,`, `;` or `[`.
+fn static_declarators<'a>(
+    tokens: &mut impl Iterator<Item = (&'a str, usize)>,
+) -> Vec<(&'a str, usize)> {
+    let mut names = Vec::new();
+    let mut last = None;
+    while let Some((token, line)) = tokens.next() {
+        match token {
+            "(" => return Vec::new(),
+            "{" => skip_nested(tokens, "{", "}"),
+            "[" => {
+                names.extend(last.take());
+                skip_nested(tokens, "[", "]");
+            }
+            "=" | "," | ";" => {
+                names.extend(last.take());
+                if token == "=" && skip_initializer(tokens) == Some(";") {
+                    break;
+                }
+                if token == ";" {
+                    break;
+                }
+            }
+            _ if is_identifier(token) => last = Some((token, line)),
+            _ => {}
+        }
+    }
+    names
+}
+
+// Skip up to the `close` matching an already consumed `open`
+fn skip_nested<'a>(tokens: &mut impl Iterator<Item = (&'a str, usize)>, open: &str, close: &str) {
+    let mut depth = 1;
+    for (token, _) in tokens {
+        if token == open {
+            depth += 1;
+        } else if token == close {
+            depth -= 1;
+            if depth == 0 {
+                return;
+            }
+        }
+    }
+}
+
+// Skip an initializer, returning the `,` or `;` that ends it
+fn skip_initializer<'a>(tokens: &mut impl Iterator<Item = (&'a str, usize)>) -> Option<&'a str> {
+    let mut depth = 0usize;
+    for (token, _) in tokens {
+        match token {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => depth = depth.saturating_sub(1),
+            "," | ";" if depth == 0 => return Some(token),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_identifier(token: &str) -> bool {
+    token
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+}
+
+/// Shell-style match: `*` matches within a path component, `**` across components
+/// and `?` any single character except `/`.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    glob_match_bytes(pattern.as_bytes(), text.as_bytes())
+}
+
+fn glob_match_bytes(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            // `**/` also matches no folder at all
+            rest.strip_prefix(b"/")
+                .is_some_and(|after| glob_match_bytes(after, text))
+                || (0..=text.len()).any(|i| glob_match_bytes(rest, &text[i..]))
+        }
+        [b'*', rest @ ..] => {
+            let component = text.iter().position(|&c| c == b'/').unwrap_or(text.len());
+            (0..=component).any(|i| glob_match_bytes(rest, &text[i..]))
+        }
+        [b'?', rest @ ..] => {
+            matches!(text.first(), Some(&c) if c != b'/') && glob_match_bytes(rest, &text[1..])
+        }
+        [c, rest @ ..] => text.first() == Some(c) && glob_match_bytes(rest, &text[1..]),
+    }
+}
+
+// C tokens with their line: identifiers, numbers and single punctuation characters.
+// Comments, string and character literals and preprocessor directives are skipped.
+struct CTokens<'a> {
+    text: &'a str,
+    pos: usize,
+    line: usize,
+    line_start: bool,
+}
+
+impl<'a> CTokens<'a> {
+    fn new(text: &'a str) -> Self {
+        CTokens {
+            text,
+            pos: 0,
+            line: 1,
+            line_start: true,
+        }
+    }
+
+    fn skip(&mut self, len: usize) {
+        let end = (self.pos + len).min(self.text.len());
+        self.line += self.text[self.pos..end].matches('\n').count();
+        self.pos = end;
+    }
+
+    fn skip_until(&mut self, pattern: &str) {
+        match self.text[self.pos..].find(pattern) {
+            Some(offset) => self.skip(offset + pattern.len()),
+            None => self.skip(self.text.len() - self.pos),
+        }
+    }
+
+    // Up to the closing quote, honouring backslash escapes
+    fn skip_literal(&mut self, quote: char) {
+        let rest = &self.text[self.pos..];
+        let mut end = rest.len();
+        let mut escaped = false;
+        for (i, ch) in rest.char_indices().skip(1) {
+            match ch {
+                '\\' if !escaped => escaped = true,
+                '\n' if !escaped => {
+                    // Unterminated, stop at the end of the line
+                    end = i;
+                    break;
+                }
+                c if c == quote && !escaped => {
+                    end = i + 1;
+                    break;
+                }
+                _ => escaped = false,
+            }
+        }
+        self.skip(end);
+    }
+
+    // Preprocessor directives run to the end of the line, including continuations
+    fn skip_directive(&mut self) {
+        loop {
+            let rest = &self.text[self.pos..];
+            let Some(newline) = rest.find('\n') else {
+                self.skip(rest.len());
+                return;
+            };
+            let continued = rest[..newline].trim_end_matches('\r').ends_with('\\');
+            // Block comments may hide the end of the line
+            match rest[..newline].find("/*") {
+                Some(comment) if !rest[..comment].contains("//") => {
+                    self.skip(comment + 2);
+                    self.skip_until("*/");
+                }
+                _ => {
+                    self.skip(newline + 1);
+                    if !continued {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for CTokens<'a> {
+    type Item = (&'a str, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let rest = &self.text[self.pos..];
+            let c = rest.chars().next()?;
+            if c == '\n' {
+                self.line_start = true;
+                self.skip(1);
+            } else if c.is_whitespace() {
+                self.skip(c.len_utf8());
+            } else if rest.starts_with("/*") {
+                self.skip(2);
+                self.skip_until("*/");
+            } else if rest.starts_with("//") {
+                self.skip_until("\n");
+                self.line_start = true;
+            } else if c == '#' && self.line_start {
+                self.skip_directive();
+            } else if c == '"' || c == '\'' {
+                self.line_start = false;
+                self.skip_literal(c);
+            } else {
+                self.line_start = false;
+                let len = if c.is_ascii_alphanumeric() || c == '_' {
+                    rest.find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_'))
+                        .unwrap_or(rest.len())
+                } else {
+                    c.len_utf8()
+                };
+                let token = &self.text[self.pos..self.pos + len];
+                let line = self.line;
+                self.skip(len);
+                return Some((token, line));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names_and_lines(labels: &[ExtractedLabel]) -> Vec<(&str, &str, usize)> {
+        labels
+            .iter()
+            .map(|label| (label.name.as_str(), label.kind.as_str(), label.line))
+            .collect()
+    }
+
+    fn extract(text: &str) -> Vec<ExtractedLabel> {
+        extract_c(text, None, &ScanConfig::default())
+    }
+
+    // A fresh directory below the temp directory, removed by the caller
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bbm-scan-{}-{}", name, std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(root: &Path, relative: &str, text: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn skips_comments_strings_and_escaped_quotes() {
+        let text = r#"// MEASUREMENT(Abc_LineComment)
+/* CHARACTERISTIC(Abc_BlockComment)
+   MEASUREMENT(Abc_StillComment) */
+const char *text = "MEASUREMENT(Abc_String) \" MEASUREMENT(Abc_AfterEscape)";
+char quote = '\''; char paren = '(';
+MEASUREMENT(Abc_nEngSpd) /* trailing */ AXIS_PTS(Abc_nEngSpd_Ax)
+"#;
+        assert_eq!(
+            names_and_lines(&extract(text)),
+            vec![
+                ("Abc_nEngSpd", "MEASUREMENT", 6),
+                ("Abc_nEngSpd_Ax", "AXIS_PTS", 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_multi_line_preprocessor_directives() {
+        let text = "#define DECLARE(name) \\\n    MEASUREMENT(Abc_InDefine) \\\n    static int Abc_DefineStatic;\n\
+                    #include \"labels.h\" /* MEASUREMENT(Abc_Hidden)\n   CHARACTERISTIC(Abc_StillHidden) */\n\
+                    CHARACTERISTIC(Abc_tMax_C)\n\
+                    x = a # b; MEASUREMENT(Abc_AfterHash)\n";
+        assert_eq!(
+            names_and_lines(&extract(text)),
+            vec![
+                ("Abc_tMax_C", "CHARACTERISTIC", 6),
+                ("Abc_AfterHash", "MEASUREMENT", 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn macro_without_an_identifier_argument_is_no_label() {
+        let text = "MEASUREMENT Abc_NoParen;\nMEASUREMENT(\"Abc_Quoted\")\nMEASUREMENT(42)\n";
+        assert!(extract(text).is_empty());
+    }
+
+    #[test]
+    fn static_declarations_yield_each_matching_variable() {
+        let text = "static int A_b = {1, f(2, 3)}, C_d[3];\n\
+                    static const uint8 Abc_tab[2][3] = {{0}};\n\
+                    static int counter = 0;\n\
+                    static struct Eng_Cfg { int Eng_Member; } Eng_cfg_C;\n\
+                    static int Abc_x = 1,\n    Abc_y;\n";
+        assert_eq!(
+            names_and_lines(&extract(text)),
+            vec![
+                ("A_b", "static", 1),
+                ("C_d", "static", 1),
+                ("Abc_tab", "static", 2),
+                ("Eng_cfg_C", "static", 4),
+                ("Abc_x", "static", 5),
+                ("Abc_y", "static", 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn static_functions_yield_nothing() {
+        let text = "static void Abc_Init(void);\n\
+                    static inline int Abc_Get(int Abc_arg) { return Abc_arg; }\n\
+                    static int (*Abc_Callback)(int);\n";
+        assert!(extract(text).is_empty());
+    }
+
+    #[test]
+    fn statics_need_a_matching_pattern() {
+        let text = "static int Abc_Count;\nstatic int Eng_Count;\n";
+        let config = ScanConfig {
+            statics: vec!["Abc_*".to_string()],
+            ..ScanConfig::default()
+        };
+        let only_abc = extract_c(text, None, &config);
+        assert_eq!(names_and_lines(&only_abc), vec![("Abc_Count", "static", 1)]);
+        let config = ScanConfig {
+            statics: Vec::new(),
+            ..ScanConfig::default()
+        };
+        assert!(extract_c(text, None, &config).is_empty());
+    }
+
+    #[test]
+    fn glob_star_stays_within_a_path_component() {
+        assert!(glob_match("Abc_*", "Abc_nEngSpd"));
+        assert!(!glob_match("Abc_*", "Eng_nSpd"));
+        assert!(glob_match("*.c", "main.c"));
+        assert!(!glob_match("*.c", "src/main.c"));
+        assert!(glob_match("src/**", "src/a/b/main.c"));
+        assert!(glob_match("Abc_?", "Abc_C"));
+        assert!(!glob_match("a?b", "a/b"));
+    }
+
+    #[test]
+    fn ignore_patterns() {
+        let config = ScanConfig {
+            ignore: [
+                "build/**",
+                "**/generated/*.c",
+                "/vendor/**",
+                ".git",
+                "*.bak.c",
+            ]
+            .map(String::from)
+            .to_vec(),
+            ..ScanConfig::default()
+        };
+        // `build/**` skips the folder itself and everything below it, only at the root
+        assert!(config.is_ignored("build"));
+        assert!(config.is_ignored("build/out/main.c"));
+        assert!(!config.is_ignored("src/build/main.c"));
+        // `**/` matches any number of folders, including none
+        assert!(config.is_ignored("generated/labels.c"));
+        assert!(config.is_ignored("src/engine/generated/labels.c"));
+        assert!(!config.is_ignored("src/generated/sub/labels.c"));
+        assert!(!config.is_ignored("src/generated/labels.h"));
+        // A leading `/` anchors at the root like without it
+        assert!(config.is_ignored("vendor/lib.c"));
+        // Patterns without a `/` match a name anywhere
+        assert!(config.is_ignored("sub/.git/hooks/pre-commit.c"));
+        assert!(config.is_ignored("src/old.bak.c"));
+        assert!(!config.is_ignored("src/main.c"));
+    }
+
+    #[test]
+    fn source_files_skip_ignored_folders_and_other_extensions() {
+        let root = temp_dir("sources");
+        for relative in [
+            "main.c",
+            "include/labels.H",
+            "build/out/generated.c",
+            "src/build/kept.cpp",
+            "notes.txt",
+        ] {
+            write(&root, relative, "");
+        }
+        let config = ScanConfig {
+            ignore: vec!["build/**".to_string()],
+            ..ScanConfig::default()
+        };
+        let sources = source_files(&root, &config);
+        fs::remove_dir_all(&root).ok();
+        assert_eq!(
+            sources.unwrap(),
+            vec!["include/labels.H", "main.c", "src/build/kept.cpp"]
+        );
+    }
+
+    #[test]
+    fn incremental_scan_reads_only_changed_files() {
+        let root = temp_dir("incremental");
+        let cache_dir = temp_dir("incremental-cache");
+        write(&root, "a.c", "MEASUREMENT(Abc_a)\n");
+        write(&root, "b.c", "MEASUREMENT(Abc_b)\n");
+        write(&root, "build/c.c", "MEASUREMENT(Abc_c)\n");
+        let config = ScanConfig {
+            ignore: vec!["build/**".to_string()],
+            ..ScanConfig::default()
+        };
+        let path = |relative: &str| root.join(relative).display().to_string();
+        let names = |result: &ScanResult| -> Vec<String> {
+            result
+                .labels
+                .iter()
+                .map(|label| label.name.clone())
+                .collect()
+        };
+
+        let first = scan(&root, &config, Some(&cache_dir), false).unwrap();
+        assert_eq!(first.files, 2);
+        assert_eq!(first.changed, vec![path("a.c"), path("b.c")]);
+        assert_eq!(names(&first), vec!["Abc_a", "Abc_b"]);
+
+        // Unchanged files come from the cache, with their file filled in
+        let again = scan(&root, &config, Some(&cache_dir), false).unwrap();
+        assert!(again.changed.is_empty());
+        assert_eq!(names(&again), vec!["Abc_a", "Abc_b"]);
+        assert_eq!(again.labels[1].file, Some(path("b.c")));
+        let changed_only = scan(&root, &config, Some(&cache_dir), true).unwrap();
+        assert_eq!(changed_only.files, 2);
+        assert!(changed_only.changed.is_empty());
+        assert!(changed_only.labels.is_empty());
+
+        write(&root, "b.c", "MEASUREMENT(Abc_b)\nMEASUREMENT(Abc_b2)\n");
+        let after_edit = scan(&root, &config, Some(&cache_dir), true).unwrap();
+        assert_eq!(after_edit.changed, vec![path("b.c")]);
+        assert_eq!(names(&after_edit), vec!["Abc_b", "Abc_b2"]);
+
+        // Another configuration may extract other labels, the cache no longer applies
+        let other_config = ScanConfig {
+            macros: vec!["MEASUREMENT".to_string()],
+            ..config.clone()
+        };
+        let rescanned = scan(&root, &other_config, Some(&cache_dir), true).unwrap();
+        assert_eq!(rescanned.changed.len(), 2);
+
+        let without_cache = scan(&root, &config, None, true).unwrap();
+        fs::remove_dir_all(&root).ok();
+        fs::remove_dir_all(&cache_dir).ok();
+        assert_eq!(without_cache.changed.len(), 2);
+        assert_eq!(names(&without_cache), vec!["Abc_a", "Abc_b", "Abc_b2"]);
+    }
+}
@@ -0,0 +1,449 @@
+// Semantic keyword search over long names and descriptions (EN and DE)
+//
+// Despite the name the similarity is lexical, not semantic: texts are embedded by
+// hashing their words and character trigrams, so "coolant temp" finds "coolant
+// temperature" through shared trigrams, but synonyms and translations sharing no letters
+// are not found. In exchange no model has to be shipped. The vectors go into an HNSW
+// graph (hnsw_rs) which is dumped next to keywords.json and reloaded as long as the
+// keywords are unchanged.
+use crate::cache;
+use crate::error::{AppError, AppResult};
+use crate::keyword::Keyword;
+use hnsw_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+/// Bump when the embedding changes so persisted indexes are rebuilt.
+const INDEX_VERSION: u32 = 1;
+const DIMENSIONS: usize = 384;
+const INDEX_BASENAME: &str = "semantic_index";
+const META_FILE: &str = "semantic_index.json";
+
+const MAX_NB_CONNECTION: usize = 24;
+const MAX_LAYER: usize = 16;
+const EF_CONSTRUCTION: usize = 200;
+const EF_SEARCH: usize = 64;
+
+pub(crate) const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "by", "for", "from", "in", "is", "of", "on", "or", "the", "to",
+    "with", "am", "auf", "aus", "bei", "das", "dem", "den", "der", "des", "die", "ein", "eine",
+    "einer", "eines", "für", "im", "mit", "oder", "und", "von", "vom", "zu", "zum", "zur",
+];
+
+/// One search hit, best first.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticMatch {
+    pub abbr_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long_name_en: Option<String>,
+    /// Cosine similarity between query and keyword text, 0 to 1.
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    abbr_name: String,
+    long_name_en: Option<String>,
+}
+
+// Stored next to the HNSW dump, maps data ids back to keywords
+#[derive(Serialize, Deserialize)]
+struct IndexMeta {
+    version: u32,
+    fingerprint: String,
+    basename: String,
+    entries: Vec<Entry>,
+}
+
+pub struct SemanticIndex {
+    hnsw: Hnsw<'static, f32, DistCosine>,
+    entries: Vec<Entry>,
+    fingerprint: String,
+}
+
+/// Lazily loaded index of the current keywords.
+pub type SemanticState = Mutex<Option<Arc<SemanticIndex>>>;
+
+impl SemanticIndex {
+    pub fn build(keywords: &[Keyword]) -> SemanticIndex {
+        let mut entries = Vec::new();
+        let mut vectors = Vec::new();
+        for keyword in keywords {
+            if let Some(vector) = embed_keyword(keyword) {
+                entries.push(Entry {
+                    abbr_name: keyword.abbr_name.clone(),
+                    long_name_en: keyword.long_name_en.clone(),
+                });
+                vectors.push(vector);
+            }
+        }
+
+        let mut hnsw = Hnsw::new(
+            MAX_NB_CONNECTION,
+            entries.len().max(1),
+            MAX_LAYER,
+            EF_CONSTRUCTION,
+            DistCosine {},
+        );
+        let data: Vec<(&Vec<f32>, usize)> = vectors.iter().zip(0..).collect();
+        hnsw.parallel_insert(&data);
+        hnsw.set_searching_mode(true);
+
+        SemanticIndex {
+            hnsw,
+            entries,
+            fingerprint: fingerprint(keywords),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SemanticMatch> {
+        let Some(vector) = embed(&[(query, 1.0)]) else {
+            return Vec::new();
+        };
+        if self.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+        // Ask for more neighbours than needed, an abbreviation may occur several times
+        let k = (limit * 2).min(self.entries.len());
+        let mut seen = HashSet::new();
+        let mut matches = Vec::new();
+        for neighbour in self.hnsw.search(&vector, k, EF_SEARCH.max(k)) {
+            let Some(entry) = self.entries.get(neighbour.d_id) else {
+                continue;
+            };
+            if !seen.insert(entry.abbr_name.as_str()) {
+                continue;
+            }
+            matches.push(SemanticMatch {
+                abbr_name: entry.abbr_name.clone(),
+                long_name_en: entry.long_name_en.clone(),
+                score: (1.0 - neighbour.distance).clamp(0.0, 1.0),
+            });
+            if matches.len() == limit {
+                break;
+            }
+        }
+        matches
+    }
+
+    /// Write the graph and its metadata into `dir`.
+    ///
+    /// The graph goes to files of its own and the metadata naming them is replaced last,
+    /// so a concurrent or interrupted save never leaves a mismatched pair behind.
+    pub fn save(&self, dir: &Path) -> AppResult<()> {
+        fs::create_dir_all(dir)
+            .map_err(|e| AppError::CacheIo(format!("Failed to create {}: {}", dir.display(), e)))?;
+        let path = dir.join(META_FILE);
+        let previous = read_meta(&path).ok().map(|meta| meta.basename);
+        // hnsw_rs cannot dump an empty graph, the metadata alone describes it
+        let basename = if self.entries.is_empty() {
+            INDEX_BASENAME.to_string()
+        } else {
+            self.hnsw
+                .file_dump(dir, &unique_basename())
+                .map_err(|e| AppError::CacheIo(format!("Failed to dump semantic index: {}", e)))?
+        };
+        let meta = IndexMeta {
+            version: INDEX_VERSION,
+            fingerprint: self.fingerprint.clone(),
+            basename: basename.clone(),
+            entries: self.entries.clone(),
+        };
+        let json = serde_json::to_vec(&meta)
+            .map_err(|e| AppError::Parse(format!("Failed to serialize semantic index: {}", e)))?;
+        if let Err(e) = cache::write_atomic(&path, &json) {
+            remove_dump(dir, &basename);
+            return Err(e);
+        }
+        if let Some(previous) = previous.filter(|previous| *previous != basename) {
+            remove_dump(dir, &previous);
+        }
+        Ok(())
+    }
+
+    /// Load the index saved in `dir` if it was built from the same keywords.
+    pub fn load(dir: &Path, keywords: &[Keyword]) -> AppResult<Option<SemanticIndex>> {
+        let path = dir.join(META_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let meta = read_meta(&path)?;
+        let expected = fingerprint(keywords);
+        if meta.version != INDEX_VERSION || meta.fingerprint != expected {
+            log::info!("Semantic index is outdated, it will be rebuilt");
+            return Ok(None);
+        }
+        if meta.entries.is_empty() {
+            return Ok(Some(SemanticIndex::build(&[])));
+        }
+
+        // The loaded graph borrows from its reloader, which is leaked to live as long.
+        // Indexes are only loaded at start and after keyword updates, so little is lost.
+        let reloader: &'static HnswIo = Box::leak(Box::new(HnswIo::new(dir, &meta.basename)));
+        let hnsw = reloader
+            .load_hnsw_with_dist::<f32, DistCosine>(DistCosine {})
+            .map_err(|e| AppError::CacheIo(format!("Failed to load semantic index: {}", e)))?;
+        Ok(Some(SemanticIndex {
+            hnsw,
+            entries: meta.entries,
+            fingerprint: expected,
+        }))
+    }
+
+    /// Reuse the index saved in `dir` or build and save a new one.
+    pub fn load_or_build(dir: &Path, keywords: &[Keyword]) -> SemanticIndex {
+        match SemanticIndex::load(dir, keywords) {
+            Ok(Some(index)) => {
+                log::info!("Loaded semantic index with {} entries", index.len());
+                return index;
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("{}, rebuilding semantic index", e),
+        }
+        let start = std::time::Instant::now();
+        let index = SemanticIndex::build(keywords);
+        log::info!(
+            "Built semantic index with {} entries in {:?}",
+            index.len(),
+            start.elapsed()
+        );
+        if let Err(e) = index.save(dir) {
+            log::error!("{}", e);
+        }
+        index
+    }
+}
+
+fn read_meta(path: &Path) -> AppResult<IndexMeta> {
+    let json = fs::read_to_string(path)
+        .map_err(|e| AppError::CacheIo(format!("Failed to read {}: {}", path.display(), e)))?;
+    serde_json::from_str(&json)
+        .map_err(|e| AppError::Parse(format!("Failed to parse {}: {}", path.display(), e)))
+}
+
+// Every save dumps under a new name, the metadata says which dump is current
+fn unique_basename() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    format!("{}-{}-{}", INDEX_BASENAME, std::process::id(), nanos)
+}
+
+fn remove_dump(dir: &Path, basename: &str) {
+    for extension in ["hnsw.graph", "hnsw.data"] {
+        let path = dir.join(format!("{}.{}", basename, extension));
+        if path.exists()
+            && let Err(e) = fs::remove_file(&path)
+        {
+            log::warn!("Failed to remove {}: {}", path.display(), e);
+        }
+    }
+}
+
+fn embed_keyword(keyword: &Keyword) -> Option<Vec<f32>> {
+    let mut parts = Vec::new();
+    for long_name in [&keyword.long_name_en, &keyword.long_name_de]
+        .into_iter()
+        .flatten()
+    {
+        parts.push((long_name.as_str(), 2.0));
+    }
+    for description in [&keyword.description_en, &keyword.description_de]
+        .into_iter()
+        .flatten()
+    {
+        parts.push((description.as_str(), 1.0));
+    }
+    embed(&parts)
+}
+
+/// Unit length feature vector of weighted texts, `None` if they contain no words.
+fn embed(parts: &[(&str, f32)]) -> Option<Vec<f32>> {
+    let mut vector = vec![0.0f32; DIMENSIONS];
+    for (text, weight) in parts {
+        for word in words(text) {
+            add_feature(&mut vector, &word, *weight);
+            let chars: Vec<char> = format!("<{}>", word).chars().collect();
+            let trigrams = chars.len().saturating_sub(2);
+            let trigram_weight = weight / (trigrams.max(1) as f32).sqrt();
+            for trigram in chars.windows(3) {
+                let trigram: String = trigram.iter().collect();
+                add_feature(&mut vector, &trigram, trigram_weight);
+            }
+        }
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return None;
+    }
+    vector.iter_mut().for_each(|v| *v /= norm);
+    Some(vector)
+}
+
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|word| word.chars().count() > 1 && !STOP_WORDS.contains(&word.as_str()))
+}
+
+fn add_feature(vector: &mut [f32], feature: &str, weight: f32) {
+    let hash = fnv1a(feature.as_bytes());
+    let index = (hash % DIMENSIONS as u64) as usize;
+    // The sign bit keeps hash collisions from always adding up
+    let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+    vector[index] += sign * weight;
+}
+
+// Stable across runs and Rust versions, unlike DefaultHasher
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn fingerprint(keywords: &[Keyword]) -> String {
+    let mut hash = fnv1a(&INDEX_VERSION.to_le_bytes());
+    for keyword in keywords {
+        for text in [
+            Some(&keyword.abbr_name),
+            keyword.long_name_en.as_ref(),
+            keyword.long_name_de.as_ref(),
+            keyword.description_en.as_ref(),
+            keyword.description_de.as_ref(),
+        ] {
+            let text = text.map(String::as_str).unwrap_or("");
+            hash = fnv1a(&[hash.to_le_bytes().as_slice(), text.as_bytes(), &[0]].concat());
+        }
+    }
+    format!("{:016x}-{}", hash, keywords.len())
+}
+
+/// The index of the cached keywords, loaded or built on first use.
+pub fn index(
+    app: &AppHandle,
+    keywords: impl FnOnce() -> AppResult<Vec<Keyword>>,
+) -> AppResult<Arc<SemanticIndex>> {
+    let state = app.state::<SemanticState>();
+    if let Some(index) = state.lock().unwrap().as_ref() {
+        return Ok(index.clone());
+    }
+    let dir = app
+        .path()
+        .app_local_data_dir()
+        .map_err(|e| AppError::CacheIo(format!("Unable to determine app data directory: {}", e)))?;
+    // Built without holding the lock, searches and keyword updates are not blocked meanwhile
+    let index = Arc::new(SemanticIndex::load_or_build(&dir, &keywords()?));
+    // A rebuild or another search may have stored an index in the meantime, it wins
+    Ok(state.lock().unwrap().get_or_insert(index).clone())
+}
+
+/// Replace the index after new keywords were stored.
+pub fn rebuild(app: &AppHandle, keywords: &[Keyword]) {
+    let index = match app.path().app_local_data_dir() {
+        Ok(dir) => SemanticIndex::load_or_build(&dir, keywords),
+        Err(e) => {
+            log::error!("Unable to determine app data directory: {}", e);
+            SemanticIndex::build(keywords)
+        }
+    };
+    *app.state::<SemanticState>().lock().unwrap() = Some(Arc::new(index));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyword::test_keyword;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Tests run in parallel, every test gets a directory of its own
+    static TEMP_DIRS: AtomicUsize = AtomicUsize::new(0);
+
+    fn temp_dir() -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "bbm-semantic-{}-{}",
+            std::process::id(),
+            TEMP_DIRS.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    fn keywords() -> Vec<Keyword> {
+        vec![
+            test_keyword("CoolT", "Descriptive", "coolant temperature"),
+            test_keyword("EngSpd", "Descriptive", "engine speed"),
+            test_keyword("OilPres", "Descriptive", "oil pressure"),
+        ]
+    }
+
+    fn abbrs(matches: &[SemanticMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.abbr_name.as_str()).collect()
+    }
+
+    #[test]
+    fn finds_texts_sharing_trigrams() {
+        let index = SemanticIndex::build(&keywords());
+        assert_eq!(abbrs(&index.search("coolant temp", 1)), ["CoolT"]);
+        assert_eq!(abbrs(&index.search("speed of the engine", 1)), ["EngSpd"]);
+        assert!(index.search("the of", 3).is_empty());
+    }
+
+    #[test]
+    fn saved_index_is_loaded_for_the_same_keywords() {
+        let dir = temp_dir();
+        SemanticIndex::build(&keywords()).save(&dir).unwrap();
+
+        let index = SemanticIndex::load(&dir, &keywords()).unwrap().unwrap();
+        assert_eq!(index.len(), 3);
+        assert_eq!(abbrs(&index.search("oil pressure", 1)), ["OilPres"]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn saved_index_of_other_keywords_is_not_loaded() {
+        let dir = temp_dir();
+        SemanticIndex::build(&keywords()).save(&dir).unwrap();
+
+        let mut changed = keywords();
+        changed[1].long_name_en = Some("engine rotational speed".to_string());
+        assert!(SemanticIndex::load(&dir, &changed).unwrap().is_none());
+        assert!(
+            SemanticIndex::load(&dir, &keywords()[..2])
+                .unwrap()
+                .is_none()
+        );
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn saving_again_replaces_the_previous_dump() {
+        let dir = temp_dir();
+        SemanticIndex::build(&keywords()).save(&dir).unwrap();
+        SemanticIndex::build(&keywords()).save(&dir).unwrap();
+
+        let graphs = fs::read_dir(&dir)
+            .unwrap()
+            .filter(|entry| {
+                let name = entry.as_ref().unwrap().file_name();
+                name.to_string_lossy().ends_with(".hnsw.graph")
+            })
+            .count();
+        assert_eq!(graphs, 1);
+        assert!(SemanticIndex::load(&dir, &keywords()).unwrap().is_some());
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,261 @@
+// Persisted backend settings: keyword API location, HTTP timeouts, the active rule set,
+// the waiver file, the local API, the clipboard shortcut, the clipboard watch and
+// working offline
+//
+// Defaults come from the environment configuration, saved values override them.
+use crate::api;
+use crate::cache;
+use crate::config;
+use crate::error::{AppError, AppResult};
+use crate::hotkey::{self, HotkeyMode};
+use crate::watch;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BackendSettings {
+    /// Base URL of the keyword API, e.g. `https://host/swap-prod/api`.
+    pub api_base_url: String,
+    pub keywords_endpoint: String,
+    pub version_endpoint: String,
+    /// Timeout for a whole request, including reading the body.
+    pub request_timeout_secs: u64,
+    pub connect_timeout_secs: u64,
+    /// File name of the active rule set in the rules folder, `None` for the built-in rules.
+    pub rule_set: Option<String>,
+    /// Path of the waiver file, `None` for no waivers.
+    pub waiver_file: Option<String>,
+    /// Whether the local HTTP API for other tools is served.
+    pub local_api_enabled: bool,
+    pub local_api_port: u16,
+    /// Bearer token the local API requires, created when it is first enabled.
+    pub local_api_token: Option<String>,
+    /// Shortcut validating the clipboard, `None` to register none.
+    pub hotkey: Option<String>,
+    pub hotkey_mode: HotkeyMode,
+    /// Whether copied labels are validated and shown in the tray.
+    pub clipboard_watch: bool,
+    /// Copied texts matching this pattern are validated, `*` and `?` as in file names.
+    pub clipboard_pattern: String,
+    /// Number of recent labels in the tray menu.
+    pub history_size: usize,
+    /// Whether the cached keywords are used without contacting the keyword API.
+    pub work_offline: bool,
+}
+
+// Settings are logged, the local API token must not end up in the log files
+impl fmt::Debug for BackendSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackendSettings")
+            .field("api_base_url", &self.api_base_url)
+            .field("keywords_endpoint", &self.keywords_endpoint)
+            .field("version_endpoint", &self.version_endpoint)
+            .field("request_timeout_secs", &self.request_timeout_secs)
+            .field("connect_timeout_secs", &self.connect_timeout_secs)
+            .field("rule_set", &self.rule_set)
+            .field("waiver_file", &self.waiver_file)
+            .field("local_api_enabled", &self.local_api_enabled)
+            .field("local_api_port", &self.local_api_port)
+            .field(
+                "local_api_token",
+                &self.local_api_token.as_ref().map(|_| "<redacted>"),
+            )
+            .field("hotkey", &self.hotkey)
+            .field("hotkey_mode", &self.hotkey_mode)
+            .field("clipboard_watch", &self.clipboard_watch)
+            .field("clipboard_pattern", &self.clipboard_pattern)
+            .field("history_size", &self.history_size)
+            .field("work_offline", &self.work_offline)
+            .finish()
+    }
+}
+
+impl Default for BackendSettings {
+    fn default() -> Self {
+        let app_config = config::get_config();
+        BackendSettings {
+            api_base_url: app_config.api_url.clone(),
+            keywords_endpoint: app_config.keywords_endpoint.clone(),
+            version_endpoint: app_config.version_endpoint.clone(),
+            request_timeout_secs: app_config.request_timeout_secs,
+            connect_timeout_secs: app_config.connect_timeout_secs,
+            rule_set: None,
+            waiver_file: None,
+            local_api_enabled: false,
+            local_api_port: api::DEFAULT_PORT,
+            local_api_token: None,
+            hotkey: Some(hotkey::DEFAULT_HOTKEY.to_string()),
+            hotkey_mode: HotkeyMode::default(),
+            clipboard_watch: false,
+            clipboard_pattern: watch::DEFAULT_PATTERN.to_string(),
+            history_size: watch::DEFAULT_HISTORY_SIZE,
+            work_offline: false,
+        }
+    }
+}
+
+impl BackendSettings {
+    pub fn keywords_url(&self) -> String {
+        join_url(&self.api_base_url, &self.keywords_endpoint)
+    }
+
+    pub fn version_url(&self) -> String {
+        join_url(&self.api_base_url, &self.version_endpoint)
+    }
+
+    /// HTTP client honouring the configured timeouts and API token.
+    pub fn http_client(&self) -> reqwest::Client {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(token) = &config::get_config().api_token {
+            match reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token)) {
+                Ok(mut value) => {
+                    value.set_sensitive(true);
+                    headers.insert(reqwest::header::AUTHORIZATION, value);
+                }
+                Err(_) => log::error!("API token contains invalid characters, not sending it"),
+            }
+        }
+        reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(Duration::from_secs(self.request_timeout_secs))
+            .connect_timeout(Duration::from_secs(self.connect_timeout_secs))
+            .build()
+            .unwrap_or_else(|e| {
+                log::error!("Failed to build HTTP client, using defaults: {}", e);
+                reqwest::Client::new()
+            })
+    }
+
+    /// Take the settings changed through their own commands or the tray from `saved`.
+    pub fn keep_separately_set(&mut self, saved: &BackendSettings) {
+        self.rule_set = saved.rule_set.clone();
+        self.waiver_file = saved.waiver_file.clone();
+        self.local_api_enabled = saved.local_api_enabled;
+        self.local_api_port = saved.local_api_port;
+        self.local_api_token = saved.local_api_token.clone();
+        self.work_offline = saved.work_offline;
+    }
+
+    /// Check the settings before they are saved.
+    pub fn validate(&self) -> AppResult<()> {
+        let url = reqwest::Url::parse(&self.api_base_url).map_err(|e| {
+            AppError::Validation(format!("Invalid API URL '{}': {}", self.api_base_url, e))
+        })?;
+        if url.scheme() != "http" && url.scheme() != "https" {
+            return Err(AppError::Validation(format!(
+                "API URL must use http or https, got '{}'",
+                url.scheme()
+            )));
+        }
+        for endpoint in [&self.keywords_endpoint, &self.version_endpoint] {
+            if !endpoint.starts_with('/') {
+                return Err(AppError::Validation(format!(
+                    "Endpoint '{}' must start with '/'",
+                    endpoint
+                )));
+            }
+        }
+        if self.request_timeout_secs == 0 || self.connect_timeout_secs == 0 {
+            return Err(AppError::Validation(
+                "Timeouts must be at least one second".to_string(),
+            ));
+        }
+        if self.local_api_port == 0 {
+            return Err(AppError::Validation(
+                "Local API port must not be 0".to_string(),
+            ));
+        }
+        if let Some(shortcut) = &self.hotkey {
+            hotkey::parse(shortcut)?;
+        }
+        if self.clipboard_pattern.trim().is_empty() {
+            return Err(AppError::Validation(
+                "Clipboard label pattern must not be empty".to_string(),
+            ));
+        }
+        if !(1..=watch::MAX_HISTORY_SIZE).contains(&self.history_size) {
+            return Err(AppError::Validation(format!(
+                "Number of recent labels must be between 1 and {}",
+                watch::MAX_HISTORY_SIZE
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn join_url(base: &str, endpoint: &str) -> String {
+    format!("{}{}", base.trim_end_matches('/'), endpoint)
+}
+
+fn settings_path(app: &AppHandle) -> AppResult<PathBuf> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(SETTINGS_FILE))
+        .map_err(|e| AppError::CacheIo(format!("Unable to determine config directory: {}", e)))
+}
+
+/// Load the saved settings, falling back to the defaults.
+pub fn load(app: &AppHandle) -> BackendSettings {
+    let path = match settings_path(app) {
+        Ok(path) => path,
+        Err(e) => {
+            log::warn!("{}, using default settings", e);
+            return BackendSettings::default();
+        }
+    };
+    if !path.exists() {
+        log::info!("No settings file at {}, using defaults", path.display());
+        return BackendSettings::default();
+    }
+    match fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+    {
+        Ok(settings) => {
+            log::info!("Loaded backend settings from {}", path.display());
+            settings
+        }
+        Err(e) => {
+            log::error!(
+                "Failed to load settings from {}: {}, using defaults",
+                path.display(),
+                e
+            );
+            BackendSettings::default()
+        }
+    }
+}
+
+/// Validate, persist and activate new settings.
+pub fn save(app: &AppHandle, settings: BackendSettings) -> AppResult<()> {
+    settings.validate()?;
+    let path = settings_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| AppError::CacheIo(format!("Failed to create {}: {}", dir.display(), e)))?;
+    }
+    let json = serde_json::to_string_pretty(&settings)
+        .map_err(|e| AppError::Parse(format!("Failed to serialize settings: {}", e)))?;
+    // Written aside and renamed, a crash mid-write must not lose all settings
+    cache::write_atomic(&path, json.as_bytes())?;
+    log::info!("Saved backend settings to {}", path.display());
+
+    *app.state::<Mutex<BackendSettings>>().lock().unwrap() = settings;
+    Ok(())
+}
+
+/// The settings currently in effect.
+pub fn current(app: &AppHandle) -> BackendSettings {
+    app.state::<Mutex<BackendSettings>>()
+        .lock()
+        .unwrap()
+        .clone()
+}
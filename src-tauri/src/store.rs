@@ -0,0 +1,344 @@
+// In-memory keyword store shared by all commands
+//
+// The webview no longer receives the keyword list, it queries this store through
+// `search_keywords`, `get_keyword_by_abbr` and `list_by_classification`.
+use crate::keyword::Keyword;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+pub const MAX_PAGE_SIZE: usize = 500;
+
+/// Same threshold as the former Fuse.js search: at most 40% of a token may differ.
+const FUZZY_THRESHOLD: f32 = 0.4;
+
+#[derive(Debug, Default)]
+pub struct KeywordStore {
+    keywords: Vec<Keyword>,
+    by_abbr: HashMap<String, Vec<usize>>,
+    loaded: bool,
+}
+
+/// Current keyword set, swapped as a whole when new keywords arrive.
+pub type KeywordState = Mutex<Arc<KeywordStore>>;
+
+/// One page of query results.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordPage {
+    pub items: Vec<Keyword>,
+    /// Number of matches over all pages.
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+}
+
+/// Payload of the `keywords-ready` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordsReady {
+    pub count: usize,
+}
+
+impl KeywordStore {
+    pub fn new(keywords: Vec<Keyword>) -> KeywordStore {
+        let mut by_abbr: HashMap<String, Vec<usize>> = HashMap::new();
+        for (index, keyword) in keywords.iter().enumerate() {
+            by_abbr
+                .entry(keyword.abbr_name.clone())
+                .or_default()
+                .push(index);
+        }
+        KeywordStore {
+            keywords,
+            by_abbr,
+            loaded: true,
+        }
+    }
+
+    pub fn keywords(&self) -> &[Keyword] {
+        &self.keywords
+    }
+
+    pub fn len(&self) -> usize {
+        self.keywords.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keywords.is_empty()
+    }
+
+    /// Whether keywords were stored yet; an empty store may still be loading.
+    pub fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    /// All keywords with exactly this (case-sensitive) abbreviation.
+    pub fn get_by_abbr(&self, abbr_name: &str) -> Vec<&Keyword> {
+        self.by_abbr
+            .get(abbr_name)
+            .map(|indices| indices.iter().map(|&i| &self.keywords[i]).collect())
+            .unwrap_or_default()
+    }
+
+    /// Keywords carrying an RB or AUTOSAR classification, compared case-insensitively.
+    pub fn list_by_classification(
+        &self,
+        classification: &str,
+        page: usize,
+        page_size: usize,
+    ) -> KeywordPage {
+        let wanted = classification.to_lowercase();
+        let matches: Vec<&Keyword> = self
+            .keywords
+            .iter()
+            .filter(|k| {
+                k.rb_classifications
+                    .iter()
+                    .chain(&k.autosar_classifications)
+                    .any(|c| c.to_lowercase() == wanted)
+            })
+            .collect();
+        paginate(matches, page, page_size)
+    }
+
+    /// Search abbreviations and long names like the former webview search.
+    ///
+    /// The query is split into whitespace separated tokens (the frontend already
+    /// adds spelling suggestions and stems). Exact matches win; only if there
+    /// are none, fuzzy matches on abbreviation and English long name are returned.
+    pub fn search(&self, query: &str, page: usize, page_size: usize) -> KeywordPage {
+        let mut seen = HashSet::new();
+        let tokens: Vec<String> = query
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .filter(|token| seen.insert(token.clone()))
+            .collect();
+        if tokens.is_empty() {
+            return paginate(Vec::new(), page, page_size);
+        }
+
+        let stripped: Vec<String> = tokens.iter().map(|t| alphanumeric(t)).collect();
+        let mut matches: Vec<&Keyword> = self
+            .keywords
+            .iter()
+            .filter(|k| {
+                let names = [
+                    Some(k.abbr_name.as_str()),
+                    k.long_name_en.as_deref(),
+                    k.long_name_de.as_deref(),
+                ];
+                names
+                    .into_iter()
+                    .flatten()
+                    .map(alphanumeric)
+                    .any(|name| stripped.contains(&name))
+            })
+            .collect();
+
+        if matches.is_empty() {
+            let mut seen_abbr = HashSet::new();
+            matches = self
+                .keywords
+                .iter()
+                .filter(|k| {
+                    tokens.iter().any(|token| {
+                        fuzzy_matches(token, &k.abbr_name.to_lowercase())
+                            || k.long_name_en
+                                .as_deref()
+                                .is_some_and(|name| fuzzy_matches(token, &name.to_lowercase()))
+                    })
+                })
+                .filter(|k| seen_abbr.insert(k.abbr_name.as_str()))
+                .collect();
+        }
+
+        sort_results(&mut matches, &tokens);
+        paginate(matches, page, page_size)
+    }
+}
+
+fn alphanumeric(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect()
+}
+
+/// Whether `token` occurs in `text` with at most `FUZZY_THRESHOLD` errors per character.
+fn fuzzy_matches(token: &str, text: &str) -> bool {
+    let token: Vec<char> = token.chars().collect();
+    if token.is_empty() {
+        return false;
+    }
+    let max_errors = (token.len() as f32 * FUZZY_THRESHOLD).floor() as usize;
+    approximate_substring_distance(&token, text) <= max_errors
+}
+
+// Edit distance between the pattern and its best matching substring of `text`
+// (Sellers' algorithm: the match may start anywhere in the text).
+fn approximate_substring_distance(pattern: &[char], text: &str) -> usize {
+    let mut column: Vec<usize> = (0..=pattern.len()).collect();
+    let mut best = pattern.len();
+    for c in text.chars() {
+        let mut diagonal = column[0];
+        column[0] = 0;
+        for i in 1..=pattern.len() {
+            let above = column[i];
+            let cost = usize::from(pattern[i - 1] != c);
+            column[i] = (diagonal + cost).min(above + 1).min(column[i - 1] + 1);
+            diagonal = above;
+        }
+        best = best.min(column[pattern.len()]);
+    }
+    best
+}
+
+// Valid keywords first, then exact abbreviation matches, shorter abbreviations, alphabetical
+fn sort_results(results: &mut [&Keyword], tokens: &[String]) {
+    let is_valid = |k: &Keyword| {
+        k.life_cycle_state
+            .as_deref()
+            .is_some_and(|s| s.eq_ignore_ascii_case("valid"))
+    };
+    let is_exact = |k: &Keyword| tokens.contains(&k.abbr_name.to_lowercase());
+    results.sort_by(|a, b| {
+        is_valid(b)
+            .cmp(&is_valid(a))
+            .then_with(|| is_exact(b).cmp(&is_exact(a)))
+            .then_with(|| a.abbr_name.len().cmp(&b.abbr_name.len()))
+            .then_with(|| a.abbr_name.cmp(&b.abbr_name))
+    });
+}
+
+fn paginate(matches: Vec<&Keyword>, page: usize, page_size: usize) -> KeywordPage {
+    let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+    KeywordPage {
+        total: matches.len(),
+        items: matches
+            .into_iter()
+            .skip(page.saturating_mul(page_size))
+            .take(page_size)
+            .cloned()
+            .collect(),
+        page,
+        page_size,
+    }
+}
+
+/// The keywords currently in use.
+pub fn current(app: &AppHandle) -> Arc<KeywordStore> {
+    app.state::<KeywordState>().lock().unwrap().clone()
+}
+
+/// Replace the stored keywords and tell the webview with `keywords-ready`.
+pub fn replace(app: &AppHandle, keywords: Vec<Keyword>) {
+    let store = Arc::new(KeywordStore::new(keywords));
+    let count = store.len();
+    *app.state::<KeywordState>().lock().unwrap() = store;
+    log::info!("Keyword store now holds {} keywords", count);
+    app.emit("keywords-ready", KeywordsReady { count }).ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn keyword(abbr_name: &str, long_name_en: &str, life_cycle_state: &str) -> Keyword {
+        serde_json::from_value(json!({
+            "abbrName": abbr_name,
+            "longNameEn": long_name_en,
+            "lifeCycleState": life_cycle_state,
+            "rbClassifications": ["Descriptive"],
+        }))
+        .unwrap()
+    }
+
+    fn store() -> KeywordStore {
+        KeywordStore::new(vec![
+            keyword("Temp", "Temperature", "valid"),
+            keyword("Tmp", "Temporary", "valid"),
+            keyword("Tempr", "Temperature raw", "obsolete"),
+            keyword("Spd", "Speed", "valid"),
+            keyword("Spd", "Speed of the vehicle", "valid"),
+            keyword("VehSpd", "Vehicle speed", "valid"),
+            keyword("Oil", "Oil", "valid"),
+        ])
+    }
+
+    fn abbrs(page: &KeywordPage) -> Vec<&str> {
+        page.items.iter().map(|k| k.abbr_name.as_str()).collect()
+    }
+
+    #[test]
+    fn exact_matches_hide_fuzzy_ones() {
+        let page = store().search("temp", 0, DEFAULT_PAGE_SIZE);
+        assert_eq!(abbrs(&page), vec!["Temp"]);
+        // Long names match as a whole, ignoring case and punctuation
+        let page = store().search("vehicle-speed", 0, DEFAULT_PAGE_SIZE);
+        assert_eq!(abbrs(&page), vec!["VehSpd"]);
+    }
+
+    #[test]
+    fn fuzzy_matches_when_nothing_matches_exactly() {
+        let page = store().search("temprature", 0, DEFAULT_PAGE_SIZE);
+        // "Temporary" is within the threshold as well; valid keywords first, then
+        // shorter abbreviations
+        assert_eq!(abbrs(&page), vec!["Tmp", "Temp", "Tempr"]);
+        // A keyword whose abbreviation occurs twice is listed once
+        let page = store().search("vehicel", 0, DEFAULT_PAGE_SIZE);
+        assert_eq!(abbrs(&page), vec!["Spd", "VehSpd"]);
+    }
+
+    #[test]
+    fn valid_and_exact_keywords_sort_first() {
+        let page = store().search("oil spd tempr", 0, DEFAULT_PAGE_SIZE);
+        assert_eq!(abbrs(&page), vec!["Oil", "Spd", "Spd", "Tempr"]);
+    }
+
+    #[test]
+    fn fuzzy_threshold_allows_forty_percent_errors() {
+        assert!(fuzzy_matches("speed", "vehicle spead"));
+        assert!(fuzzy_matches("speed", "spxxd"));
+        assert!(!fuzzy_matches("speed", "sxxxd"));
+        // Two characters allow no error
+        assert!(fuzzy_matches("ab", "cab"));
+        assert!(!fuzzy_matches("ab", "ac"));
+        assert!(!fuzzy_matches("", "anything"));
+    }
+
+    #[test]
+    fn empty_query_finds_nothing() {
+        let page = store().search("   ", 0, DEFAULT_PAGE_SIZE);
+        assert_eq!(page.total, 0);
+        assert!(page.items.is_empty());
+    }
+
+    #[test]
+    fn pages_are_bounded() {
+        let store = store();
+        let all: Vec<&Keyword> = store.keywords().iter().collect();
+        let page = paginate(all.clone(), 1, 3);
+        assert_eq!(abbrs(&page), vec!["Spd", "Spd", "VehSpd"]);
+        assert_eq!((page.total, page.page, page.page_size), (7, 1, 3));
+        assert_eq!(abbrs(&paginate(all.clone(), 2, 3)), vec!["Oil"]);
+        assert!(paginate(all.clone(), 3, 3).items.is_empty());
+        assert!(paginate(all.clone(), usize::MAX, 3).items.is_empty());
+        assert_eq!(paginate(all.clone(), 0, 0).page_size, 1);
+        assert_eq!(paginate(all, 0, usize::MAX).page_size, MAX_PAGE_SIZE);
+    }
+
+    #[test]
+    fn lookups_by_abbreviation_and_classification() {
+        let store = store();
+        assert_eq!(store.get_by_abbr("Spd").len(), 2);
+        assert!(store.get_by_abbr("spd").is_empty());
+        let page = store.list_by_classification("descriptive", 0, 2);
+        assert_eq!(page.total, 7);
+        assert_eq!(abbrs(&page), vec!["Temp", "Tmp"]);
+        assert_eq!(store.list_by_classification("Physical", 0, 2).total, 0);
+    }
+}
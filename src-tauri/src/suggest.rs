@@ -0,0 +1,621 @@
+// Label suggestions from a plain-English (or German) description
+//
+// Words of the description are matched against keyword long names, longest phrase
+// first, e.g. "rear left wheel speed" -> Re, Le, Whl and the Physical keyword n whose
+// description is "(rotational) speed". The combinations are assembled into
+// `<Id>_<pp><DescriptiveName>_<Ex>` labels and only those the active rule set
+// accepts are returned.
+use crate::keyword::Keyword;
+use crate::label::{self, LabelPart};
+use crate::rules::RuleSet;
+use crate::semantic::STOP_WORDS;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+/// Physical keywords tried per description, best matches first.
+const MAX_PHYSICAL_OPTIONS: usize = 3;
+/// Partial descriptive names kept while walking through the description.
+const BEAM_WIDTH: usize = 64;
+/// Descriptions longer than this are prose, not a name for the quantity.
+const MAX_DEFINITION_WORDS: usize = 4;
+
+/// One part of a suggested label and why it was chosen.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedPart {
+    pub part: LabelPart,
+    pub abbr_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long_name_en: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelSuggestion {
+    pub label: String,
+    /// Share of the description words the label covers, 0 to 1.
+    pub coverage: f32,
+    pub parts: Vec<SuggestedPart>,
+    /// Description words no keyword was used for.
+    pub unmatched: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Source {
+    LongNameEn,
+    LongNameDe,
+    Description,
+}
+
+// A keyword whose name covers the content words `start..start + len`
+#[derive(Debug, Clone)]
+struct Match<'a> {
+    keyword: &'a Keyword,
+    start: usize,
+    len: usize,
+    source: Source,
+    /// Matched without stripping plural endings.
+    exact: bool,
+}
+
+impl Match<'_> {
+    fn covers(&self, position: usize) -> bool {
+        (self.start..self.start + self.len).contains(&position)
+    }
+}
+
+// Content words of a keyword name with the keyword and where they came from
+type Phrase<'a> = (Vec<String>, &'a Keyword, Source);
+
+// Descriptive name under construction
+#[derive(Debug, Clone, Default)]
+struct Partial<'a> {
+    parts: Vec<&'a Match<'a>>,
+    skipped: Vec<usize>,
+    score: i32,
+}
+
+/// Suggest up to `limit` valid labels for `description`, best first.
+///
+/// `id` and `extension` are used as given; without an extension, Extension keywords
+/// named in the description are offered as one.
+pub fn suggest_labels(
+    id: &str,
+    description: &str,
+    extension: Option<&str>,
+    keywords: &[Keyword],
+    rules: &RuleSet,
+    limit: usize,
+) -> Vec<LabelSuggestion> {
+    let words = content_words(description);
+    if words.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+    let matches = find_matches(&words, keywords);
+
+    // Only the first physical classification with matches is used, e.g. Logical
+    // keywords only when the description names no Physical one
+    let is_physical = |m: &&Match| is_physical_abbr(m.keyword);
+    let classification = rules.classifications.physical.iter().find(|c| {
+        matches
+            .iter()
+            .filter(is_physical)
+            .any(|m| m.keyword.has_classification(c))
+    });
+    let mut physical: Vec<&Match> = matches
+        .iter()
+        .filter(is_physical)
+        .filter(|m| classification.is_some_and(|c| m.keyword.has_classification(c)))
+        .collect();
+    physical.sort_by_key(|m| {
+        (
+            m.source == Source::Description,
+            Reverse(m.len),
+            !m.exact,
+            !is_valid(m.keyword),
+            m.keyword.abbr_name.len(),
+        )
+    });
+    physical.truncate(MAX_PHYSICAL_OPTIONS);
+
+    let extensions: Vec<Option<&Match>> = if extension.is_some() {
+        vec![None]
+    } else {
+        let mut options: Vec<Option<&Match>> = matches
+            .iter()
+            .filter(|m| rules.is_extension(m.keyword) && m.source != Source::Description)
+            .map(Some)
+            .collect();
+        options.push(None);
+        options
+    };
+
+    let mut suggestions = Vec::new();
+    let mut seen_labels = HashSet::new();
+    for pp in &physical {
+        for ex in &extensions {
+            let taken =
+                |position: usize| pp.covers(position) || ex.is_some_and(|e| e.covers(position));
+            for partial in descriptive_names(&words, &matches, rules, &taken) {
+                if partial.parts.is_empty() {
+                    continue;
+                }
+                let suggestion = assemble(id, pp, &partial, *ex, extension, &words, keywords);
+                if suggestion.label.len() > rules.max_length
+                    || !label::validate_label_with(&suggestion.label, keywords, rules).valid
+                {
+                    continue;
+                }
+                if seen_labels.insert(suggestion.label.clone()) {
+                    suggestions.push((partial.score, suggestion));
+                }
+            }
+        }
+    }
+
+    suggestions.sort_by(|(a_score, a), (b_score, b)| {
+        b.coverage
+            .total_cmp(&a.coverage)
+            .then_with(|| b_score.cmp(a_score))
+            .then_with(|| a.label.len().cmp(&b.label.len()))
+            .then_with(|| a.label.cmp(&b.label))
+    });
+    suggestions
+        .into_iter()
+        .take(limit)
+        .map(|(_, suggestion)| suggestion)
+        .collect()
+}
+
+// Every keyword whose English or German long name equals a run of description
+// words, plus Physical keywords whose short description does.
+fn find_matches<'a>(words: &[String], keywords: &'a [Keyword]) -> Vec<Match<'a>> {
+    let stems: Vec<String> = words.iter().map(|w| stem(w)).collect();
+    let mut phrases: HashMap<&str, Vec<Phrase>> = HashMap::new();
+    let mut add = |text: &Option<String>, keyword: &'a Keyword, source: Source| {
+        let Some(text) = text else { return };
+        // "(rotational) speed" names a speed, the parenthesis only qualifies it
+        let text = match source {
+            Source::Description => without_parentheses(text),
+            _ => text.clone(),
+        };
+        let phrase = content_words(&text);
+        if phrase.is_empty()
+            || (source == Source::Description && phrase.len() > MAX_DEFINITION_WORDS)
+        {
+            return;
+        }
+        // Keyed by the first stem, looked up with the stems of the description
+        let first = stem(&phrase[0]);
+        if let Some(first) = stems.iter().find(|s| **s == first) {
+            phrases
+                .entry(first.as_str())
+                .or_default()
+                .push((phrase, keyword, source));
+        }
+    };
+    for keyword in keywords.iter().filter(|k| !is_retired(k)) {
+        add(&keyword.long_name_en, keyword, Source::LongNameEn);
+        add(&keyword.long_name_de, keyword, Source::LongNameDe);
+        if keyword.has_classification("Physical") {
+            add(&keyword.description_en, keyword, Source::Description);
+            add(&keyword.description_de, keyword, Source::Description);
+        }
+    }
+
+    let mut matches = Vec::new();
+    let mut seen = HashSet::new();
+    for (start, word) in stems.iter().enumerate() {
+        for (phrase, keyword, source) in phrases.get(word.as_str()).into_iter().flatten() {
+            let end = start + phrase.len();
+            if end <= stems.len()
+                && stems[start..end]
+                    .iter()
+                    .zip(phrase)
+                    .all(|(s, p)| *s == stem(p))
+                && seen.insert((start, phrase.len(), *keyword as *const Keyword))
+            {
+                matches.push(Match {
+                    keyword,
+                    start,
+                    len: phrase.len(),
+                    source: *source,
+                    exact: words[start..end] == phrase[..],
+                });
+            }
+        }
+    }
+    matches
+}
+
+// Walk through the description, either using a keyword for the next words or
+// skipping a word, and keep the best partial names at every position.
+fn descriptive_names<'a>(
+    words: &[String],
+    matches: &'a [Match<'a>],
+    rules: &RuleSet,
+    taken: &dyn Fn(usize) -> bool,
+) -> Vec<Partial<'a>> {
+    let usable: Vec<&Match> = matches
+        .iter()
+        .filter(|m| is_descriptive(m.keyword, rules) && m.source != Source::Description)
+        .filter(|m| !(m.start..m.start + m.len).any(taken))
+        .collect();
+
+    // beams[i] holds partial names that covered the first i words
+    let mut beams: Vec<Vec<Partial>> = vec![Vec::new(); words.len() + 1];
+    beams[0].push(Partial::default());
+    for position in 0..words.len() {
+        let mut current = std::mem::take(&mut beams[position]);
+        current.sort_by_key(|partial| Reverse(partial.score));
+        current.truncate(BEAM_WIDTH);
+        for partial in &current {
+            if taken(position) {
+                beams[position + 1].push(partial.clone());
+                continue;
+            }
+            let mut skipped = partial.clone();
+            skipped.skipped.push(position);
+            skipped.score -= 2;
+            beams[position + 1].push(skipped);
+
+            for m in usable.iter().filter(|m| m.start == position) {
+                if partial
+                    .parts
+                    .iter()
+                    .any(|p| p.keyword.abbr_name == m.keyword.abbr_name)
+                {
+                    continue;
+                }
+                let mut next = partial.clone();
+                next.parts.push(m);
+                // Covering words counts most, then fewer keywords, then exactly named and
+                // valid ones; a keyword costs more than both bonuses together
+                next.score +=
+                    3 * (m.len as i32 - 1) + i32::from(m.exact) + i32::from(is_valid(m.keyword));
+                beams[m.start + m.len].push(next);
+            }
+        }
+        beams[position] = current;
+    }
+    let mut finished = std::mem::take(&mut beams[words.len()]);
+    finished.sort_by_key(|partial| Reverse(partial.score));
+    finished.truncate(BEAM_WIDTH);
+    finished
+}
+
+fn assemble(
+    id: &str,
+    pp: &Match,
+    partial: &Partial,
+    ex: Option<&Match>,
+    extension: Option<&str>,
+    words: &[String],
+    keywords: &[Keyword],
+) -> LabelSuggestion {
+    let ex_abbr = extension.or(ex.map(|m| m.keyword.abbr_name.as_str()));
+    let descriptive: String = partial
+        .parts
+        .iter()
+        .map(|m| m.keyword.abbr_name.as_str())
+        .collect();
+    let label = match ex_abbr {
+        Some(ex) => format!("{}_{}{}_{}", id, pp.keyword.abbr_name, descriptive, ex),
+        None => format!("{}_{}{}", id, pp.keyword.abbr_name, descriptive),
+    };
+    let mut parts = vec![SuggestedPart {
+        part: LabelPart::Id,
+        abbr_name: id.to_string(),
+        long_name_en: None,
+        reason: "ID prefix as entered".to_string(),
+    }];
+    parts.push(suggested_part(LabelPart::Physical, pp, words));
+    parts.extend(
+        partial
+            .parts
+            .iter()
+            .map(|m| suggested_part(LabelPart::Descriptive, m, words)),
+    );
+    match (extension, ex) {
+        (Some(extension), _) => parts.push(SuggestedPart {
+            part: LabelPart::Extension,
+            abbr_name: extension.to_string(),
+            long_name_en: keywords
+                .iter()
+                .find(|k| k.abbr_name == extension && k.is_extension_only())
+                .and_then(|k| k.long_name_en.clone()),
+            reason: "Extension as entered".to_string(),
+        }),
+        (None, Some(ex)) => parts.push(suggested_part(LabelPart::Extension, ex, words)),
+        (None, None) => {}
+    }
+
+    let used: HashSet<usize> = partial
+        .parts
+        .iter()
+        .copied()
+        .chain([pp])
+        .chain(ex)
+        .flat_map(|m| m.start..m.start + m.len)
+        .collect();
+    LabelSuggestion {
+        label,
+        coverage: used.len() as f32 / words.len() as f32,
+        parts,
+        unmatched: partial.skipped.iter().map(|&i| words[i].clone()).collect(),
+    }
+}
+
+fn suggested_part(part: LabelPart, m: &Match, words: &[String]) -> SuggestedPart {
+    let phrase = words[m.start..m.start + m.len].join(" ");
+    let keyword = m.keyword;
+    let name = |text: &Option<String>| text.clone().unwrap_or_default();
+    let kind = match part {
+        LabelPart::Physical => "physical quantity ",
+        LabelPart::Extension => "extension ",
+        _ => "",
+    };
+    let reason = match m.source {
+        Source::LongNameEn => format!(
+            "\"{}\" is the long name of {}{}",
+            phrase, kind, keyword.abbr_name
+        ),
+        Source::LongNameDe => format!(
+            "\"{}\" is the German long name of {}{} ({})",
+            phrase,
+            kind,
+            keyword.abbr_name,
+            name(&keyword.long_name_en)
+        ),
+        Source::Description => format!(
+            "\"{}\" matches the description of {}{} ({})",
+            phrase,
+            kind,
+            keyword.abbr_name,
+            name(&keyword.description_en)
+        ),
+    };
+    SuggestedPart {
+        part,
+        abbr_name: keyword.abbr_name.clone(),
+        long_name_en: keyword.long_name_en.clone(),
+        reason,
+    }
+}
+
+fn content_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .filter(|w| !STOP_WORDS.contains(&w.as_str()))
+        .collect()
+}
+
+// Plural and genitive endings, so "wheels" finds "Wheel"
+fn stem(word: &str) -> String {
+    if word.len() > 4
+        && let Some(base) = word.strip_suffix("ies")
+    {
+        return format!("{}y", base);
+    }
+    if word.len() > 3 && word.ends_with('s') && !word.ends_with("ss") {
+        return word[..word.len() - 1].to_string();
+    }
+    word.to_string()
+}
+
+fn without_parentheses(text: &str) -> String {
+    let mut depth = 0usize;
+    text.chars()
+        .filter(|&c| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                _ => return depth == 0,
+            }
+            false
+        })
+        .collect()
+}
+
+fn is_valid(keyword: &Keyword) -> bool {
+    keyword
+        .life_cycle_state
+        .as_deref()
+        .is_some_and(|s| s.eq_ignore_ascii_case("valid"))
+}
+
+fn is_retired(keyword: &Keyword) -> bool {
+    keyword.is_obsolete()
+        || keyword
+            .life_cycle_state
+            .as_deref()
+            .is_some_and(|s| s.eq_ignore_ascii_case("removed"))
+}
+
+// `split_label` takes the first letter and the following lowercase letters as <pp>
+fn is_physical_abbr(keyword: &Keyword) -> bool {
+    let mut chars = keyword.abbr_name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+}
+
+// One camel case word, which `split_label` will find again
+fn is_descriptive(keyword: &Keyword, rules: &RuleSet) -> bool {
+    let mut chars = keyword.abbr_name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_uppercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && rules.is_descriptive(keyword)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keyword::test_keyword;
+
+    fn keywords() -> Vec<Keyword> {
+        let mut n = test_keyword("n", "Physical", "rotational speed");
+        n.long_name_de = Some("Drehzahl".to_string());
+        n.description_en = Some("(rotational) speed".to_string());
+        let mut whl = test_keyword("Whl", "Descriptive", "wheel");
+        whl.long_name_de = Some("Rad".to_string());
+        vec![
+            n,
+            test_keyword("t", "Physical", "temperature"),
+            test_keyword("st", "Logical", "status"),
+            test_keyword("Re", "Descriptive", "rear"),
+            test_keyword("Le", "Descriptive", "left"),
+            test_keyword("Rl", "Descriptive", "rear left"),
+            whl,
+            test_keyword("Wh", "Descriptive", "wheel").replaced_by("Whl"),
+            test_keyword("Raw", "Descriptive", "raw value"),
+            test_keyword("C", "Extension", "calibration"),
+        ]
+    }
+
+    fn suggest(description: &str, extension: Option<&str>) -> Vec<LabelSuggestion> {
+        suggest_labels(
+            "RB",
+            description,
+            extension,
+            &keywords(),
+            &RuleSet::default(),
+            10,
+        )
+    }
+
+    fn labels(suggestions: &[LabelSuggestion]) -> Vec<&str> {
+        suggestions.iter().map(|s| s.label.as_str()).collect()
+    }
+
+    #[test]
+    fn longest_phrases_are_preferred() {
+        let suggestions = suggest("rear left wheel speed, raw value", None);
+        assert_eq!(
+            labels(&suggestions)[..2],
+            ["RB_nRlWhlRaw", "RB_nReLeWhlRaw"]
+        );
+
+        let best = &suggestions[0];
+        assert_eq!(best.coverage, 1.0);
+        assert!(best.unmatched.is_empty());
+        let parts: Vec<(LabelPart, &str)> = best
+            .parts
+            .iter()
+            .map(|p| (p.part, p.abbr_name.as_str()))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                (LabelPart::Id, "RB"),
+                (LabelPart::Physical, "n"),
+                (LabelPart::Descriptive, "Rl"),
+                (LabelPart::Descriptive, "Whl"),
+                (LabelPart::Descriptive, "Raw"),
+            ]
+        );
+        assert_eq!(
+            best.parts[1].reason,
+            "\"speed\" matches the description of physical quantity n ((rotational) speed)"
+        );
+        assert_eq!(best.parts[2].reason, "\"rear left\" is the long name of Rl");
+    }
+
+    #[test]
+    fn every_suggestion_is_a_valid_label() {
+        let keywords = keywords();
+        for suggestion in suggest("rear left wheel speed, raw value", None) {
+            let report =
+                label::validate_label_with(&suggestion.label, &keywords, &RuleSet::default());
+            assert!(report.valid, "{} is invalid", suggestion.label);
+        }
+    }
+
+    #[test]
+    fn unmatched_words_lower_the_coverage() {
+        let suggestions = suggest("rear wheels speed filtered", None);
+        let best = &suggestions[0];
+        assert_eq!(best.label, "RB_nReWhl");
+        assert_eq!(best.coverage, 0.75);
+        assert_eq!(best.unmatched, vec!["filtered"]);
+    }
+
+    #[test]
+    fn german_long_names_match() {
+        let suggestions = suggest("Drehzahl vom Rad", None);
+        assert_eq!(suggestions[0].label, "RB_nWhl");
+        assert_eq!(
+            suggestions[0].parts[2].reason,
+            "\"rad\" is the German long name of Whl (wheel)"
+        );
+    }
+
+    #[test]
+    fn extensions_named_in_the_description_are_offered() {
+        let named = suggest("wheel speed calibration", None);
+        assert_eq!(labels(&named)[..2], ["RB_nWhl_C", "RB_nWhl"]);
+        assert_eq!(named[1].unmatched, vec!["calibration"]);
+
+        let given = suggest("wheel speed", Some("C"));
+        assert_eq!(labels(&given), vec!["RB_nWhl_C"]);
+        let extension = given[0].parts.last().unwrap();
+        assert_eq!(extension.part, LabelPart::Extension);
+        assert_eq!(extension.long_name_en.as_deref(), Some("calibration"));
+        assert_eq!(extension.reason, "Extension as entered");
+    }
+
+    #[test]
+    fn logical_keywords_only_without_a_physical_match() {
+        assert_eq!(suggest("wheel status", None)[0].label, "RB_stWhl");
+        let with_speed = suggest("wheel speed status", None);
+        assert_eq!(with_speed[0].label, "RB_nWhl");
+        assert_eq!(with_speed[0].unmatched, vec!["status"]);
+    }
+
+    #[test]
+    fn keywords_are_used_once_and_obsolete_ones_not_at_all() {
+        let suggestions = suggest("wheel wheel speed", None);
+        assert_eq!(labels(&suggestions), vec!["RB_nWhl"]);
+        assert!(
+            suggestions
+                .iter()
+                .flat_map(|s| &s.parts)
+                .all(|part| part.abbr_name != "Wh")
+        );
+    }
+
+    #[test]
+    fn labels_longer_than_the_rule_set_allows_are_dropped() {
+        let rules = RuleSet {
+            max_length: 8,
+            ..RuleSet::default()
+        };
+        let suggestions =
+            suggest_labels("RB", "rear left wheel speed", None, &keywords(), &rules, 10);
+        assert!(!suggestions.is_empty());
+        assert!(suggestions.iter().all(|s| s.label.len() <= 8));
+        assert!(!labels(&suggestions).contains(&"RB_nRlWhl"));
+    }
+
+    #[test]
+    fn nothing_to_suggest() {
+        assert!(suggest("", None).is_empty());
+        assert!(suggest("of the and", None).is_empty());
+        // A descriptive name needs at least one keyword
+        assert!(suggest("speed", None).is_empty());
+        let rules = RuleSet::default();
+        assert!(suggest_labels("RB", "wheel speed", None, &keywords(), &rules, 0).is_empty());
+    }
+
+    #[test]
+    fn stems_and_parentheses() {
+        assert_eq!(stem("wheels"), "wheel");
+        assert_eq!(stem("batteries"), "battery");
+        assert_eq!(stem("pressure"), "pressure");
+        assert_eq!(stem("class"), "class");
+        assert_eq!(stem("bus"), "bus");
+        assert_eq!(without_parentheses("(rotational) speed"), " speed");
+    }
+}
@@ -0,0 +1,280 @@
+// Incremental keyword sync
+//
+// The `updatedDate` of the versions endpoint is stored in the keyword cache manifest
+// when keywords are downloaded. On later starts the cached keywords are used right
+// away and the full payload is only downloaded again when the server reports newer data.
+use crate::cache;
+use crate::error::{self, AppError, AppResult};
+use crate::fetch::{self, FetchError};
+use crate::history;
+use crate::keyword::{self, Keyword};
+use crate::semantic;
+use crate::settings::{self, BackendSettings};
+use crate::store;
+use crate::tray::{self, LoaderStatus};
+use chrono::DateTime;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Payload of the `keywords-updated` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordsUpdated {
+    pub updated_date: String,
+    /// Formatted for the title bar, e.g. `DB: 13.06.2025`.
+    pub version: String,
+    pub count: usize,
+}
+
+/// `updatedDate` of the keyword database on the server.
+pub async fn fetch_updated_date(settings: &BackendSettings) -> AppResult<String> {
+    let api_url = settings.version_url();
+    let response = settings
+        .http_client()
+        .get(&api_url)
+        .send()
+        .await
+        .map_err(|e| AppError::network(format!("Failed to make API request: {}", e)))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AppError::Network {
+            message: format!("API request failed with status: {}", status),
+            retryable: status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS,
+        });
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| AppError::network(format!("Failed to read response body: {}", e)))?;
+    let json: serde_json::Value = serde_json::from_slice(&bytes)
+        .map_err(|e| AppError::Parse(format!("Failed to parse JSON response: {}", e)))?;
+    let updated_date = json
+        .get("updatedDate")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| {
+            AppError::Parse("updatedDate field not found in the response".to_string())
+        })?;
+    log::info!("Version API returned updatedDate: {}", updated_date);
+    Ok(updated_date.to_string())
+}
+
+/// Format an `updatedDate` like `2025-06-13T06:52:11Z` as `DB: 13.06.2025`.
+pub fn format_version_date(updated_date: &str) -> String {
+    let date_part = updated_date.get(0..10).unwrap_or("");
+    if date_part.len() == 10
+        && date_part.chars().nth(4) == Some('-')
+        && date_part.chars().nth(7) == Some('-')
+    {
+        let year = &date_part[0..4];
+        let month = &date_part[5..7];
+        let day = &date_part[8..10];
+        format!("DB: {}.{}.{}", day, month, year)
+    } else {
+        // Fallback if date format unexpected
+        format!("DB: {}", updated_date)
+    }
+}
+
+/// Whether the server data is newer than the cached one.
+///
+/// Dates that are not RFC 3339 are only compared for equality.
+pub fn is_newer(server: &str, cached: &str) -> bool {
+    match (
+        DateTime::parse_from_rfc3339(server),
+        DateTime::parse_from_rfc3339(cached),
+    ) {
+        (Ok(server), Ok(cached)) => server > cached,
+        _ => server != cached,
+    }
+}
+
+/// Keep the cached keywords as snapshot and send what changed since the previous one.
+///
+/// Emits `keyword-changes` with the diff when the keywords changed and there is an
+/// earlier snapshot. Failures are reported as `backend-error`, they do not affect loading.
+pub fn record_snapshot(app: &AppHandle, dir: &Path) {
+    let recorded = history::record(dir).and_then(|snapshot| match snapshot {
+        Some(snapshot) => history::diff(dir, None, Some(&snapshot.id)),
+        None => Ok(None),
+    });
+    match recorded {
+        Ok(Some(diff)) => {
+            log::info!(
+                "Keywords changed from {} to {}: {:?}",
+                diff.from.version(),
+                diff.to.version(),
+                diff.summary
+            );
+            app.emit("keyword-changes", &diff).ok();
+        }
+        Ok(None) => {}
+        Err(e) => error::emit(app, "keyword-history", &e),
+    }
+}
+
+/// Download the complete keyword list, keep it in the cache as version `updated_date`
+/// and swap it in. `on_retry` is called before each new attempt after a failed one.
+///
+/// Emits `keyword-parse-issues` for entries that could not be read. A cache that cannot
+/// be written is reported as `backend-error`, the downloaded keywords are used anyway.
+/// Returns the number of keywords.
+pub async fn download_and_store(
+    app: &AppHandle,
+    settings: &BackendSettings,
+    updated_date: Option<&str>,
+    on_retry: impl FnMut(usize, usize, &FetchError, Duration),
+) -> AppResult<usize> {
+    let parsed = fetch::fetch_keywords(
+        &settings.http_client(),
+        &settings.keywords_url(),
+        &fetch::RetryPolicy::default(),
+        on_retry,
+    )
+    .await?;
+    keyword::log_issues("keyword API response", &parsed.issues);
+    if !parsed.issues.is_empty() {
+        app.emit("keyword-parse-issues", &parsed.issues).ok();
+    }
+
+    let count = parsed.keywords.len();
+    match store_in_cache(app, &parsed.keywords, updated_date) {
+        Ok(dir) => {
+            record_snapshot(app, &dir);
+            let keywords = parsed.keywords.clone();
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || semantic::rebuild(&app, &keywords));
+        }
+        Err(e) => error::emit(app, "keyword-cache", &e),
+    }
+    store::replace(app, parsed.keywords);
+    Ok(count)
+}
+
+// The cache directory the keywords were written to
+fn store_in_cache(
+    app: &AppHandle,
+    keywords: &[Keyword],
+    updated_date: Option<&str>,
+) -> AppResult<PathBuf> {
+    let dir = data_dir(app)?;
+    let json_string = serde_json::to_string_pretty(keywords)
+        .map_err(|e| AppError::Parse(format!("Failed to serialize keywords: {}", e)))?;
+    cache::store_keywords(&dir, &json_string, keywords.len(), updated_date)?;
+    Ok(dir)
+}
+
+fn data_dir(app: &AppHandle) -> AppResult<PathBuf> {
+    app.path()
+        .app_local_data_dir()
+        .map_err(|e| AppError::CacheIo(format!("Could not determine app data directory: {}", e)))
+}
+
+/// Check the server version and swap in fresher keywords while the cached ones stay in use,
+/// `force` downloads them even when the cache is current.
+///
+/// Emits `keywords-updated` after new keywords were stored, `keywords-up-to-date`
+/// when the cache is current. Failures are reported as `backend-error`, the cache
+/// stays in use and the tray shows the loader offline.
+pub async fn refresh_keywords(app: AppHandle, force: bool) {
+    tray::set_loader(&app, LoaderStatus::Loading);
+    match try_refresh_keywords(&app, force).await {
+        Ok(()) => tray::set_loader(&app, LoaderStatus::Online),
+        Err(e) => {
+            error::emit(&app, "keyword-sync", &e);
+            let status = if store::current(&app).is_loaded() {
+                LoaderStatus::Offline
+            } else {
+                LoaderStatus::Error(e.message().to_string())
+            };
+            tray::set_loader(&app, status);
+        }
+    }
+}
+
+async fn try_refresh_keywords(app: &AppHandle, force: bool) -> AppResult<()> {
+    let dir = data_dir(app)?;
+    let backend_settings = settings::current(app);
+    let updated_date = fetch_updated_date(&backend_settings).await?;
+    let version = format_version_date(&updated_date);
+    tray::set_db_version(app, &version);
+
+    match cache::manifest(&dir).and_then(|manifest| manifest.updated_date) {
+        _ if force => log::info!("Downloading keywords {} on request", updated_date),
+        Some(cached) if !is_newer(&updated_date, &cached) => {
+            log::info!("Cached keywords are up to date ({})", cached);
+            app.emit("keywords-up-to-date", version).ok();
+            return Ok(());
+        }
+        Some(cached) => log::info!(
+            "Server keywords {} are newer than cached {}, downloading",
+            updated_date,
+            cached
+        ),
+        None => log::info!("Cached keywords have no version information, downloading"),
+    }
+
+    let count = download_and_store(
+        app,
+        &backend_settings,
+        Some(&updated_date),
+        |retry, max_retries, error, delay| {
+            log::warn!(
+                "Keyword sync retry {} of {} in {:?} after: {}",
+                retry,
+                max_retries,
+                delay,
+                error
+            )
+        },
+    )
+    .await?;
+    app.emit(
+        "keywords-updated",
+        KeywordsUpdated {
+            version,
+            updated_date,
+            count,
+        },
+    )
+    .ok();
+    log::info!("Swapped in {} fresher keywords", count);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_version_dates() {
+        assert_eq!(
+            format_version_date("2025-06-13T06:52:11Z"),
+            "DB: 13.06.2025"
+        );
+        assert_eq!(format_version_date("2025-06-13"), "DB: 13.06.2025");
+        assert_eq!(format_version_date("13.06.2025"), "DB: 13.06.2025");
+        assert_eq!(format_version_date("2025/06/13"), "DB: 2025/06/13");
+        assert_eq!(format_version_date(""), "DB: ");
+    }
+
+    #[test]
+    fn compares_rfc_3339_dates_by_time() {
+        assert!(is_newer("2025-06-14T00:00:00Z", "2025-06-13T06:52:11Z"));
+        assert!(!is_newer("2025-06-13T06:52:11Z", "2025-06-13T06:52:11Z"));
+        assert!(!is_newer("2025-06-12T00:00:00Z", "2025-06-13T06:52:11Z"));
+        // Same instant in another time zone
+        assert!(!is_newer(
+            "2025-06-13T08:52:11+02:00",
+            "2025-06-13T06:52:11Z"
+        ));
+    }
+
+    #[test]
+    fn compares_other_dates_for_equality() {
+        assert!(!is_newer("13.06.2025", "13.06.2025"));
+        assert!(is_newer("12.06.2025", "13.06.2025"));
+        assert!(is_newer("2025-06-13T06:52:11Z", "13.06.2025"));
+    }
+}
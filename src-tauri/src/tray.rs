@@ -0,0 +1,262 @@
+// System tray: menu, keyword loader state and the labels checked recently
+//
+// The menu shows the keyword database date and the age of the cached copy, refreshes the
+// database on demand, switches to working offline with the cached keywords.json and lists
+// the recently validated labels, newest first. The tooltip names the loader state and the
+// last checked label; the icon gets a green, amber or red dot for a valid label, a valid
+// label with warnings and an invalid label.
+use crate::cache;
+use crate::label::LabelReport;
+use crate::settings;
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tauri::image::Image;
+use tauri::menu::{CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder};
+use tauri::{AppHandle, Manager, Wry};
+
+pub const TRAY_ID: &str = "main";
+/// Menu item id of a recent label, followed by its index.
+pub const RECENT_PREFIX: &str = "recent:";
+pub const REFRESH_ID: &str = "refresh-keywords";
+pub const OFFLINE_ID: &str = "work-offline";
+const TOOLTIP: &str = "BBM Label Explorer";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Valid,
+    /// Valid, but with warnings.
+    Warning,
+    Invalid,
+}
+
+impl Verdict {
+    pub fn of(report: &LabelReport) -> Verdict {
+        if !report.valid {
+            Verdict::Invalid
+        } else if !report.warnings.is_empty() {
+            Verdict::Warning
+        } else {
+            Verdict::Valid
+        }
+    }
+
+    fn rgb(self) -> [u8; 3] {
+        match self {
+            Verdict::Valid => [46, 160, 67],
+            Verdict::Warning => [237, 161, 0],
+            Verdict::Invalid => [215, 38, 38],
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Verdict::Valid => "✔",
+            Verdict::Warning => "⚠",
+            Verdict::Invalid => "✖",
+        }
+    }
+}
+
+/// State of the keyword loader shown in the tooltip.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum LoaderStatus {
+    #[default]
+    Loading,
+    /// Keywords are current with the server.
+    Online,
+    /// The cached keywords are in use, by choice or because the server is unreachable.
+    Offline,
+    /// No keywords could be loaded.
+    Error(String),
+}
+
+impl LoaderStatus {
+    fn describe(&self) -> String {
+        match self {
+            LoaderStatus::Loading => "Loading keywords...".to_string(),
+            LoaderStatus::Online => "Online".to_string(),
+            LoaderStatus::Offline => "Offline, using cached keywords".to_string(),
+            LoaderStatus::Error(message) => format!("Error: {}", message),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RecentLabel {
+    pub label: String,
+    pub verdict: Verdict,
+}
+
+#[derive(Debug, Default)]
+pub struct TrayInfo {
+    /// Labels validated recently, newest first.
+    recent: VecDeque<RecentLabel>,
+    loader: LoaderStatus,
+    /// Keyword database date, e.g. `DB: 13.06.2025`.
+    db_version: Option<String>,
+    /// Verdict of the last checked label for the tooltip.
+    last_check: Option<String>,
+}
+
+pub type TrayState = Mutex<TrayInfo>;
+
+/// The tray menu for the current state.
+pub fn menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let (recent, loader, db_version) = {
+        let info = app.state::<TrayState>().lock().unwrap();
+        (
+            info.recent.clone(),
+            info.loader.clone(),
+            info.db_version.clone(),
+        )
+    };
+    let work_offline = settings::current(app).work_offline;
+
+    let mut database = db_version.unwrap_or_else(|| "DB: unknown".to_string());
+    let fetched_at = app
+        .path()
+        .app_local_data_dir()
+        .ok()
+        .and_then(|dir| cache::manifest(&dir))
+        .and_then(|manifest| DateTime::parse_from_rfc3339(&manifest.fetched_at).ok());
+    if let Some(fetched_at) = fetched_at {
+        let age = Utc::now().signed_duration_since(fetched_at);
+        database.push_str(&format!(", cached {} ago", format_age(age.num_minutes())));
+    }
+    let database = MenuItemBuilder::new(database)
+        .id("database")
+        .enabled(false)
+        .build(app)?;
+    let refresh = MenuItemBuilder::new("Refresh keyword database")
+        .id(REFRESH_ID)
+        .enabled(!work_offline && loader != LoaderStatus::Loading)
+        .build(app)?;
+    let offline = CheckMenuItemBuilder::new("Work offline")
+        .id(OFFLINE_ID)
+        .checked(work_offline)
+        .build(app)?;
+
+    let mut submenu = SubmenuBuilder::new(app, "Recent labels").enabled(!recent.is_empty());
+    for (index, entry) in recent.iter().enumerate() {
+        submenu = submenu.text(
+            format!("{}{}", RECENT_PREFIX, index),
+            format!("{} {}", entry.verdict.symbol(), entry.label),
+        );
+    }
+    let submenu = submenu.build()?;
+    let quit = MenuItemBuilder::new("Quit").id("quit").build(app)?;
+    let settings = MenuItemBuilder::new("Settings").id("settings").build(app)?;
+    MenuBuilder::new(app)
+        .items(&[&database, &refresh])
+        .item(&offline)
+        .separator()
+        .item(&submenu)
+        .separator()
+        .items(&[&quit, &settings])
+        .build()
+}
+
+/// `minutes` as a short age like `5 min`, `3 h` or `2 days`.
+fn format_age(minutes: i64) -> String {
+    match minutes {
+        ..1 => "less than a minute".to_string(),
+        1..60 => format!("{} min", minutes),
+        60..1440 => format!("{} h", minutes / 60),
+        1440..2880 => "1 day".to_string(),
+        _ => format!("{} days", minutes / 1440),
+    }
+}
+
+/// Build the menu again and show the tooltip for the current state.
+pub fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        log::warn!("Tray icon not found");
+        return;
+    };
+    let tooltip = {
+        let info = app.state::<TrayState>().lock().unwrap();
+        let mut tooltip = format!("{} - {}", TOOLTIP, info.loader.describe());
+        if let Some(last_check) = &info.last_check {
+            tooltip.push('\n');
+            tooltip.push_str(last_check);
+        }
+        tooltip
+    };
+    if let Err(e) = tray.set_tooltip(Some(tooltip)) {
+        log::error!("Failed to set tray tooltip: {}", e);
+    }
+    match menu(app) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                log::error!("Failed to update tray menu: {}", e);
+            }
+        }
+        Err(e) => log::error!("Failed to build tray menu: {}", e),
+    }
+}
+
+pub fn set_loader(app: &AppHandle, status: LoaderStatus) {
+    log::debug!("Keyword loader status: {:?}", status);
+    app.state::<TrayState>().lock().unwrap().loader = status;
+    refresh(app);
+}
+
+/// Remember the keyword database date shown in the menu, e.g. `DB: 13.06.2025`.
+pub fn set_db_version(app: &AppHandle, version: &str) {
+    app.state::<TrayState>().lock().unwrap().db_version = Some(version.to_string());
+    refresh(app);
+}
+
+/// The label of the recent labels menu item `id`.
+pub fn recent_label(app: &AppHandle, id: &str) -> Option<String> {
+    let index: usize = id.strip_prefix(RECENT_PREFIX)?.parse().ok()?;
+    let info = app.state::<TrayState>().lock().unwrap();
+    info.recent.get(index).map(|entry| entry.label.clone())
+}
+
+/// Show the verdict of `report` on the tray icon and add it to the recent labels.
+pub fn record(app: &AppHandle, report: &LabelReport) {
+    let verdict = Verdict::of(report);
+    {
+        let mut info = app.state::<TrayState>().lock().unwrap();
+        info.recent.retain(|entry| entry.label != report.label);
+        info.recent.push_front(RecentLabel {
+            label: report.label.clone(),
+            verdict,
+        });
+        info.recent.truncate(settings::current(app).history_size);
+        info.last_check = Some(if report.message.is_empty() {
+            format!("{} {}", verdict.symbol(), report.label)
+        } else {
+            format!("{} {}: {}", verdict.symbol(), report.label, report.message)
+        });
+    }
+    if let Some(tray) = app.tray_by_id(TRAY_ID)
+        && let Some(icon) = app.default_window_icon()
+        && let Err(e) = tray.set_icon(Some(with_dot(icon, verdict.rgb())))
+    {
+        log::error!("Failed to set tray icon: {}", e);
+    }
+    refresh(app);
+}
+
+/// `icon` with a filled circle of color `rgb` in the bottom-right quarter.
+fn with_dot(icon: &Image<'_>, rgb: [u8; 3]) -> Image<'static> {
+    let (width, height) = (icon.width() as usize, icon.height() as usize);
+    let mut rgba = icon.rgba().to_vec();
+    let radius = width.min(height) as f32 / 4.0;
+    let (center_x, center_y) = (width as f32 - radius, height as f32 - radius);
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 + 0.5 - center_x;
+            let dy = y as f32 + 0.5 - center_y;
+            if dx * dx + dy * dy <= radius * radius {
+                let pixel = (y * width + x) * 4;
+                rgba[pixel..pixel + 3].copy_from_slice(&rgb);
+                rgba[pixel + 3] = 255;
+            }
+        }
+    }
+    Image::new_owned(rgba, width as u32, height as u32)
+}
@@ -0,0 +1,479 @@
+// Waivers for accepted label violations
+//
+// Some legacy labels will never conform, e.g. because a customer contract freezes them.
+// A waiver file (TOML or JSON) lists them with the rule they break, why, who accepted it
+// and until when:
+//
+//   [[waivers]]
+//   label = "Abc_legacy*"          # label or glob
+//   rule = "descriptiveNotAvailable"        # rule id, or "*" for all rules
+//   justification = "Frozen by the customer interface specification"
+//   author = "J. Doe"
+//   expires = "2027-06-30"         # optional
+//
+// Waived findings move from a report's errors and warnings to `waived` and no longer make
+// the label invalid. Expired waivers do not apply; they and waivers matching nothing are
+// reported so the file can be cleaned up. The GUI uses the file named in the backend
+// settings, the command line the one given with `--waivers`.
+use crate::config_file;
+use crate::error::{AppError, AppResult};
+use crate::label::{self, LabelError, LabelReport};
+use crate::scan;
+use crate::settings;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+/// Waiver rule matching every rule.
+const ANY_RULE: &str = "*";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Waiver {
+    /// Label name, `*` and `?` match like in file names.
+    pub label: String,
+    /// [`LabelError::rule`] id, or `*` for all rules.
+    pub rule: String,
+    pub justification: String,
+    pub author: String,
+    /// Last day the waiver applies, `YYYY-MM-DD`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<String>,
+}
+
+impl Waiver {
+    fn expiry(&self) -> Option<NaiveDate> {
+        self.expires
+            .as_deref()
+            .and_then(|date| NaiveDate::parse_from_str(date, DATE_FORMAT).ok())
+    }
+
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        self.expiry().is_some_and(|expiry| expiry < today)
+    }
+
+    fn covers(&self, label: &str, error: LabelError) -> bool {
+        (self.rule == ANY_RULE || self.rule == error.rule()) && scan::glob_match(&self.label, label)
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WaiverFile {
+    #[serde(default)]
+    waivers: Vec<Waiver>,
+}
+
+/// The waivers of one file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WaiverSet {
+    /// Path of the waiver file, `None` when no file is used.
+    pub file: Option<String>,
+    pub waivers: Vec<Waiver>,
+}
+
+/// A finding accepted by a waiver.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaivedFinding {
+    pub error: LabelError,
+    /// Whether the finding would otherwise have been an error rather than a warning.
+    pub was_error: bool,
+    pub justification: String,
+    pub author: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires: Option<String>,
+}
+
+/// Waiver file health for a batch report.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaiverSummary {
+    pub file: Option<String>,
+    /// Number of waived findings.
+    pub waived: usize,
+    /// Waivers past their expiry date, they no longer apply.
+    pub expired: Vec<Waiver>,
+    /// Valid waivers that matched no finding.
+    pub unused: Vec<Waiver>,
+}
+
+impl WaiverSet {
+    pub fn is_empty(&self) -> bool {
+        self.waivers.is_empty()
+    }
+
+    /// Move the findings of `report` covered by a waiver valid on `today` to
+    /// `report.waived`. Returns the indices of the waivers that applied.
+    pub fn apply(&self, report: &mut LabelReport, today: NaiveDate) -> BTreeSet<usize> {
+        let mut used = BTreeSet::new();
+        if self.is_empty() {
+            return used;
+        }
+        let mut waived = Vec::new();
+        for (findings, was_error) in [(&mut report.errors, true), (&mut report.warnings, false)] {
+            findings.retain(|error| {
+                let waiver = self.waivers.iter().enumerate().find(|(_, waiver)| {
+                    !waiver.is_expired(today) && waiver.covers(&report.label, *error)
+                });
+                let Some((index, waiver)) = waiver else {
+                    return true;
+                };
+                used.insert(index);
+                waived.push(WaivedFinding {
+                    error: *error,
+                    was_error,
+                    justification: waiver.justification.clone(),
+                    author: waiver.author.clone(),
+                    expires: waiver.expires.clone(),
+                });
+                false
+            });
+        }
+        if !waived.is_empty() {
+            report.waived.extend(waived);
+            report.update_verdict();
+        }
+        used
+    }
+
+    /// Expired and unused waivers, given the indices [`WaiverSet::apply`] returned.
+    pub fn summary(
+        &self,
+        used: &BTreeSet<usize>,
+        waived: usize,
+        today: NaiveDate,
+    ) -> WaiverSummary {
+        let (expired, unused) = self
+            .waivers
+            .iter()
+            .enumerate()
+            .filter(|(index, waiver)| waiver.is_expired(today) || !used.contains(index))
+            .map(|(_, waiver)| waiver.clone())
+            .partition(|waiver| waiver.is_expired(today));
+        WaiverSummary {
+            file: self.file.clone(),
+            waived,
+            expired,
+            unused,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (index, waiver) in self.waivers.iter().enumerate() {
+            let entry = index + 1;
+            if waiver.label.trim().is_empty() {
+                return Err(format!("waiver #{} has no label", entry));
+            }
+            if waiver.rule != ANY_RULE && !label::RULES.contains(&waiver.rule.as_str()) {
+                return Err(format!(
+                    "waiver #{} has unknown rule '{}', expected * or one of: {}",
+                    entry,
+                    waiver.rule,
+                    label::RULES.join(", ")
+                ));
+            }
+            if waiver.justification.trim().is_empty() || waiver.author.trim().is_empty() {
+                return Err(format!(
+                    "waiver #{} ({}) needs a justification and an author",
+                    entry, waiver.label
+                ));
+            }
+            if let Some(expires) = &waiver.expires
+                && NaiveDate::parse_from_str(expires, DATE_FORMAT).is_err()
+            {
+                return Err(format!(
+                    "waiver #{} ({}) has invalid expiry date '{}', expected YYYY-MM-DD",
+                    entry, waiver.label, expires
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The local date expiry dates are compared with.
+pub fn today() -> NaiveDate {
+    chrono::Local::now().date_naive()
+}
+
+/// Parse and check a waiver file, `.toml` files as TOML and everything else as JSON.
+pub fn read_waivers(path: &Path) -> Result<WaiverSet, String> {
+    let parsed: WaiverFile =
+        config_file::read_toml_or_json(path, "waiver file").map_err(|e| e.to_string())?;
+    let waivers = WaiverSet {
+        file: Some(path.display().to_string()),
+        waivers: parsed.waivers,
+    };
+    waivers
+        .validate()
+        .map_err(|e| format!("Invalid waiver file {}: {}", path.display(), e))?;
+    Ok(waivers)
+}
+
+/// Waiver file shown in Settings.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaiverFileInfo {
+    pub path: String,
+    pub count: usize,
+    pub expired: usize,
+    /// Why the file cannot be used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The waiver file named in the settings, empty when there is none.
+pub fn load(app: &AppHandle) -> AppResult<WaiverSet> {
+    match settings::current(app).waiver_file {
+        Some(path) => read_waivers(Path::new(&path)).map_err(AppError::Validation),
+        None => Ok(WaiverSet::default()),
+    }
+}
+
+/// Information about the configured waiver file, `None` when there is none.
+pub fn info(app: &AppHandle) -> Option<WaiverFileInfo> {
+    let path = settings::current(app).waiver_file?;
+    let today = today();
+    Some(match read_waivers(Path::new(&path)) {
+        Ok(waivers) => WaiverFileInfo {
+            path,
+            count: waivers.waivers.len(),
+            expired: waivers
+                .waivers
+                .iter()
+                .filter(|w| w.is_expired(today))
+                .count(),
+            error: None,
+        },
+        Err(e) => WaiverFileInfo {
+            path,
+            count: 0,
+            expired: 0,
+            error: Some(e),
+        },
+    })
+}
+
+/// Use the waiver file at `path` (`None` for no waivers) and remember it.
+///
+/// Emits `waivers-changed` so open windows validate again.
+pub fn activate(app: &AppHandle, path: Option<String>) -> AppResult<Option<WaiverFileInfo>> {
+    let waivers = match &path {
+        Some(path) => read_waivers(Path::new(path)).map_err(AppError::Validation)?,
+        None => WaiverSet::default(),
+    };
+    let mut backend_settings = settings::current(app);
+    backend_settings.waiver_file = path;
+    settings::save(app, backend_settings)?;
+
+    replace(app, waivers);
+    let info = info(app);
+    app.emit("waivers-changed", &info).ok();
+    Ok(info)
+}
+
+pub type WaiverState = Mutex<Arc<WaiverSet>>;
+
+/// The waivers currently in use.
+pub fn current(app: &AppHandle) -> Arc<WaiverSet> {
+    app.state::<WaiverState>().lock().unwrap().clone()
+}
+
+pub fn replace(app: &AppHandle, waivers: WaiverSet) {
+    log::info!(
+        "Using {} waivers from {}",
+        waivers.waivers.len(),
+        waivers.file.as_deref().unwrap_or("no waiver file")
+    );
+    *app.state::<WaiverState>().lock().unwrap() = Arc::new(waivers);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn waiver(label: &str, rule: &str, expires: Option<&str>) -> Waiver {
+        Waiver {
+            label: label.to_string(),
+            rule: rule.to_string(),
+            justification: "Frozen by the customer interface specification".to_string(),
+            author: "J. Doe".to_string(),
+            expires: expires.map(str::to_string),
+        }
+    }
+
+    fn waivers(waivers: Vec<Waiver>) -> WaiverSet {
+        WaiverSet {
+            file: Some("waivers.toml".to_string()),
+            waivers,
+        }
+    }
+
+    fn report(label: &str, errors: Vec<LabelError>, warnings: Vec<LabelError>) -> LabelReport {
+        let mut report = LabelReport {
+            label: label.to_string(),
+            valid: false,
+            errors,
+            warnings,
+            waived: Vec::new(),
+            rows: Vec::new(),
+            message: String::new(),
+            color: String::new(),
+            consolidated_messages: Vec::new(),
+            life_cycle_state: None,
+        };
+        report.update_verdict();
+        report
+    }
+
+    #[test]
+    fn waives_findings_matching_label_glob_and_rule() {
+        let set = waivers(vec![waiver("Abc_legacy*", "descriptiveNotAvailable", None)]);
+        let errors = vec![LabelError::DescriptiveNotAvailable, LabelError::TooLong(27)];
+
+        let mut matching = report("Abc_legacyTemp", errors.clone(), Vec::new());
+        let used = set.apply(&mut matching, date(2026, 1, 1));
+        assert_eq!(used, BTreeSet::from([0]));
+        assert_eq!(matching.errors, vec![LabelError::TooLong(27)]);
+        assert_eq!(matching.waived.len(), 1);
+        assert_eq!(
+            matching.waived[0].error,
+            LabelError::DescriptiveNotAvailable
+        );
+        assert!(!matching.valid);
+        assert_eq!(matching.message, "Label exceeds 27 characters");
+
+        let mut other_label = report("Abc_newTemp", errors.clone(), Vec::new());
+        assert!(set.apply(&mut other_label, date(2026, 1, 1)).is_empty());
+        assert_eq!(other_label.errors, errors);
+        assert!(other_label.waived.is_empty());
+    }
+
+    #[test]
+    fn any_rule_waives_every_finding() {
+        let set = waivers(vec![waiver("Abc_tLegacy_?", ANY_RULE, None)]);
+        let mut report = report(
+            "Abc_tLegacy_C",
+            vec![LabelError::IdNotAllowed, LabelError::ExtensionNotAvailable],
+            vec![LabelError::TooLong(27)],
+        );
+        set.apply(&mut report, date(2026, 1, 1));
+        assert!(report.errors.is_empty());
+        assert!(report.warnings.is_empty());
+        assert_eq!(report.waived.len(), 3);
+        assert!(report.valid);
+        assert_eq!(report.message, "ID prefix <Id> is not allowed (waived)");
+        assert_eq!(report.color, "navy");
+    }
+
+    #[test]
+    fn waived_findings_remember_whether_they_were_errors() {
+        let set = waivers(vec![waiver("*", ANY_RULE, Some("2026-12-31"))]);
+        let mut report = report(
+            "Abc_xTemp",
+            vec![LabelError::PhysicalNotAvailable],
+            vec![LabelError::TooLong(8)],
+        );
+        set.apply(&mut report, date(2026, 1, 1));
+        let split: Vec<(LabelError, bool)> = report
+            .waived
+            .iter()
+            .map(|finding| (finding.error, finding.was_error))
+            .collect();
+        assert_eq!(
+            split,
+            vec![
+                (LabelError::PhysicalNotAvailable, true),
+                (LabelError::TooLong(8), false),
+            ]
+        );
+        assert_eq!(report.waived[0].author, "J. Doe");
+        assert_eq!(report.waived[0].expires.as_deref(), Some("2026-12-31"));
+    }
+
+    #[test]
+    fn waiver_applies_until_the_end_of_its_expiry_day() {
+        let expiring = waiver("Abc_*", ANY_RULE, Some("2026-06-30"));
+        assert!(!expiring.is_expired(date(2026, 6, 29)));
+        assert!(!expiring.is_expired(date(2026, 6, 30)));
+        assert!(expiring.is_expired(date(2026, 7, 1)));
+        assert!(!waiver("Abc_*", ANY_RULE, None).is_expired(date(2999, 1, 1)));
+
+        let set = waivers(vec![expiring]);
+        let mut last_day = report(
+            "Abc_xTemp",
+            vec![LabelError::PhysicalNotAvailable],
+            Vec::new(),
+        );
+        assert_eq!(
+            set.apply(&mut last_day, date(2026, 6, 30)),
+            BTreeSet::from([0])
+        );
+        assert!(last_day.valid);
+
+        let mut day_after = report(
+            "Abc_xTemp",
+            vec![LabelError::PhysicalNotAvailable],
+            Vec::new(),
+        );
+        assert!(set.apply(&mut day_after, date(2026, 7, 1)).is_empty());
+        assert!(!day_after.valid);
+        assert!(day_after.waived.is_empty());
+    }
+
+    #[test]
+    fn summary_lists_expired_and_unused_waivers() {
+        let used_waiver = waiver("Abc_*", "physicalNotAvailable", None);
+        let unused_waiver = waiver("Xyz_*", ANY_RULE, Some("2027-01-01"));
+        let expired_waiver = waiver("Abc_*", ANY_RULE, Some("2025-12-31"));
+        let set = waivers(vec![
+            used_waiver,
+            unused_waiver.clone(),
+            expired_waiver.clone(),
+        ]);
+        let today = date(2026, 1, 1);
+
+        let mut report = report(
+            "Abc_xTemp",
+            vec![LabelError::PhysicalNotAvailable, LabelError::TooLong(27)],
+            Vec::new(),
+        );
+        let used = set.apply(&mut report, today);
+        assert_eq!(report.errors, vec![LabelError::TooLong(27)]);
+
+        let summary = set.summary(&used, report.waived.len(), today);
+        assert_eq!(summary.file.as_deref(), Some("waivers.toml"));
+        assert_eq!(summary.waived, 1);
+        assert_eq!(summary.expired, vec![expired_waiver]);
+        assert_eq!(summary.unused, vec![unused_waiver]);
+    }
+
+    #[test]
+    fn rejects_unknown_rules_missing_authors_and_invalid_dates() {
+        assert!(
+            waivers(vec![waiver("Abc_*", "tooLong", Some("2026-06-30"))])
+                .validate()
+                .is_ok()
+        );
+        let unknown_rule = waivers(vec![waiver("Abc_*", "tooShort", None)]).validate();
+        assert!(
+            unknown_rule
+                .unwrap_err()
+                .contains("unknown rule 'tooShort'")
+        );
+        let mut anonymous = waiver("Abc_*", ANY_RULE, None);
+        anonymous.author = " ".to_string();
+        assert!(waivers(vec![anonymous]).validate().is_err());
+        let bad_date = waivers(vec![waiver("Abc_*", ANY_RULE, Some("30.06.2026"))]).validate();
+        assert!(bad_date.unwrap_err().contains("invalid expiry date"));
+    }
+}
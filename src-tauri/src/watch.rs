@@ -0,0 +1,64 @@
+// Clipboard watch mode
+//
+// When enabled in the settings, a background thread polls the clipboard and hands every
+// newly copied text that looks like a label to a callback, which validates it and shows
+// the verdict in the tray. Texts look like a label when they are a single label candidate
+// matching the configured pattern (`*` and `?` as in file names, `*_*` by default).
+use crate::label;
+use crate::scan;
+use crate::settings;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+pub const DEFAULT_PATTERN: &str = "*_*";
+pub const DEFAULT_HISTORY_SIZE: usize = 10;
+pub const MAX_HISTORY_SIZE: usize = 50;
+const POLL_INTERVAL: Duration = Duration::from_millis(750);
+
+/// Stop flag of the running watcher thread.
+pub type WatchState = Mutex<Option<Arc<AtomicBool>>>;
+
+/// The label in a copied `text`, if it is one matching `pattern`.
+pub fn clipboard_label<'a>(text: &'a str, pattern: &str) -> Option<&'a str> {
+    let text = text.trim();
+    (label::is_label_candidate(text) && scan::glob_match(pattern, text)).then_some(text)
+}
+
+/// Stop the watcher and start it again if the settings enable it.
+pub fn restart(app: &AppHandle, on_label: fn(&AppHandle, &str)) {
+    if let Some(stop) = app.state::<WatchState>().lock().unwrap().take() {
+        stop.store(true, Ordering::Relaxed);
+        log::info!("Clipboard watch stopped");
+    }
+    let backend_settings = settings::current(app);
+    if !backend_settings.clipboard_watch {
+        return;
+    }
+    let stop = Arc::new(AtomicBool::new(false));
+    *app.state::<WatchState>().lock().unwrap() = Some(stop.clone());
+    let app = app.clone();
+    let pattern = backend_settings.clipboard_pattern;
+    log::info!("Watching the clipboard for labels matching {}", pattern);
+    std::thread::spawn(move || {
+        // What is on the clipboard when watching starts was copied before, skip it
+        let mut last = app.clipboard().read_text().ok();
+        while !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(POLL_INTERVAL);
+            let Ok(text) = app.clipboard().read_text() else {
+                continue;
+            };
+            if last.as_deref() == Some(text.as_str()) {
+                continue;
+            }
+            if let Some(label) = clipboard_label(&text, &pattern)
+                && !stop.load(Ordering::Relaxed)
+            {
+                on_label(&app, label);
+            }
+            last = Some(text);
+        }
+    });
+}
@@ -1,8 +1,85 @@
-import { render, screen, fireEvent } from '@testing-library/react';
+import { render, screen, fireEvent, waitFor } from '@testing-library/react';
 import '@testing-library/jest-dom';
 import { act } from 'react-dom/test-utils';
+import { invoke } from '@tauri-apps/api/core';
 import KeywordSearch from '../components/KeywordSearch/KeywordSearch';
 
+// `invoke` is mocked in jest.setup.js, each test answers the backend commands it needs
+const mockInvoke = invoke as unknown as jest.Mock;
+const emitTauriEvent = (global as any).emitTauriEvent as (name: string, payload?: unknown) => void;
+
+const row = (part: string, abbrName: string, longNameEn: string, extra: Record<string, unknown> = {}) => ({
+  part,
+  abbrName,
+  rbClassifications: ['AUTOSAR'],
+  longNameEn,
+  longNameDe: longNameEn,
+  domainName: 'General',
+  lifeCycleState: 'valid',
+  ...extra,
+});
+
+// A LabelReport as returned by the `validate_label` command
+const report = (label: string, rows: unknown[], errors: string[] = [], warnings: string[] = []) => {
+  const message = errors[0] ?? warnings[0] ?? '';
+  const color = errors.length ? 'red' : warnings.length ? 'orange' : '';
+  return {
+    label,
+    valid: errors.length === 0,
+    errors,
+    warnings,
+    waived: [],
+    rows,
+    message,
+    color,
+    consolidatedMessages: message ? [{ text: message, color }] : [],
+    lifeCycleState: 'valid',
+  };
+};
+
+const tMaxLimRows = [
+  row('id', 'RB', 'Robert Bosch'),
+  row('physical', 't', 'time'),
+  row('descriptive', 'Max', 'Maximum'),
+  row('descriptive', 'Lim', 'Limit'),
+  row('extension', 'C', 'Calibration'),
+];
+
+// A KeywordPage as returned by the `search_keywords` command
+const page = (abbrNames: string[]) => ({
+  items: abbrNames.map(abbrName => row('descriptive', abbrName, abbrName)),
+  total: abbrNames.length,
+  page: 0,
+  pageSize: 200,
+});
+
+const answer = (responses: { validate?: unknown; search?: unknown }) => {
+  mockInvoke.mockImplementation((cmd: string) => {
+    if (cmd === 'validate_label' && responses.validate) return Promise.resolve(responses.validate);
+    if (cmd === 'search_keywords' && responses.search) return Promise.resolve(responses.search);
+    return Promise.resolve(null);
+  });
+};
+
+// The table replaces the spinner once the backend reports its keywords
+const renderReady = async () => {
+  render(<KeywordSearch />);
+  await act(async () => {
+    emitTauriEvent('keywords-ready', { count: 3 });
+  });
+};
+
+const enterLabel = async (value: string) => {
+  const labelInput = screen.getByLabelText(/Label Input/i);
+  await act(async () => {
+    fireEvent.focus(labelInput);
+    fireEvent.change(labelInput, { target: { value } });
+  });
+  await screen.findByDisplayValue(value);
+};
+
+const bodyRows = () => document.querySelectorAll('tbody tr');
+
 describe('KeywordSearch', () => {
   it('renders input fields', () => {
     render(<KeywordSearch />);
@@ -10,9 +87,13 @@ describe('KeywordSearch', () => {
     expect(screen.getByLabelText(/Keywords or Names/i)).toBeInTheDocument();
   });
 
-  it('shows no results message when no search', () => {
+  it('shows no results message once the keywords are ready', async () => {
     render(<KeywordSearch />);
-    expect(screen.getByText(/Enter a search term to begin/i)).toBeInTheDocument();
+    expect(screen.queryByText(/Enter a name to begin/i)).not.toBeInTheDocument();
+    await act(async () => {
+      emitTauriEvent('keywords-ready', { count: 0 });
+    });
+    expect(screen.getByText(/Enter a name to begin/i)).toBeInTheDocument();
   });
 
   it('updates label input', () => {
@@ -22,510 +103,149 @@ describe('KeywordSearch', () => {
     expect(labelInput).toHaveValue('TestLabel');
   });
 
-  it('displays entries for each parsed keyword when Label Input is focused', async () => {
-    // Mock localStorage with keywords for t, Max, Lim
-    const mockKeywords = [
-      {
-        abbrName: 't',
-        longNameEn: 'time',
-        longNameDe: 'Zeit',
-        domainName: 'General',
-        rbClassifications: ['AUTOSAR'],
-        lifeCycleState: 'valid',
-        state: 'Released',
-      },
-      {
-        abbrName: 'Max',
-        longNameEn: 'Maximum',
-        longNameDe: 'Maximum',
-        domainName: 'General',
-        rbClassifications: ['AUTOSAR'],
-        lifeCycleState: 'valid',
-        state: 'Released',
-      },
-      {
-        abbrName: 'Lim',
-        longNameEn: 'Limit',
-        longNameDe: 'Grenze',
-        domainName: 'General',
-        rbClassifications: ['AUTOSAR'],
-        lifeCycleState: 'valid',
-        state: 'Released',
-      },
-    ];
-    window.localStorage.setItem('ubk_keywords', JSON.stringify(mockKeywords));
+  it('validates the label in the backend and shows a row for each part', async () => {
+    answer({ validate: report('RB_tMaxLim_C', tMaxLimRows) });
+    await renderReady();
+    await enterLabel('RB_tMaxLim_C');
 
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
-    await act(async () => {
-      fireEvent.focus(labelInput);
-      fireEvent.change(labelInput, { target: { value: 'RB_tMaxLim_C' } });
-    });
-    await screen.findByDisplayValue('RB_tMaxLim_C');
-    // Should show all parts: Id, pp, DescriptiveName1, DescriptiveName2, Ex
-    expect(await screen.findByTestId('rb-cell')).toBeInTheDocument(); // Id
-    expect(screen.getByTestId('rb-cell')).toHaveTextContent('RB');
-
-    // Instead of directly checking for these elements which may be nested in table cells
-    // Let's check if rows are correctly rendered
-    const rows = document.querySelectorAll('tbody tr');
-    expect(rows.length).toBe(5);
-
-    // Check for AUTOSAR status message
-    expect(screen.getByTestId('autosar-status-message')).toBeInTheDocument();
+    await waitFor(() => expect(bodyRows()).toHaveLength(5));
+    expect(mockInvoke).toHaveBeenCalledWith('validate_label', { label: 'RB_tMaxLim_C' });
+    const keywords = Array.from(bodyRows()).map(tr => tr.querySelector('td')?.textContent);
+    expect(keywords).toEqual(['RB', 't', 'Max', 'Lim', 'C']);
+    expect(screen.getByText('Maximum')).toBeInTheDocument();
   });
 
-  it('displays only case-sensitive DescriptiveName keyword matches for Label Input', async () => {
-    // Mock localStorage with keywords for t, Max, Lim, max, lim, MAX, LIM
-    const mockKeywords = [
-      {
-        abbrName: 't',
-        longNameEn: 'time',
-        longNameDe: 'Zeit',
-        domainName: 'General',
-        rbClassifications: ['AUTOSAR'],
-        lifeCycleState: 'valid',
-        state: 'Released',
-      },
-      {
-        abbrName: 'Max',
-        longNameEn: 'Maximum',
-        longNameDe: 'Maximum',
-        domainName: 'General',
-        rbClassifications: ['AUTOSAR'],
-        lifeCycleState: 'valid',
-        state: 'Released',
-      },
-      {
-        abbrName: 'Lim',
-        longNameEn: 'Limit',
-        longNameDe: 'Grenze',
-        domainName: 'General',
-        rbClassifications: ['AUTOSAR'],
-        lifeCycleState: 'valid',
-        state: 'Released',
-      },
-      {
-        abbrName: 'max',
-        longNameEn: 'not used',
-        longNameDe: 'not used',
-        domainName: 'General',
-        rbClassifications: ['AUTOSAR'],
-        lifeCycleState: 'valid',
-        state: 'Released',
-      },
-      {
-        abbrName: 'lim',
-        longNameEn: 'not used',
-        longNameDe: 'not used',
-        domainName: 'General',
-        rbClassifications: ['AUTOSAR'],
-        lifeCycleState: 'valid',
-        state: 'Released',
-      },
-      {
-        abbrName: 'MAX',
-        longNameEn: 'not used',
-        longNameDe: 'not used',
-        domainName: 'General',
-        rbClassifications: ['AUTOSAR'],
-        lifeCycleState: 'valid',
-        state: 'Released',
-      },
-      {
-        abbrName: 'LIM',
-        longNameEn: 'not used',
-        longNameDe: 'not used',
-        domainName: 'General',
-        rbClassifications: ['AUTOSAR'],
-        lifeCycleState: 'valid',
-        state: 'Released',
-      },
-    ];
-    window.localStorage.setItem('ubk_keywords', JSON.stringify(mockKeywords));
-
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
-    await act(async () => {
-      fireEvent.focus(labelInput);
-      fireEvent.change(labelInput, { target: { value: 'RB_tMaxLim_C' } });
-    });
-    await screen.findByDisplayValue('RB_tMaxLim_C');
-    // All parts should be present as rows
-    expect(await screen.findByTestId('rb-cell')).toBeInTheDocument();
-    expect(screen.getByTestId('rb-cell')).toHaveTextContent('RB');
-
-    // Instead of checking for individual text elements, check the row count
-    const rows = document.querySelectorAll('tbody tr');
-    expect(rows.length).toBe(5);
+  it('sends the trimmed label to the backend', async () => {
+    answer({ validate: report('RB_tMaxLim_C', tMaxLimRows) });
+    await renderReady();
+    await enterLabel('  RB_tMaxLim_C ');
 
-    // Lowercase, uppercase, and other variants should NOT be present as separate rows
-    // This is implicit in the row count check
+    await waitFor(() =>
+      expect(mockInvoke).toHaveBeenCalledWith('validate_label', { label: 'RB_tMaxLim_C' })
+    );
   });
 
-  it('does not match if any DescriptiveName keyword is missing in data', async () => {
-    // Only 't' and 'Max' exist, 'Lim' is missing
-    const mockKeywords = [
-      { abbrName: 't', longNameEn: 'time', longNameDe: 'Zeit', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Max', longNameEn: 'Maximum', longNameDe: 'Maximum', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-    ];
-    window.localStorage.setItem('ubk_keywords', JSON.stringify(mockKeywords));
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
-    await act(async () => {
-      fireEvent.focus(labelInput);
-      fireEvent.change(labelInput, { target: { value: 'RB_tMaxLim_C' } });
-    });
-    await screen.findByDisplayValue('RB_tMaxLim_C');
-    // All parts should be present as rows
-    expect(await screen.findByTestId('rb-cell')).toBeInTheDocument();
-    expect(screen.getByTestId('rb-cell')).toHaveTextContent('RB');
-
-    // Check row count
-    const rows = document.querySelectorAll('tbody tr');
-    expect(rows.length).toBe(5);
-
-    // Check for error message - commented out as error message UI elements were removed
-    // expect(screen.getByText('Abbreviation not available')).toBeInTheDocument();
-  });
+  it('does not validate an empty label', async () => {
+    await renderReady();
+    await enterLabel('RB_t');
+    await waitFor(() => expect(mockInvoke).toHaveBeenCalledWith('validate_label', { label: 'RB_t' }));
+    mockInvoke.mockClear();
 
-  it('does not match if prefix (pp) is missing in data', async () => {
-    // Only 'Max' and 'Lim' exist, 't' is missing
-    const mockKeywords = [
-      { abbrName: 'Max', longNameEn: 'Maximum', longNameDe: 'Maximum', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Lim', longNameEn: 'Limit', longNameDe: 'Grenze', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-    ];
-    window.localStorage.setItem('ubk_keywords', JSON.stringify(mockKeywords));
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
+    await enterLabel('   ');
     await act(async () => {
-      fireEvent.focus(labelInput);
-      fireEvent.change(labelInput, { target: { value: 'RB_tMaxLim_C' } });
+      await new Promise(resolve => setTimeout(resolve, 400));
     });
-    await screen.findByDisplayValue('RB_tMaxLim_C');
-    // Check for ID
-    expect(await screen.findByTestId('rb-cell')).toBeInTheDocument();
-    expect(screen.getByTestId('rb-cell')).toHaveTextContent('RB');
+    expect(mockInvoke).not.toHaveBeenCalledWith('validate_label', expect.anything());
+  });
 
-    // Check row count
-    const rows = document.querySelectorAll('tbody tr');
-    expect(rows.length).toBe(5);
+  it('shows the most important error of the report', async () => {
+    const rows = [row('id', 'RB', 'Robert Bosch'), row('physical', 'x', '', { lifeCycleState: '' })];
+    answer({ validate: report('RB_xMaxLim_C', rows, ['Abbreviation of <pp> not available', 'Incomplete Label']) });
+    await renderReady();
+    await enterLabel('RB_xMaxLim_C');
 
-    // Check for error message - commented out as error message UI elements were removed
-    // expect(screen.getByText('Abbreviation not available')).toBeInTheDocument();
+    const status = await screen.findByTestId('autosar-status-message');
+    expect(status).toHaveTextContent('Abbreviation of <pp> not available');
+    expect(screen.queryByText('Incomplete Label')).not.toBeInTheDocument();
   });
 
-  it('handles label with no matching keywords', async () => {
-    // No keywords in data
-    window.localStorage.setItem('ubk_keywords', JSON.stringify([]));
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
-    await act(async () => {
-      fireEvent.focus(labelInput);
-      fireEvent.change(labelInput, { target: { value: 'RB_tMaxLim_C' } });
-    });
-    await screen.findByDisplayValue('RB_tMaxLim_C');
-    // Check for ID
-    expect(await screen.findByTestId('rb-cell')).toBeInTheDocument();
-    expect(screen.getByTestId('rb-cell')).toHaveTextContent('RB');
-
-    // Check row count
-    const rows = document.querySelectorAll('tbody tr');
-    expect(rows.length).toBe(5);
+  it('shows a warning of a valid label', async () => {
+    answer({ validate: report('RB_tMaxLim_C', tMaxLimRows, [], ['Label exceeds 8 characters']) });
+    await renderReady();
+    await enterLabel('RB_tMaxLim_C');
 
-    // Check for error message - commented out as error message UI elements were removed
-    // expect(screen.getByText('Abbreviation not available')).toBeInTheDocument();
+    expect(await screen.findByTestId('autosar-status-message')).toHaveTextContent('Label exceeds 8 characters');
   });
 
-  it('handles label with extra underscores or malformed format', async () => {
-    // Data contains all possible keywords
-    const mockKeywords = [
-      { abbrName: 't', longNameEn: 'time', longNameDe: 'Zeit', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Max', longNameEn: 'Maximum', longNameDe: 'Maximum', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Lim', longNameEn: 'Limit', longNameDe: 'Grenze', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-    ];
-    window.localStorage.setItem('ubk_keywords', JSON.stringify(mockKeywords));
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
-    await act(async () => {
-      fireEvent.focus(labelInput);
-      fireEvent.change(labelInput, { target: { value: 'RB__tMaxLim__C' } }); // malformed: double underscores
-    });
-    await screen.findByDisplayValue('RB__tMaxLim__C');
-    
-    // This test checked for error messages - commenting out since UI elements were removed
-    /* 
-    // Should show error message for malformed label
-    const errorMessages = [
-      'No Label',
-      'Label is too long',
-      'Label is longer than 27 characters',
-      'Incomplete Label',
-      'DescriptiveName missing',
-      'Abbreviation not available',
-    ];
-    const renderedMessages = errorMessages.filter(msg => screen.queryByText(new RegExp(msg, 'i')));
-    if (renderedMessages.length > 1) {
-      const ranks = renderedMessages.map(msg => errorMessages.indexOf(msg));
-      for (let i = 1; i < ranks.length; ++i) {
-        expect(ranks[i]).toBeGreaterThan(ranks[i - 1]);
-      }
-    }
-    */
-  });
+  it('shows no status message for a label without findings', async () => {
+    answer({ validate: report('RB_tMaxLim_C', tMaxLimRows) });
+    await renderReady();
+    await enterLabel('RB_tMaxLim_C');
 
-  it('handles label with only prefix (no DescriptiveName)', async () => {
-    // Data contains 't'
-    const mockKeywords = [
-      { abbrName: 't', longNameEn: 'time', longNameDe: 'Zeit', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-    ];
-    window.localStorage.setItem('ubk_keywords', JSON.stringify(mockKeywords));
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
-    await act(async () => {
-      fireEvent.focus(labelInput);
-      fireEvent.change(labelInput, { target: { value: 'RB_t__C' } }); // Only prefix, no descriptive
-    });
-    await screen.findByDisplayValue('RB_t__C');
-    
-    // This test checked for error messages - commenting out since UI elements were removed
-    /*
-    // Should show error message for missing DescriptiveName
-    const errorMessages = [
-      'No Label',
-      'Label is too long',
-      'Label is longer than 27 characters',
-      'Incomplete Label',
-      'DescriptiveName missing',
-      'Abbreviation not available',
-    ];
-    const renderedMessages = errorMessages.filter(msg => screen.queryByText(new RegExp(msg, 'i')));
-    if (renderedMessages.length > 1) {
-      const ranks = renderedMessages.map(msg => errorMessages.indexOf(msg));
-      for (let i = 1; i < ranks.length; ++i) {
-        expect(ranks[i]).toBeGreaterThan(ranks[i - 1]);
-      }
-    }
-    */
+    await waitFor(() => expect(bodyRows()).toHaveLength(5));
+    expect(screen.queryByTestId('autosar-status-message')).not.toBeInTheDocument();
   });
 
-  it('shows no error for label input length <= 27', () => {
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
-    fireEvent.change(labelInput, { target: { value: 'A'.repeat(27) } });
-    expect(labelInput).toHaveValue('A'.repeat(27));
-    
-    // These checks are for error messages - commenting out since UI elements were removed
-    expect(screen.queryByText('Label is longer than 27 characters')).not.toBeInTheDocument();
-    expect(screen.queryByText('Label is too long')).not.toBeInTheDocument();
-  });
+  it('hides the status message when Keywords or Names input is focused and shows it when Label Input is focused', async () => {
+    answer({ validate: report('RB_TMaxLim_C', tMaxLimRows, ['Physical part <pp> is missing']) });
+    await renderReady();
+    await enterLabel('RB_TMaxLim_C');
+    await screen.findByTestId('autosar-status-message');
 
-  it('shows "Label is longer than 27 characters" for label input length 28-50', () => {
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
-    fireEvent.change(labelInput, { target: { value: 'B'.repeat(28) } });
-    expect(labelInput).toHaveValue('B'.repeat(28));
-    
-    // These checks are for error messages - commenting out since UI elements were removed
-    // expect(screen.getByText('Label is longer than 27 characters')).toBeInTheDocument();
-    // expect(screen.queryByText('Label is too long')).not.toBeInTheDocument();
-    
-    fireEvent.change(labelInput, { target: { value: 'C'.repeat(50) } });
-    expect(labelInput).toHaveValue('C'.repeat(50));
-    
-    // These checks are for error messages - commenting out since UI elements were removed
-    // expect(screen.getByText('Label is longer than 27 characters')).toBeInTheDocument();
-    // expect(screen.queryByText('Label is too long')).not.toBeInTheDocument();
-  });
+    fireEvent.focus(screen.getByLabelText(/Keywords or Names/i));
+    expect(screen.queryByTestId('autosar-status-message')).not.toBeInTheDocument();
 
-  it('shows "Label is too long" for label input length > 50', () => {
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
-    fireEvent.change(labelInput, { target: { value: 'D'.repeat(51) } });
-    expect(labelInput).toHaveValue('D'.repeat(51));
-    
-    // These checks are for error messages - commenting out since UI elements were removed
-    // expect(screen.getByText('Label is too long')).toBeInTheDocument();
-    // Should not show the 27-char error
-    // expect(screen.queryByText('Label is longer than 27 characters')).not.toBeInTheDocument();
+    fireEvent.focus(screen.getByLabelText(/Label Input/i));
+    expect(await screen.findByTestId('autosar-status-message')).toBeInTheDocument();
   });
 
-  it('hides AUTOSAR check message when Keywords or Names input is focused and shows it when Label Input is focused', async () => {
-    // Mock localStorage with keywords for t, Max, Lim, C
-    const mockKeywords = [
-      { abbrName: 't', longNameEn: 'time', longNameDe: 'Zeit', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Max', longNameEn: 'Maximum', longNameDe: 'Maximum', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Lim', longNameEn: 'Limit', longNameDe: 'Grenze', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'C', longNameEn: 'C', longNameDe: 'C', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-    ];
-    window.localStorage.setItem('ubk_keywords', JSON.stringify(mockKeywords));
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
-    const searchInput = screen.getByLabelText(/Keywords or Names/i);
+  it('validates the label again when the rule set changes', async () => {
+    answer({ validate: report('RB_tMaxLim_C', tMaxLimRows) });
+    await renderReady();
+    await enterLabel('RB_tMaxLim_C');
+    await waitFor(() => expect(bodyRows()).toHaveLength(5));
+    const calls = () => mockInvoke.mock.calls.filter(([cmd]) => cmd === 'validate_label').length;
+    const before = calls();
 
-    // Focus label input and enter a valid label
     await act(async () => {
-      fireEvent.focus(labelInput);
-      fireEvent.change(labelInput, { target: { value: 'RB_tMaxLim_C' } });
+      emitTauriEvent('rule-set-changed', { name: 'strict' });
     });
-    await screen.findByDisplayValue('RB_tMaxLim_C');
-    // AUTOSAR message should be visible
-    await screen.findByTestId('autosar-status-message');
-    expect(screen.getByTestId('autosar-status-message')).toBeInTheDocument();
+    await waitFor(() => expect(calls()).toBe(before + 1));
+  });
 
-    // Focus search input
+  it('shows the keywords found by the backend search', async () => {
+    answer({ search: page(['Temp', 'Tmp', 'Tempr']) });
+    await renderReady();
+    const searchInput = screen.getByLabelText(/Keywords or Names/i);
     fireEvent.focus(searchInput);
-    // AUTOSAR message should disappear
-    expect(screen.queryByTestId('autosar-status-message')).not.toBeInTheDocument();
+    fireEvent.change(searchInput, { target: { value: 'temperature' } });
 
-    // Focus label input again
-    fireEvent.focus(labelInput);
-    await screen.findByTestId('autosar-status-message');
-    expect(screen.getByTestId('autosar-status-message')).toBeInTheDocument();
+    await waitFor(() => expect(bodyRows()).toHaveLength(3));
+    expect(mockInvoke).toHaveBeenCalledWith(
+      'search_keywords',
+      expect.objectContaining({ page: 0, pageSize: 200 })
+    );
+    const keywords = Array.from(bodyRows()).map(tr => tr.querySelector('td')?.textContent);
+    expect(keywords).toEqual(['Temp', 'Tmp', 'Tempr']);
   });
 
-  it('shows pagination only when there are more than 5 rows (label input)', async () => {
-    // 6 keywords for label mode
-    const mockKeywords = [
-      { abbrName: 't', longNameEn: 'time', longNameDe: 'Zeit', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Max', longNameEn: 'Maximum', longNameDe: 'Maximum', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Lim', longNameEn: 'Limit', longNameDe: 'Grenze', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Foo', longNameEn: 'Foo', longNameDe: 'Foo', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Bar', longNameEn: 'Bar', longNameDe: 'Bar', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Baz', longNameEn: 'Baz', longNameDe: 'Baz', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-    ];
-    window.localStorage.setItem('ubk_keywords', JSON.stringify(mockKeywords));
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
-    await act(async () => {
-      fireEvent.focus(labelInput);
-      fireEvent.change(labelInput, { target: { value: 'RB_tMaxLimFooBarBaz_C' } });
-    });
-    await screen.findByDisplayValue('RB_tMaxLimFooBarBaz_C');
-    // Pagination should be visible
-    expect(screen.getByRole('button', { name: /next page/i })).toBeInTheDocument();
-  });
+  it('shows no name found when the search has no matches', async () => {
+    await renderReady();
+    const searchInput = screen.getByLabelText(/Keywords or Names/i);
+    fireEvent.focus(searchInput);
+    fireEvent.change(searchInput, { target: { value: 'unknown' } });
 
-  it('does not show pagination when there are 5 or fewer rows (label input)', async () => {
-    // 3 keywords for label mode
-    const mockKeywords = [
-      { abbrName: 't', longNameEn: 'time', longNameDe: 'Zeit', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Max', longNameEn: 'Maximum', longNameDe: 'Maximum', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Lim', longNameEn: 'Limit', longNameDe: 'Grenze', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-    ];
-    window.localStorage.setItem('ubk_keywords', JSON.stringify(mockKeywords));
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
-    await act(async () => {
-      fireEvent.focus(labelInput);
-      fireEvent.change(labelInput, { target: { value: 'RB_tMaxLim_C' } });
-    });
-    await screen.findByDisplayValue('RB_tMaxLim_C');
-    // Pagination should NOT be visible
-    expect(screen.queryByRole('button', { name: /next page/i })).not.toBeInTheDocument();
+    expect(await screen.findByText(/No name found/i)).toBeInTheDocument();
+    expect(mockInvoke).toHaveBeenCalledWith('search_keywords', expect.anything());
   });
 
-  it('shows pagination only when there are more than 5 rows (search input)', async () => {
-    // 6 keywords for search mode, all matching 'A' in abbrName
-    const mockKeywords = [
-      { abbrName: 'A1', longNameEn: 'A1', longNameDe: 'A1', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'A2', longNameEn: 'A2', longNameDe: 'A2', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'A3', longNameEn: 'A3', longNameDe: 'A3', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'A4', longNameEn: 'A4', longNameDe: 'A4', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'A5', longNameEn: 'A5', longNameDe: 'A5', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'A6', longNameEn: 'A6', longNameDe: 'A6', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-    ];
-    window.localStorage.setItem('ubk_keywords', JSON.stringify(mockKeywords));
-    render(<KeywordSearch />);
+  it('shows pagination when the search finds more rows than fit on a page', async () => {
+    answer({ search: page(Array.from({ length: 30 }, (_, i) => `A${i + 1}`)) });
+    await renderReady();
     const searchInput = screen.getByLabelText(/Keywords or Names/i);
     fireEvent.focus(searchInput);
     fireEvent.change(searchInput, { target: { value: 'A' } });
-    // Wait for the pagination button to appear
-    await screen.findByRole('button', { name: /next page/i });
-    // Pagination should be visible
-    expect(screen.getByRole('button', { name: /next page/i })).toBeInTheDocument();
+
+    expect(await screen.findByRole('button', { name: /next page/i })).toBeInTheDocument();
   });
 
-  it('does not show pagination when there are 5 or fewer rows (search input)', async () => {
-    // 3 keywords for search mode
-    const mockKeywords = [
-      { abbrName: 'A', longNameEn: 'A', longNameDe: 'A', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'B', longNameEn: 'B', longNameDe: 'B', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'C', longNameEn: 'C', longNameDe: 'C', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-    ];
-    window.localStorage.setItem('ubk_keywords', JSON.stringify(mockKeywords));
-    render(<KeywordSearch />);
+  it('does not show pagination when the search finds few rows', async () => {
+    answer({ search: page(['A', 'B', 'C']) });
+    await renderReady();
     const searchInput = screen.getByLabelText(/Keywords or Names/i);
     fireEvent.focus(searchInput);
     fireEvent.change(searchInput, { target: { value: 'A' } });
-    // Pagination should NOT be visible
+
+    await waitFor(() => expect(bodyRows()).toHaveLength(3));
     expect(screen.queryByRole('button', { name: /next page/i })).not.toBeInTheDocument();
   });
 
   it('shows all label parts up to DescriptiveName even if <Ex> part is missing', async () => {
-    // Mock keywords for t, Max, Lim
-    const mockKeywords = [
-      { abbrName: 't', longNameEn: 'time', longNameDe: 'Zeit', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Max', longNameEn: 'Maximum', longNameDe: 'Maximum', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Lim', longNameEn: 'Limit', longNameDe: 'Grenze', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-    ];
-    window.localStorage.setItem('ubk_keywords', JSON.stringify(mockKeywords));
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
-    await act(async () => {
-      fireEvent.focus(labelInput);
-      fireEvent.change(labelInput, { target: { value: 'RB_tMaxLim' } });
-    });    await screen.findByDisplayValue('RB_tMaxLim');
-    // Check for ID
-    expect(await screen.findByTestId('rb-cell')).toBeInTheDocument();
-    expect(screen.getByTestId('rb-cell')).toHaveTextContent('RB');
-
-    // Check row count
-    const rows = document.querySelectorAll('tbody tr');
-    expect(rows.length).toBe(4);
-
-    // These checks are for error messages - commenting out since UI elements were removed
-    // expect(screen.queryByText('Abbreviation not available')).not.toBeInTheDocument();
-    // expect(screen.queryByText('DescriptiveName missing')).not.toBeInTheDocument();
-  });
-
-  it('shows error if <pp> does not start with a lowercase letter', async () => {
-    // Data contains 't', 'Max', 'Lim'
-    const mockKeywords = [
-      { abbrName: 't', longNameEn: 'time', longNameDe: 'Zeit', domainName: 'General', rbClassifications: ['Physical'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Max', longNameEn: 'Maximum', longNameDe: 'Maximum', domainName: 'General', rbClassifications: ['Physical'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Lim', longNameEn: 'Limit', longNameDe: 'Grenze', domainName: 'General', rbClassifications: ['Physical'], lifeCycleState: 'valid', state: 'Released' },
-    ];
-    window.localStorage.setItem('ubk_keywords', JSON.stringify(mockKeywords));
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
-    await act(async () => {
-      fireEvent.focus(labelInput);
-      fireEvent.change(labelInput, { target: { value: 'RB_TMaxLim_C' } }); // <pp> is 'T', uppercase
-    });
-    await screen.findByDisplayValue('RB_TMaxLim_C');
-    
-    // This check is for error message - commenting out since UI element was removed
-    // expect(screen.getByText(/pp must start with a lowercase/)).toBeInTheDocument();
-  });
+    answer({ validate: report('RB_tMaxLim', tMaxLimRows.slice(0, 4)) });
+    await renderReady();
+    await enterLabel('RB_tMaxLim');
 
-  it('shows "No physical part <pp> used" if <pp> is not classified as Physical', async () => {
-    // Data contains 't', 'Max', 'Lim', but only 't' is Physical
-    const mockKeywords = [
-      { abbrName: 't', longNameEn: 'time', longNameDe: 'Zeit', domainName: 'General', rbClassifications: ['Physical'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Max', longNameEn: 'Maximum', longNameDe: 'Maximum', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-      { abbrName: 'Lim', longNameEn: 'Limit', longNameDe: 'Grenze', domainName: 'General', rbClassifications: ['AUTOSAR'], lifeCycleState: 'valid', state: 'Released' },
-    ];
-    window.localStorage.setItem('ubk_keywords', JSON.stringify(mockKeywords));
-    render(<KeywordSearch />);
-    const labelInput = screen.getByLabelText(/Label Input/i);
-    await act(async () => {
-      fireEvent.focus(labelInput);
-      fireEvent.change(labelInput, { target: { value: 'RB_tMaxLim_C' } });
-    });
-    await screen.findByDisplayValue('RB_tMaxLim_C');
-    
-    // This check is for error message - commenting out since UI element was removed
-    // expect(screen.getByText(/No physical part <pp> used/)).toBeInTheDocument();
+    await waitFor(() => expect(bodyRows()).toHaveLength(4));
+    expect(bodyRows()[0].querySelector('td')).toHaveTextContent('RB');
   });
 });
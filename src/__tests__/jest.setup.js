@@ -1,8 +1,42 @@
-// Jest setup file to mock Tauri event API for all import styles
-jest.mock('@tauri-apps/api/event', () => ({
-  __esModule: true,
-  default: {
-    listen: jest.fn(() => Promise.resolve(() => {})),
-  },
-  listen: jest.fn(() => Promise.resolve(() => {})),
-}));
+// Handlers registered through `listen`, tests fire backend events with `emitTauriEvent`
+const mockListeners = {};
+const mockListen = jest.fn((name, handler) => {
+  (mockListeners[name] = mockListeners[name] || []).push(handler);
+  return Promise.resolve(() => {});
+});
+jest.mock('@tauri-apps/api/event', () => ({ __esModule: true, default: { listen: mockListen }, listen: mockListen }));
+
+global.emitTauriEvent = (name, payload) => {
+  (mockListeners[name] || []).forEach(handler => handler({ event: name, payload }));
+};
+
+// Backend commands answer with nothing found unless a test sets its own implementation
+const mockDefaultInvoke = (cmd, args) => {
+  switch (cmd) {
+    case 'search_keywords':
+      return Promise.resolve({ items: [], total: 0, page: args.page, pageSize: args.pageSize });
+    case 'validate_label':
+      return Promise.resolve({
+        label: args.label,
+        valid: true,
+        errors: [],
+        warnings: [],
+        waived: [],
+        rows: [],
+        message: '',
+        color: '',
+        consolidatedMessages: [],
+        lifeCycleState: null,
+      });
+    default:
+      return Promise.resolve(null);
+  }
+};
+jest.mock('@tauri-apps/api/core', () => ({ __esModule: true, invoke: jest.fn(mockDefaultInvoke) }));
+
+beforeEach(() => {
+  Object.keys(mockListeners).forEach(name => delete mockListeners[name]);
+  const { invoke } = jest.requireMock('@tauri-apps/api/core');
+  invoke.mockReset();
+  invoke.mockImplementation(mockDefaultInvoke);
+});
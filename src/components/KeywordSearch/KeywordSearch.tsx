@@ -10,28 +10,71 @@ import {
 import MenuBookIcon from '@mui/icons-material/MenuBook';
 import MinimizeIcon from '@mui/icons-material/Minimize';
 import CloseIcon from '@mui/icons-material/Close';
+import FileOpenIcon from '@mui/icons-material/FileOpen';
+import FolderOpenIcon from '@mui/icons-material/FolderOpen';
+import HistoryIcon from '@mui/icons-material/History';
 import { Window } from '@tauri-apps/api/window';
 import { event as tauriEvent } from "@tauri-apps/api";
+import { invoke } from '@tauri-apps/api/core';
 import tryCatch, { logDebug, logError } from '../index';
 import { useTheme } from '@mui/material/styles';
-import { filterData, getLabelRows, Keyword } from './utils/validation';
+import { expandQuery, Keyword } from './utils/validation';
 import SearchInput from './components/SearchInput';
 import ResultTable from './components/ResultTable';
+import ImportResults, { ImportReport, WaivedFinding } from './components/ImportResults';
+import KeywordChanges, { KeywordDiff } from './components/KeywordChanges';
 // Import new configuration system and error handling
-import { config, debugLog } from '../../config';
-import { handleError } from '../../utils/errorHandling';
+import { config } from '../../config';
+import { BackendError, fromBackendError, handleError } from '../../utils/errorHandling';
+
+// Matches fetched per search; ResultTable pages through them locally
+const SEARCH_RESULT_LIMIT = 200;
+// A validated label joins the recent labels in the tray after staying unchanged this long
+const RECENT_LABEL_DELAY_MS = 2000;
+
+interface KeywordPage {
+  items: Keyword[];
+  total: number;
+  page: number;
+  pageSize: number;
+}
+
+// Label or keyword from a `bbm-label://` link or the command line, see src-tauri/src/launch.rs
+interface OpenRequest {
+  kind: 'validate' | 'keyword';
+  value: string;
+}
+
+// Result of the `validate_label` command
+interface LabelReport {
+  label: string;
+  valid: boolean;
+  errors: string[];
+  warnings: string[];
+  waived: WaivedFinding[];
+  rows: Array<Record<string, unknown>>;
+  message: string;
+  color: string;
+  consolidatedMessages: { text: string; color: string }[];
+  lifeCycleState?: string | null;
+}
 
 const KeywordSearch = () => {
   const theme = useTheme();
-  const [keywords, setKeywords] = useState<Keyword[]>([]);
+  const [keywordsRevision, setKeywordsRevision] = useState(0);
+  const [filtered, setFiltered] = useState<Keyword[]>([]);
+  const [labelResult, setLabelResult] = useState<LabelReport | null>(null);
   const [search, setSearch] = useState("");
   const [loading, setLoading] = useState(true);
   const [labelInput, setLabelInput] = useState(""); const [activeInput, setActiveInput] = useState<'label' | 'search' | null>(null);
   const [autoDisappearTimer, setAutoDisappearTimer] = useState<NodeJS.Timeout | null>(null);
-  const [activeMenu] = useState('search'); const [debouncedSearch, setDebouncedSearch] = useState(search);
+  const [activeMenu, setActiveMenu] = useState('search'); const [debouncedSearch, setDebouncedSearch] = useState(search);
   const [debouncedLabelInput, setDebouncedLabelInput] = useState(labelInput);
   const [versionInfo, setVersionInfo] = useState<string>("");
   const [isOfflineMode, setIsOfflineMode] = useState(false);
+  const [importReport, setImportReport] = useState<ImportReport | null>(null);
+  const [importing, setImporting] = useState(false);
+  const [keywordChanges, setKeywordChanges] = useState<KeywordDiff | null>(null);
 
   // Resize handler to adjust UI for different window sizes
   useEffect(() => {
@@ -81,36 +124,53 @@ const KeywordSearch = () => {
     return () => clearTimeout(handler);
   }, [labelInput]);
 
-  const filtered = useMemo(() => {
-    if (!debouncedSearch.trim()) return [];
-    // Efficient: split and deduplicate tokens once, then filter in a single call
-    const normalized = debouncedSearch
-      .normalize('NFD')
-      .replace(/[-_,]/g, ' ')
-      .replace(/([a-z])([A-Z])/g, '$1 $2')
-      .replace(/\p{Diacritic}/gu, '')
-      .trim()
-      .toLowerCase();
-    const tokens = Array.from(new Set(normalized.split(/\s+/).filter(Boolean)));
-    // Join tokens back to a single string for filterData, or pass as array if filterData supports it
-    return filterData(keywords, tokens.join(' '));
-  }, [keywords, debouncedSearch]); const labelResult = useMemo(() => {
-    if (debouncedLabelInput.trim()) {
-      const result = getLabelRows(debouncedLabelInput.trim(), keywords);
-      // Ensure all properties match expected types
-      return {
-        ...result,
-        message: typeof result.message === 'string' ? result.message : undefined,
-        color: typeof result.color === 'string' ? result.color : undefined,
-        consolidatedMessages: result.consolidatedMessages || [],
-        // Include the lifecycle state and useInstead information
-        lifeCycleState: result.lifeCycleState,
-        useInstead: result.useInstead,
-        useInsteadAbbrName: result.useInsteadAbbrName
-      };
+  // The keywords live in the backend, search them there (spelling suggestions and stems are added here)
+  useEffect(() => {
+    if (!debouncedSearch.trim()) {
+      setFiltered([]);
+      return;
     }
-    return null;
-  }, [debouncedLabelInput, keywords]);  // Process labelResult for ResultTable component which expects rows as Keyword[]
+    let cancelled = false;
+    const tokens = expandQuery(debouncedSearch);
+    invoke<KeywordPage>('search_keywords', { query: tokens.join(' '), page: 0, pageSize: SEARCH_RESULT_LIMIT })
+      .then(result => {
+        if (!cancelled) {
+          logDebug(`search_keywords returned ${result.items.length} of ${result.total} matches`);
+          setFiltered(result.items);
+        }
+      })
+      .catch(error => handleError(error, 'KeywordSearch: search keywords'));
+    return () => { cancelled = true; };
+  }, [debouncedSearch, keywordsRevision]);
+
+  useEffect(() => {
+    const label = debouncedLabelInput.trim();
+    if (!label) {
+      setLabelResult(null);
+      return;
+    }
+    let cancelled = false;
+    invoke<LabelReport>('validate_label', { label })
+      .then(report => {
+        if (!cancelled) {
+          setLabelResult(report);
+        }
+      })
+      .catch(error => handleError(error, 'KeywordSearch: validate label'));
+    return () => { cancelled = true; };
+  }, [debouncedLabelInput, keywordsRevision]);
+
+  // Add a label to the recent labels in the tray once it stayed unchanged for a moment, not while typing
+  useEffect(() => {
+    if (!labelResult) return;
+    const handler = setTimeout(() => {
+      invoke('remember_label', { label: labelResult.label })
+        .catch(error => handleError(error, 'KeywordSearch: remember label'));
+    }, RECENT_LABEL_DELAY_MS);
+    return () => clearTimeout(handler);
+  }, [labelResult]);
+
+  // Process labelResult for ResultTable component which expects rows as Keyword[]
   const resultTableLabelData = useMemo(() => {
     if (!labelResult) return null;
 
@@ -123,6 +183,61 @@ const KeywordSearch = () => {
     };
   }, [labelResult]);
 
+  // Let the backend pick A2L/ARXML files, extract their labels and validate them
+  const handleImport = async () => {
+    setImporting(true);
+    try {
+      const report = await invoke<ImportReport | null>('import_label_files');
+      if (report) {
+        logDebug(`Imported ${report.summary.total} labels`);
+        setImportReport(report);
+        setActiveMenu('import');
+      }
+    } catch (error) {
+      handleError(error, 'KeywordSearch: import label files');
+    } finally {
+      setImporting(false);
+    }
+  };
+
+  // Let the backend pick a C/C++ source folder, scan it for labels and validate them
+  const handleScan = async () => {
+    setImporting(true);
+    try {
+      const report = await invoke<ImportReport | null>('scan_source_folder');
+      if (report) {
+        logDebug(`Scanned ${report.summary.total} labels`);
+        setImportReport(report);
+        setActiveMenu('import');
+      }
+    } catch (error) {
+      handleError(error, 'KeywordSearch: scan source folder');
+    } finally {
+      setImporting(false);
+    }
+  };
+
+  // Fill in what a link or a second start asked for; the backend hands each request out once
+  const takeOpenRequest = async () => {
+    try {
+      const request = await invoke<OpenRequest | null>('take_open_request');
+      if (!request) return;
+      logDebug(`Opening ${request.kind} ${request.value}`);
+      setActiveMenu('search');
+      if (request.kind === 'validate') {
+        setLabelInput(request.value);
+        setSearch('');
+        setActiveInput('label');
+      } else {
+        setSearch(request.value);
+        setLabelInput('');
+        setActiveInput('search');
+      }
+    } catch (error) {
+      handleError(error, 'KeywordSearch: open request');
+    }
+  };
+
   const handleMinimize = () => {
     Window.getCurrent().minimize();
   };
@@ -135,54 +250,30 @@ const KeywordSearch = () => {
     Window.getCurrent().close();
   };
   useEffect(() => {
-    let unlistenClear: (() => void) | undefined;
-    let unlistenStore: (() => void) | undefined;
+    let unlistenReady: (() => void) | undefined;
     let unlistenVersion: (() => void) | undefined;
+    let unlistenUpdated: (() => void) | undefined;
+    let unlistenUpToDate: (() => void) | undefined;
+    let unlistenBackendError: (() => void) | undefined;
+    let unlistenRuleSet: (() => void) | undefined;
+    let unlistenWaivers: (() => void) | undefined;
+    let unlistenOpen: (() => void) | undefined;
+    let unlistenChanges: (() => void) | undefined;
 
-    logDebug("Setting up Tauri event listeners for localStorage operations");
-
-    tauriEvent.listen('keyword-clear-localstorage', () => {
-      logDebug("Received keyword-clear-localstorage event");
-      tryCatch(() => {
-        logDebug("Removing keywords from localStorage with key: " + config.storage.keywordsKey);
-        localStorage.removeItem(config.storage.keywordsKey);
-        logDebug("Successfully cleared keywords from localStorage");
-      }, 'KeywordSearch: clearLocalStorage');
-    }).then(
-      (fn) => {
-        logDebug("Successfully registered keyword-clear-localstorage listener");
-        unlistenClear = fn;
-      }
-    ).catch(error => {
-      logError(error, "Failed to set up keyword-clear-localstorage listener");
-    });
-    tauriEvent.listen<string>('keyword-store-localstorage', (event) => {
-      logDebug("Received keyword-store-localstorage event");
-      tryCatch(() => {
-        if (event.payload) {
-          logDebug(`Storing keywords in localStorage with key: ${config.storage.keywordsKey}, data length: ${event.payload.length}`);
-          localStorage.setItem(config.storage.keywordsKey, event.payload);
+    logDebug("Setting up Tauri event listeners for keyword loading");
 
-          try {
-            const parsed = JSON.parse(event.payload);
-            logDebug(`Parsed keywords count: ${parsed.length}`);
-            setKeywords(parsed);
+    // Keywords are no longer kept in the webview, drop the copy older versions stored
+    localStorage.removeItem(config.storage.keywordsKey);
 
-            logDebug("Setting loading state to false");
-            setLoading(false);
-          } catch (error) {
-            handleError(error, 'KeywordSearch: parse JSON');
-            setLoading(false);
-          }
-        } else {
-          debugLog("Received keyword-store-localstorage event with empty payload");
-        }
-      }, 'KeywordSearch: storeLocalStorage');
+    tauriEvent.listen<{ count: number }>('keywords-ready', (event) => {
+      logDebug(`Received keywords-ready event: ${event.payload.count} keywords`);
+      setKeywordsRevision(revision => revision + 1);
+      setLoading(false);
     }).then((fn) => {
-      logDebug("Successfully registered keyword-store-localstorage listener");
-      unlistenStore = fn;
+      logDebug("Successfully registered keywords-ready listener");
+      unlistenReady = fn;
     }).catch(error => {
-      logError(error, "Failed to set up keyword-store-localstorage listener");
+      logError(error, "Failed to set up keywords-ready listener");
     });
     // Listen for offline mode status
     tauriEvent.listen('using-cached-keywords', () => {
@@ -226,20 +317,108 @@ const KeywordSearch = () => {
     }).catch(error => {
       logError(error, "Failed to set up version-info listener");
     });
+    // Background sync swapped in fresher keywords (keywords-ready follows)
+    tauriEvent.listen<{ updatedDate: string; version: string; count: number }>('keywords-updated', (event) => {
+      logDebug(`Received keywords-updated event: ${event.payload.count} keywords, ${event.payload.updatedDate}`);
+      setIsOfflineMode(false);
+      setVersionInfo(event.payload.version);
+    }).then((fn) => {
+      unlistenUpdated = fn;
+    }).catch(error => {
+      logError(error, "Failed to set up keywords-updated listener");
+    });
+    // Background sync confirmed the cached keywords are current
+    tauriEvent.listen<string>('keywords-up-to-date', (event) => {
+      logDebug("Received keywords-up-to-date event");
+      setIsOfflineMode(false);
+      setVersionInfo(event.payload);
+    }).then((fn) => {
+      unlistenUpToDate = fn;
+    }).catch(error => {
+      logError(error, "Failed to set up keywords-up-to-date listener");
+    });
+    // Failures of background tasks (loading, sync, semantic index) have no caller to reject
+    tauriEvent.listen<BackendError>('backend-error', (event) => {
+      const { source, code } = event.payload;
+      handleError(fromBackendError(event.payload), `backend: ${source ?? 'unknown'}`);
+      if (code === 'network') {
+        setIsOfflineMode(true);
+      }
+    }).then((fn) => {
+      unlistenBackendError = fn;
+    }).catch(error => {
+      logError(error, "Failed to set up backend-error listener");
+    });
+    // Another rule set was selected in Settings, validate the current label again
+    tauriEvent.listen<{ name: string }>('rule-set-changed', (event) => {
+      logDebug(`Received rule-set-changed event: ${event.payload.name}`);
+      setKeywordsRevision(revision => revision + 1);
+    }).then((fn) => {
+      unlistenRuleSet = fn;
+    }).catch(error => {
+      logError(error, "Failed to set up rule-set-changed listener");
+    });
+    // The waiver file changed in Settings, validate the current label again
+    tauriEvent.listen('waivers-changed', () => {
+      logDebug("Received waivers-changed event");
+      setKeywordsRevision(revision => revision + 1);
+    }).then((fn) => {
+      unlistenWaivers = fn;
+    }).catch(error => {
+      logError(error, "Failed to set up waivers-changed listener");
+    });
+    // A link was opened or the app was started again with a label
+    tauriEvent.listen('open-request', () => {
+      logDebug("Received open-request event");
+      takeOpenRequest();
+    }).then((fn) => {
+      unlistenOpen = fn;
+    }).catch(error => {
+      logError(error, "Failed to set up open-request listener");
+    });
+    // An update changed the keyword database, show what changed unless import results are open
+    tauriEvent.listen<KeywordDiff>('keyword-changes', (event) => {
+      logDebug(`Received keyword-changes event: ${JSON.stringify(event.payload.summary)}`);
+      setKeywordChanges(event.payload);
+      setActiveMenu(menu => (menu === 'import' ? menu : 'changes'));
+    }).then((fn) => {
+      unlistenChanges = fn;
+    }).catch(error => {
+      logError(error, "Failed to set up keyword-changes listener");
+    });
+    // The link or label this instance was started with
+    takeOpenRequest();
     return () => {
       logDebug("Cleaning up Tauri event listeners");
-      if (unlistenClear) {
-        logDebug("Removing keyword-clear-localstorage listener");
-        unlistenClear();
-      }
-      if (unlistenStore) {
-        logDebug("Removing keyword-store-localstorage listener");
-        unlistenStore();
+      if (unlistenReady) {
+        logDebug("Removing keywords-ready listener");
+        unlistenReady();
       }
       if (unlistenVersion) {
         logDebug("Removing version-info listener");
         unlistenVersion();
       }
+      if (unlistenUpdated) {
+        unlistenUpdated();
+      }
+      if (unlistenUpToDate) {
+        unlistenUpToDate();
+      }
+      if (unlistenBackendError) {
+        unlistenBackendError();
+      }
+      if (unlistenRuleSet) {
+        unlistenRuleSet();
+      }
+      if (unlistenWaivers) {
+        unlistenWaivers();
+      }
+      if (unlistenOpen) {
+        unlistenOpen();
+      }
+      if (unlistenChanges) {
+        unlistenChanges();
+      }
     };
   }, []);
 
@@ -334,6 +513,48 @@ const KeywordSearch = () => {
               BBM Label Explorer
             </Typography>
           </Box>          <Box sx={{ display: 'flex', alignItems: 'center', gap: 0.5, minWidth: 0, WebkitAppRegion: 'no-drag' }}>
+            <FileOpenIcon
+              onClick={importing ? undefined : handleImport}
+              sx={{
+                cursor: importing ? 'progress' : 'pointer',
+                fontSize: 17,
+                color: theme.palette.mode === 'dark' ? '#b7c2d0' : '#2d3a4d',
+                '&:hover': { color: theme.palette.primary.main },
+                transition: 'color 0.2s',
+                borderRadius: 1,
+                p: 0.1,
+                mx: 0.2
+              }}
+              titleAccess="Validate labels from A2L / ARXML files"
+            />
+            <FolderOpenIcon
+              onClick={importing ? undefined : handleScan}
+              sx={{
+                cursor: importing ? 'progress' : 'pointer',
+                fontSize: 17,
+                color: theme.palette.mode === 'dark' ? '#b7c2d0' : '#2d3a4d',
+                '&:hover': { color: theme.palette.primary.main },
+                transition: 'color 0.2s',
+                borderRadius: 1,
+                p: 0.1,
+                mx: 0.2
+              }}
+              titleAccess="Validate labels used in a C/C++ source folder"
+            />
+            <HistoryIcon
+              onClick={() => setActiveMenu('changes')}
+              sx={{
+                cursor: 'pointer',
+                fontSize: 17,
+                color: theme.palette.mode === 'dark' ? '#b7c2d0' : '#2d3a4d',
+                '&:hover': { color: theme.palette.primary.main },
+                transition: 'color 0.2s',
+                borderRadius: 1,
+                p: 0.1,
+                mx: 0.2
+              }}
+              titleAccess="What changed in the keyword database"
+            />
             <MinimizeIcon
               onClick={handleMinimize}
               sx={{
@@ -412,6 +633,18 @@ const KeywordSearch = () => {
             />)}
             </React.Fragment>
             )}
+            {activeMenu === 'import' && importReport && (
+              <ImportResults
+                report={importReport}
+                onBack={() => setActiveMenu('search')}
+              />
+            )}
+            {activeMenu === 'changes' && (
+              <KeywordChanges
+                diff={keywordChanges}
+                onBack={() => setActiveMenu('search')}
+              />
+            )}
             {activeMenu === 'info' && (
               <Box sx={{ width: '100%', maxWidth: 600, mt: 2 }}>
                 <Typography variant="h6" sx={{ mb: 1 }}>Info</Typography>
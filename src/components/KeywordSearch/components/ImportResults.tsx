@@ -0,0 +1,260 @@
+import * as React from "react";
+import {
+  Box,
+  Table,
+  TableBody,
+  TableCell,
+  TableContainer,
+  TableHead,
+  TableRow,
+  Paper,
+  Typography,
+  Pagination,
+  Tooltip
+} from "@mui/material";
+import ArrowBackIcon from '@mui/icons-material/ArrowBack';
+import { useTheme } from '@mui/material/styles';
+import { invoke } from '@tauri-apps/api/core';
+import { logDebug } from '../../index';
+import { handleError } from '../../../utils/errorHandling';
+
+// Finding accepted by an entry of the waiver file
+export interface WaivedFinding {
+  error: string;
+  wasError: boolean;
+  justification: string;
+  author: string;
+  expires?: string;
+}
+
+// Waiver file entry, reported when it expired or matched nothing
+export interface Waiver {
+  label: string;
+  rule: string;
+  justification: string;
+  author: string;
+  expires?: string;
+}
+
+// Shape of the batch report returned by the `import_label_files` command
+export interface ImportedLabel {
+  name: string;
+  kind: string;
+  file?: string | null;
+  line: number;
+  report: {
+    valid: boolean;
+    errors: string[];
+    warnings: string[];
+    waived: WaivedFinding[];
+    message: string;
+  };
+}
+
+export interface ImportReport {
+  files: string[];
+  results: ImportedLabel[];
+  summary: {
+    total: number;
+    valid: number;
+    invalid: number;
+    errors: Array<{ error: string; count: number }>;
+    warnings: Array<{ error: string; count: number }>;
+    waived: Array<{ error: string; count: number }>;
+  };
+  waivers: {
+    file?: string | null;
+    waived: number;
+    expired: Waiver[];
+    unused: Waiver[];
+  };
+}
+
+export interface ImportResultsProps {
+  report: ImportReport;
+  onBack: () => void;
+}
+
+const ROWS_PER_PAGE = 8;
+
+// Formats accepted by the `export_report` command
+const EXPORT_FORMATS = [
+  { format: 'json', label: 'JSON' },
+  { format: 'sarif', label: 'SARIF' },
+  { format: 'junit', label: 'JUnit' },
+];
+
+// File name without directories, the full path is shown as tooltip
+const baseName = (path?: string | null) => (path ? path.split(/[\\/]/).pop() : '') || '';
+
+const ImportResults: React.FC<ImportResultsProps> = ({ report, onBack }) => {
+  const theme = useTheme();
+  const [page, setPage] = React.useState(1);
+  const [onlyInvalid, setOnlyInvalid] = React.useState(true);
+
+  const rows = React.useMemo(
+    () => report.results.filter(r => !onlyInvalid || !r.report.valid),
+    [report, onlyInvalid]
+  );
+  const pageCount = Math.max(1, Math.ceil(rows.length / ROWS_PER_PAGE));
+  const safePage = Math.min(page, pageCount);
+  const paginatedRows = rows.slice((safePage - 1) * ROWS_PER_PAGE, safePage * ROWS_PER_PAGE);
+
+  // The backend keeps the last imported report and asks where to save it
+  const handleExport = async (format: string) => {
+    try {
+      const path = await invoke<string | null>('export_report', { format });
+      if (path) {
+        logDebug(`Exported ${format} report to ${path}`);
+      }
+    } catch (error) {
+      handleError(error, 'ImportResults: export report');
+    }
+  };
+
+  const cellStyle = {
+    fontSize: '0.7rem',
+    py: 0.2,
+    border: '1px solid',
+    borderColor: theme.palette.divider,
+    whiteSpace: 'nowrap',
+    overflow: 'hidden',
+    textOverflow: 'ellipsis'
+  };
+  const headerCellStyle = {
+    ...cellStyle,
+    fontWeight: 600,
+    background: theme.palette.mode === 'dark' ? '#2a2d33' : '#eef1f6'
+  };
+
+  return (
+    <Box sx={{ width: '100%', display: 'flex', flexDirection: 'column', gap: 0.5 }}>
+      <Box sx={{ display: 'flex', alignItems: 'center', gap: 0.5 }}>
+        <ArrowBackIcon
+          onClick={onBack}
+          titleAccess="Back to search"
+          sx={{ fontSize: 16, cursor: 'pointer', '&:hover': { color: theme.palette.primary.main } }}
+        />
+        <Typography sx={{ fontSize: '0.75rem', fontWeight: 500 }}>
+          {report.summary.total} labels from {report.files.map(f => baseName(f)).join(', ')}
+        </Typography>
+        <Typography sx={{ fontSize: '0.75rem', color: 'success.main', ml: 1 }}>
+          {report.summary.valid} valid
+        </Typography>
+        <Typography
+          onClick={() => { setOnlyInvalid(!onlyInvalid); setPage(1); }}
+          title={onlyInvalid ? 'Showing invalid labels only, click to show all' : 'Click to show invalid labels only'}
+          sx={{ fontSize: '0.75rem', color: 'error.main', cursor: 'pointer', textDecoration: onlyInvalid ? 'underline' : 'none' }}
+        >
+          {report.summary.invalid} invalid
+        </Typography>
+        <Box sx={{ ml: 'auto', display: 'flex', alignItems: 'center', gap: 0.75 }}>
+          <Typography sx={{ fontSize: '0.7rem', color: 'text.secondary' }}>Export report:</Typography>
+          {EXPORT_FORMATS.map(({ format, label }) => (
+            <Typography
+              key={format}
+              onClick={() => handleExport(format)}
+              sx={{ fontSize: '0.7rem', color: 'primary.main', cursor: 'pointer', '&:hover': { textDecoration: 'underline' } }}
+            >
+              {label}
+            </Typography>
+          ))}
+        </Box>
+      </Box>
+      {(report.summary.errors.length > 0 || report.summary.warnings.length > 0 || report.summary.waived.length > 0) && (
+        <Box sx={{ display: 'flex', flexWrap: 'wrap', gap: 1 }}>
+          {report.summary.errors.map(e => (
+            <Typography key={e.error} sx={{ fontSize: '0.65rem', color: 'text.secondary' }}>
+              {e.error}: {e.count}
+            </Typography>
+          ))}
+          {report.summary.warnings.map(w => (
+            <Typography key={`warning-${w.error}`} sx={{ fontSize: '0.65rem', color: 'warning.main' }}>
+              {w.error}: {w.count}
+            </Typography>
+          ))}
+          {report.summary.waived.map(w => (
+            <Typography key={`waived-${w.error}`} sx={{ fontSize: '0.65rem', color: 'info.main' }}>
+              waived {w.error}: {w.count}
+            </Typography>
+          ))}
+        </Box>
+      )}
+      {(report.waivers.expired.length > 0 || report.waivers.unused.length > 0) && (
+        <Tooltip title={report.waivers.file ?? ''} placement="bottom-start">
+          <Box sx={{ display: 'flex', flexWrap: 'wrap', gap: 1 }}>
+            {report.waivers.expired.map((w, index) => (
+              <Typography key={`expired-${index}`} sx={{ fontSize: '0.65rem', color: 'warning.main' }}>
+                Expired waiver: {w.label} {w.rule} ({w.expires})
+              </Typography>
+            ))}
+            {report.waivers.unused.map((w, index) => (
+              <Typography key={`unused-${index}`} sx={{ fontSize: '0.65rem', color: 'text.secondary' }}>
+                Unused waiver: {w.label} {w.rule}
+              </Typography>
+            ))}
+          </Box>
+        </Tooltip>
+      )}
+      <TableContainer component={Paper} elevation={0} sx={{ borderRadius: 0, boxShadow: 'none' }}>
+        <Table size="small" sx={{ tableLayout: 'fixed', width: '100%' }}>
+          <TableHead>
+            <TableRow>
+              <TableCell sx={{ ...headerCellStyle, width: '35%' }}>Label</TableCell>
+              <TableCell sx={{ ...headerCellStyle, width: '25%' }}>Location</TableCell>
+              <TableCell sx={{ ...headerCellStyle, width: '40%' }}>Result</TableCell>
+            </TableRow>
+          </TableHead>
+          <TableBody>
+            {paginatedRows.length === 0 ? (
+              <TableRow>
+                <TableCell colSpan={3} align="center" sx={cellStyle}>
+                  No labels to show
+                </TableCell>
+              </TableRow>
+            ) : paginatedRows.map((row, index) => (
+              <TableRow key={`${row.file}-${row.line}-${index}`} className={row.report.valid ? 'row-valid' : 'row-invalid'}>
+                <TableCell sx={cellStyle}>{row.name}</TableCell>
+                <Tooltip title={`${row.file ?? ''}:${row.line} (${row.kind})`} placement="bottom-start">
+                  <TableCell sx={cellStyle}>{baseName(row.file)}:{row.line}</TableCell>
+                </Tooltip>
+                <Tooltip
+                  title={[
+                    ...row.report.errors,
+                    ...row.report.warnings,
+                    ...row.report.waived.map(w => `waived: ${w.error} (${w.justification}, ${w.author})`)
+                  ].join('\n')}
+                  placement="bottom-start"
+                >
+                  <TableCell sx={{
+                    ...cellStyle,
+                    color: !row.report.valid ? 'error.main'
+                      : row.report.warnings.length > 0 ? 'warning.main'
+                        : row.report.waived.length > 0 ? 'info.main' : 'success.main'
+                  }}>
+                    {row.report.valid && row.report.warnings.length === 0 && row.report.waived.length === 0 ? 'Valid' : row.report.message}
+                  </TableCell>
+                </Tooltip>
+              </TableRow>
+            ))}
+          </TableBody>
+        </Table>
+      </TableContainer>
+      {pageCount > 1 && (
+        <Box sx={{ display: 'flex', justifyContent: 'flex-end' }}>
+          <Pagination
+            count={pageCount}
+            page={safePage}
+            onChange={(_, value) => setPage(value)}
+            size="small"
+            siblingCount={1}
+            boundaryCount={0}
+            sx={{ '& .MuiPaginationItem-root': { fontSize: '0.7rem', minWidth: 20, height: 20 } }}
+          />
+        </Box>
+      )}
+    </Box>
+  );
+};
+
+export default ImportResults;
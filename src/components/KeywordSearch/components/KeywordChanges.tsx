@@ -0,0 +1,266 @@
+import * as React from "react";
+import {
+  Box,
+  Table,
+  TableBody,
+  TableCell,
+  TableContainer,
+  TableHead,
+  TableRow,
+  Paper,
+  Typography,
+  Pagination,
+  Tooltip,
+  Select,
+  MenuItem
+} from "@mui/material";
+import ArrowBackIcon from '@mui/icons-material/ArrowBack';
+import { useTheme } from '@mui/material/styles';
+import { invoke } from '@tauri-apps/api/core';
+import { logDebug } from '../../index';
+import { handleError } from '../../../utils/errorHandling';
+
+// Dated copy of the keyword database, see src-tauri/src/history.rs
+export interface KeywordSnapshot {
+  id: string;
+  updatedDate?: string;
+  fetchedAt: string;
+  count: number;
+}
+
+export type ChangeKind = 'added' | 'deprecated' | 'changed' | 'removed';
+
+export interface KeywordChange {
+  kind: ChangeKind;
+  abbrName: string;
+  longNameEn?: string | null;
+  lifeCycleState?: string | null;
+  fields: Array<{ field: string; old?: string | null; new?: string | null }>;
+}
+
+// Result of the `diff_keyword_snapshots` command and payload of the `keyword-changes` event
+export interface KeywordDiff {
+  from: KeywordSnapshot;
+  to: KeywordSnapshot;
+  summary: Record<ChangeKind, number>;
+  changes: KeywordChange[];
+}
+
+export interface KeywordChangesProps {
+  diff: KeywordDiff | null;
+  onBack: () => void;
+}
+
+const ROWS_PER_PAGE = 8;
+
+const KINDS: Array<{ kind: ChangeKind; color: string }> = [
+  { kind: 'added', color: 'success.main' },
+  { kind: 'deprecated', color: 'warning.main' },
+  { kind: 'changed', color: 'info.main' },
+  { kind: 'removed', color: 'error.main' },
+];
+
+// Formats accepted by the `export_keyword_changes` command
+const EXPORT_FORMATS = [
+  { format: 'markdown', label: 'Markdown' },
+  { format: 'csv', label: 'CSV' },
+];
+
+// Same wording as the title bar, e.g. `DB: 13.06.2025`
+const snapshotName = (snapshot: KeywordSnapshot) => {
+  const date = (snapshot.updatedDate ?? snapshot.fetchedAt).slice(0, 10).split('-');
+  return date.length === 3 ? `DB: ${date[2]}.${date[1]}.${date[0]}` : `DB: ${snapshot.updatedDate ?? snapshot.fetchedAt}`;
+};
+
+const fieldChanges = (change: KeywordChange) =>
+  change.fields.map(f => `${f.field}: ${f.old ?? '–'} → ${f.new ?? '–'}`).join('\n');
+
+const KeywordChanges: React.FC<KeywordChangesProps> = ({ diff: initialDiff, onBack }) => {
+  const theme = useTheme();
+  const [diff, setDiff] = React.useState<KeywordDiff | null>(initialDiff);
+  const [snapshots, setSnapshots] = React.useState<KeywordSnapshot[]>([]);
+  const [kind, setKind] = React.useState<ChangeKind | null>(null);
+  const [page, setPage] = React.useState(1);
+
+  // A newer update replaces the diff shown
+  React.useEffect(() => {
+    if (initialDiff) {
+      setDiff(initialDiff);
+    }
+  }, [initialDiff]);
+
+  React.useEffect(() => {
+    invoke<KeywordSnapshot[]>('list_keyword_snapshots')
+      .then(setSnapshots)
+      .catch(error => handleError(error, 'KeywordChanges: list snapshots'));
+    if (!initialDiff) {
+      invoke<KeywordDiff | null>('diff_keyword_snapshots', { from: null, to: null })
+        .then(setDiff)
+        .catch(error => handleError(error, 'KeywordChanges: diff snapshots'));
+    }
+  }, []);
+
+  const compare = async (from: string, to: string) => {
+    try {
+      const result = await invoke<KeywordDiff | null>('diff_keyword_snapshots', { from, to });
+      setDiff(result);
+      setPage(1);
+    } catch (error) {
+      handleError(error, 'KeywordChanges: diff snapshots');
+    }
+  };
+
+  // The backend computes the diff again and asks where to save it
+  const handleExport = async (format: string) => {
+    if (!diff) return;
+    try {
+      const path = await invoke<string | null>('export_keyword_changes', { from: diff.from.id, to: diff.to.id, format });
+      if (path) {
+        logDebug(`Exported keyword changes as ${format} to ${path}`);
+      }
+    } catch (error) {
+      handleError(error, 'KeywordChanges: export changes');
+    }
+  };
+
+  const rows = React.useMemo(
+    () => (diff?.changes ?? []).filter(c => !kind || c.kind === kind),
+    [diff, kind]
+  );
+  const pageCount = Math.max(1, Math.ceil(rows.length / ROWS_PER_PAGE));
+  const safePage = Math.min(page, pageCount);
+  const paginatedRows = rows.slice((safePage - 1) * ROWS_PER_PAGE, safePage * ROWS_PER_PAGE);
+
+  const cellStyle = {
+    fontSize: '0.7rem',
+    py: 0.2,
+    border: '1px solid',
+    borderColor: theme.palette.divider,
+    whiteSpace: 'nowrap',
+    overflow: 'hidden',
+    textOverflow: 'ellipsis'
+  };
+  const headerCellStyle = {
+    ...cellStyle,
+    fontWeight: 600,
+    background: theme.palette.mode === 'dark' ? '#2a2d33' : '#eef1f6'
+  };
+  const selectStyle = { fontSize: '0.7rem', '& .MuiSelect-select': { py: 0.1 } };
+
+  const snapshotSelect = (value: string, onChange: (id: string) => void) => (
+    <Select variant="standard" value={value} onChange={e => onChange(e.target.value)} sx={selectStyle}>
+      {snapshots.map(s => (
+        <MenuItem key={s.id} value={s.id} sx={{ fontSize: '0.7rem' }}>
+          {snapshotName(s)} ({s.count})
+        </MenuItem>
+      ))}
+    </Select>
+  );
+
+  return (
+    <Box sx={{ width: '100%', display: 'flex', flexDirection: 'column', gap: 0.5 }}>
+      <Box sx={{ display: 'flex', alignItems: 'center', gap: 0.5 }}>
+        <ArrowBackIcon
+          onClick={onBack}
+          titleAccess="Back to search"
+          sx={{ fontSize: 16, cursor: 'pointer', '&:hover': { color: theme.palette.primary.main } }}
+        />
+        <Typography sx={{ fontSize: '0.75rem', fontWeight: 500 }}>What changed</Typography>
+        {diff && snapshots.some(s => s.id === diff.from.id) && snapshots.some(s => s.id === diff.to.id) ? (
+          <>
+            {snapshotSelect(diff.from.id, id => compare(id, diff.to.id))}
+            <Typography sx={{ fontSize: '0.7rem' }}>→</Typography>
+            {snapshotSelect(diff.to.id, id => compare(diff.from.id, id))}
+          </>
+        ) : diff && (
+          <Typography sx={{ fontSize: '0.7rem' }}>
+            {snapshotName(diff.from)} → {snapshotName(diff.to)}
+          </Typography>
+        )}
+        {diff && (
+          <Box sx={{ ml: 'auto', display: 'flex', alignItems: 'center', gap: 0.75 }}>
+            <Typography sx={{ fontSize: '0.7rem', color: 'text.secondary' }}>Export:</Typography>
+            {EXPORT_FORMATS.map(({ format, label }) => (
+              <Typography
+                key={format}
+                onClick={() => handleExport(format)}
+                sx={{ fontSize: '0.7rem', color: 'primary.main', cursor: 'pointer', '&:hover': { textDecoration: 'underline' } }}
+              >
+                {label}
+              </Typography>
+            ))}
+          </Box>
+        )}
+      </Box>
+      {!diff ? (
+        <Typography sx={{ fontSize: '0.7rem', color: 'text.secondary' }}>
+          No changes yet: the keyword database is compared once it has been updated.
+        </Typography>
+      ) : (
+        <>
+          <Box sx={{ display: 'flex', flexWrap: 'wrap', gap: 1 }}>
+            {KINDS.map(k => (
+              <Typography
+                key={k.kind}
+                onClick={() => { setKind(kind === k.kind ? null : k.kind); setPage(1); }}
+                title={kind === k.kind ? 'Click to show all changes' : `Click to show ${k.kind} keywords only`}
+                sx={{ fontSize: '0.7rem', color: k.color, cursor: 'pointer', textDecoration: kind === k.kind ? 'underline' : 'none' }}
+              >
+                {diff.summary[k.kind]} {k.kind}
+              </Typography>
+            ))}
+          </Box>
+          <TableContainer component={Paper} elevation={0} sx={{ borderRadius: 0, boxShadow: 'none' }}>
+            <Table size="small" sx={{ tableLayout: 'fixed', width: '100%' }}>
+              <TableHead>
+                <TableRow>
+                  <TableCell sx={{ ...headerCellStyle, width: '15%' }}>Change</TableCell>
+                  <TableCell sx={{ ...headerCellStyle, width: '20%' }}>Abbreviation</TableCell>
+                  <TableCell sx={{ ...headerCellStyle, width: '25%' }}>Long name</TableCell>
+                  <TableCell sx={{ ...headerCellStyle, width: '40%' }}>Details</TableCell>
+                </TableRow>
+              </TableHead>
+              <TableBody>
+                {paginatedRows.length === 0 ? (
+                  <TableRow>
+                    <TableCell colSpan={4} align="center" sx={cellStyle}>
+                      No keywords changed
+                    </TableCell>
+                  </TableRow>
+                ) : paginatedRows.map((change, index) => (
+                  <TableRow key={`${change.kind}-${change.abbrName}-${index}`}>
+                    <TableCell sx={{ ...cellStyle, color: KINDS.find(k => k.kind === change.kind)?.color }}>
+                      {change.kind}
+                    </TableCell>
+                    <TableCell sx={cellStyle}>{change.abbrName}</TableCell>
+                    <TableCell sx={cellStyle}>{change.longNameEn ?? ''}</TableCell>
+                    <Tooltip title={<span style={{ whiteSpace: 'pre-line' }}>{fieldChanges(change)}</span>} placement="bottom-start">
+                      <TableCell sx={cellStyle}>
+                        {change.fields.length > 0 ? fieldChanges(change).replace(/\n/g, '; ') : change.lifeCycleState ?? ''}
+                      </TableCell>
+                    </Tooltip>
+                  </TableRow>
+                ))}
+              </TableBody>
+            </Table>
+          </TableContainer>
+          {pageCount > 1 && (
+            <Box sx={{ display: 'flex', justifyContent: 'flex-end' }}>
+              <Pagination
+                count={pageCount}
+                page={safePage}
+                onChange={(_, value) => setPage(value)}
+                size="small"
+                siblingCount={1}
+                boundaryCount={0}
+                sx={{ '& .MuiPaginationItem-root': { fontSize: '0.7rem', minWidth: 20, height: 20 } }}
+              />
+            </Box>
+          )}
+        </>
+      )}
+    </Box>
+  );
+};
+
+export default KeywordChanges;
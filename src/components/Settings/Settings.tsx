@@ -18,7 +18,8 @@ import {
   Toolbar,
   CssBaseline,
   useTheme as useMuiTheme,
-  Link
+  Link,
+  MenuItem
 } from '@mui/material';
 import SettingsIcon from '@mui/icons-material/Settings';
 import InfoIcon from '@mui/icons-material/Info';
@@ -28,17 +29,28 @@ import CloseIcon from '@mui/icons-material/Close';
 import Brightness4Icon from '@mui/icons-material/Brightness4';
 import Brightness7Icon from '@mui/icons-material/Brightness7';
 import { Window } from '@tauri-apps/api/window';
+import { config } from '../../config';
+import { isBackendError } from '../../utils/errorHandling';
 // Import custom hooks
 import { useTheme } from '../../hooks';
 
 // Import from modular files
-import { ToolBehaviorSettings } from './types';
+import { BackendSettings, HotkeyMode, LocalApiInfo, RuleSetInfo, ToolBehaviorSettings, WaiverFileInfo } from './types';
 import { defaultToolBehavior } from './constants';
 import {
   getApiUrl,
   saveApiUrl,
+  getBackendSettings,
+  saveBackendSettings,
   getToolBehavior,
-  saveToolBehavior
+  saveToolBehavior,
+  listRuleSets,
+  setActiveRuleSet,
+  importRuleSet,
+  getWaiverFile,
+  setWaiverFile,
+  getLocalApi,
+  setLocalApi
 } from './utils';
 
 const SIDEBAR_ITEMS = [
@@ -58,7 +70,22 @@ const Settings: React.FC<SettingsProps> = () => {
   const [saved, setSaved] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [toolBehavior, setToolBehavior] = useState<ToolBehaviorSettings>(defaultToolBehavior);
+  const [backendSettings, setBackendSettings] = useState<BackendSettings | null>(null);
   const [activeSection, setActiveSection] = useState('tool');
+  const [ruleSets, setRuleSets] = useState<RuleSetInfo[]>([]);
+  const [ruleSetError, setRuleSetError] = useState<string | null>(null);
+  const [waiverFile, setWaiverFileInfo] = useState<WaiverFileInfo | null>(null);
+  const [waiverError, setWaiverError] = useState<string | null>(null);
+  const [localApi, setLocalApiInfo] = useState<LocalApiInfo | null>(null);
+  const [localApiPort, setLocalApiPort] = useState('');
+  const [localApiError, setLocalApiError] = useState<string | null>(null);
+  const [hotkey, setHotkey] = useState('');
+  const [hotkeyMode, setHotkeyMode] = useState<HotkeyMode>('window');
+  const [clipboardError, setClipboardError] = useState<string | null>(null);
+  const [clipboardSaved, setClipboardSaved] = useState(false);
+  const [clipboardWatch, setClipboardWatch] = useState(false);
+  const [clipboardPattern, setClipboardPattern] = useState('*_*');
+  const [historySize, setHistorySize] = useState(10);
 
   const handleClose = (e?: React.MouseEvent) => {
     if (e) {
@@ -73,12 +100,137 @@ const Settings: React.FC<SettingsProps> = () => {
     setToolBehavior(getToolBehavior());
     // Load additional URLs from localStorage if present
     setApiUrl2(localStorage.getItem('settings_api_url_2') || '');
+    // The backend owns the URL the keyword loaders use
+    getBackendSettings().then(settings => {
+      if (settings) {
+        setBackendSettings(settings);
+        setApiUrl(settings.apiBaseUrl);
+        setHotkey(settings.hotkey ?? '');
+        setHotkeyMode(settings.hotkeyMode ?? 'window');
+        setClipboardWatch(settings.clipboardWatch ?? false);
+        setClipboardPattern(settings.clipboardPattern ?? '*_*');
+        setHistorySize(settings.historySize ?? 10);
+      }
+    });
+    listRuleSets().then(setRuleSets);
+    getWaiverFile().then(setWaiverFileInfo);
+    getLocalApi().then(info => {
+      if (info) {
+        setLocalApiInfo(info);
+        setLocalApiPort(String(info.port));
+      }
+    });
   }, []);
+
+  // Rule sets apply right away, the main window validates again on rule-set-changed
+  const handleRuleSetChange = async (file: string | null) => {
+    try {
+      const info = await setActiveRuleSet(file);
+      setRuleSetError(null);
+      setRuleSets(await listRuleSets());
+      if (backendSettings) {
+        setBackendSettings({ ...backendSettings, ruleSet: info.file });
+      }
+    } catch (err) {
+      setRuleSetError(isBackendError(err) ? err.message : String(err));
+    }
+  };
+
+  const handleRuleSetImport = async () => {
+    try {
+      const file = await importRuleSet();
+      if (file) {
+        setRuleSets(await listRuleSets());
+        await handleRuleSetChange(file);
+      }
+    } catch (err) {
+      setRuleSetError(isBackendError(err) ? err.message : String(err));
+    }
+  };
   
-  const handleSave = () => {
+  // Waiver files stay where they are, usually next to the project's sources
+  const handleWaiverFile = async (clear: boolean) => {
+    try {
+      const info = await setWaiverFile(clear);
+      if (info || clear) {
+        setWaiverFileInfo(info);
+        setWaiverError(null);
+        if (backendSettings) {
+          setBackendSettings({ ...backendSettings, waiverFile: info?.path ?? null });
+        }
+      }
+    } catch (err) {
+      setWaiverError(isBackendError(err) ? err.message : String(err));
+    }
+  };
+
+  // The local API starts or stops right away; a new token locks out tools using the old one
+  const handleLocalApi = async (enabled: boolean, newToken = false) => {
+    const port = Number(localApiPort);
+    if (!Number.isInteger(port) || port < 1 || port > 65535) {
+      setLocalApiError('Port must be a number from 1 to 65535');
+      return;
+    }
+    try {
+      const info = await setLocalApi(enabled, port, newToken);
+      setLocalApiInfo(info);
+      setLocalApiError(null);
+      if (backendSettings) {
+        setBackendSettings({
+          ...backendSettings,
+          localApiEnabled: info.enabled,
+          localApiPort: info.port,
+          localApiToken: info.token
+        });
+      }
+    } catch (err) {
+      setLocalApiError(isBackendError(err) ? err.message : String(err));
+      setLocalApiInfo(await getLocalApi());
+    }
+  };
+
+  // The backend registers the shortcut and starts or stops watching right away; an empty
+  // shortcut field registers none
+  const handleClipboardApply = async () => {
+    if (!backendSettings) return;
+    const updated = {
+      ...backendSettings,
+      hotkey: hotkey.trim() || null,
+      hotkeyMode,
+      clipboardWatch,
+      clipboardPattern: clipboardPattern.trim(),
+      historySize
+    };
+    try {
+      await saveBackendSettings(updated);
+      setBackendSettings(updated);
+      setClipboardError(null);
+      setClipboardSaved(true);
+      setTimeout(() => setClipboardSaved(false), 1200);
+    } catch (err) {
+      setClipboardError(isBackendError(err) ? err.message : String(err));
+    }
+  };
+
+  const handleSave = async () => {
+    if (!apiUrl) {
+      setError('Primary API URL cannot be empty');
+      return;
+    }
+    if (backendSettings) {
+      try {
+        await saveBackendSettings({ ...backendSettings, apiBaseUrl: apiUrl });
+      } catch (err) {
+        setError(isBackendError(err) ? err.message : String(err));
+        return;
+      }
+    }
     tryCatch(() => {
-      if (!apiUrl) throw new Error('Primary API URL cannot be empty');
-      saveApiUrl(apiUrl);
+      if (backendSettings) {
+        localStorage.setItem(config.storage.apiUrlKey, apiUrl);
+      } else {
+        saveApiUrl(apiUrl);
+      }
       localStorage.setItem('settings_api_url_2', apiUrl2);
       setSaved(true);
       setError(null);
@@ -86,6 +238,26 @@ const Settings: React.FC<SettingsProps> = () => {
     }, 'Settings handleSave');
   };
 
+  const backendFieldProps = {
+    fullWidth: true,
+    variant: 'outlined' as const,
+    size: 'small' as const,
+    InputLabelProps: {
+      shrink: true,
+      sx: { fontWeight: 400, fontSize: '13px', fontFamily: '"Segoe UI", system-ui, -apple-system, sans-serif' }
+    },
+    InputProps: {
+      sx: {
+        borderRadius: 0.5,
+        fontSize: '13px',
+        fontFamily: '"Segoe UI", system-ui, -apple-system, sans-serif',
+        '.MuiOutlinedInput-notchedOutline': {
+          borderColor: themeMode === 'dark' ? 'rgba(255, 255, 255, 0.12)' : 'rgba(0, 0, 0, 0.15)'
+        }
+      }
+    }
+  };
+
   const handleToolBehaviorChange = (field: keyof ToolBehaviorSettings, value: any) => {
     const updated = { ...toolBehavior, [field]: value };
     setToolBehavior(updated);
@@ -340,6 +512,25 @@ const Settings: React.FC<SettingsProps> = () => {
                   }
                 }
               }} />
+            {backendSettings && (
+              <Box sx={{ display: 'flex', gap: 1 }}>
+                <TextField
+                  {...backendFieldProps}
+                  label="Version endpoint"
+                  value={backendSettings.versionEndpoint}
+                  onChange={e => setBackendSettings({ ...backendSettings, versionEndpoint: e.target.value })}
+                />
+                <TextField
+                  {...backendFieldProps}
+                  type="number"
+                  label="Timeout (sec)"
+                  value={backendSettings.requestTimeoutSecs}
+                  onChange={e => setBackendSettings({ ...backendSettings, requestTimeoutSecs: Math.max(1, Number(e.target.value)) })}
+                  inputProps={{ min: 1 }}
+                  sx={{ width: 140 }}
+                />
+              </Box>
+            )}
           </Box>
 
           <Box sx={{ mb: 2, width: '100%', backgroundColor: themeMode === 'dark' ? 'rgba(50, 55, 65, 0.5)' : 'rgba(240, 245, 255, 0.5)', p: 1.5, borderRadius: 1 }}>
@@ -634,6 +825,319 @@ const Settings: React.FC<SettingsProps> = () => {
                 />
               </Box>
             </Box>
+            <Typography
+              variant="subtitle1"
+              sx={{
+                mb: 1.5,
+                fontWeight: 500,
+                fontSize: '14px',
+                color: themeMode === 'dark' ? '#e0e0e0' : '#424242',
+                borderBottom: `1px solid ${muiTheme.palette.divider}`,
+                pb: 0.75,
+                fontFamily: '"Segoe UI", system-ui, -apple-system, sans-serif'
+              }}
+            >
+              Validation Rules
+            </Typography>
+            <Box sx={{ mb: 1.5, display: 'flex', alignItems: 'center', gap: 1, width: '100%' }}>
+              <TextField
+                {...backendFieldProps}
+                select
+                label="Active rule set"
+                value={ruleSets.find(r => r.active)?.file ?? ''}
+                onChange={e => handleRuleSetChange(e.target.value || null)}
+              >
+                {ruleSets.map(ruleSet => (
+                  <MenuItem
+                    key={ruleSet.file ?? ''}
+                    value={ruleSet.file ?? ''}
+                    disabled={!!ruleSet.error}
+                    title={ruleSet.error}
+                    sx={{ fontSize: '13px' }}
+                  >
+                    {ruleSet.error
+                      ? `${ruleSet.name} (invalid)`
+                      : `${ruleSet.name} (max. ${ruleSet.maxLength} characters)`}
+                  </MenuItem>
+                ))}
+              </TextField>
+              <Button
+                variant="outlined"
+                size="small"
+                onClick={handleRuleSetImport}
+                sx={{
+                  textTransform: 'none',
+                  borderRadius: 0.5,
+                  fontSize: '13px',
+                  fontFamily: '"Segoe UI", system-ui, -apple-system, sans-serif',
+                  fontWeight: 400,
+                  whiteSpace: 'nowrap'
+                }}
+              >
+                Import...
+              </Button>
+            </Box>
+            {ruleSetError && (
+              <Typography variant="caption" sx={{ mb: 1.5, color: 'error.main' }}>
+                ⚠️ {ruleSetError}
+              </Typography>
+            )}
+            <Box sx={{ mb: 1.5, display: 'flex', alignItems: 'center', gap: 1, width: '100%' }}>
+              <TextField
+                {...backendFieldProps}
+                label="Waiver file"
+                value={waiverFile?.path ?? ''}
+                placeholder="No waivers"
+                InputProps={{ ...backendFieldProps.InputProps, readOnly: true }}
+                helperText={waiverFile
+                  ? waiverFile.error
+                    ? 'Invalid, see the error below'
+                    : `${waiverFile.count} waivers, ${waiverFile.expired} expired`
+                  : undefined}
+                title={waiverFile?.path}
+              />
+              <Button
+                variant="outlined"
+                size="small"
+                onClick={() => handleWaiverFile(false)}
+                sx={{
+                  textTransform: 'none',
+                  borderRadius: 0.5,
+                  fontSize: '13px',
+                  fontFamily: '"Segoe UI", system-ui, -apple-system, sans-serif',
+                  fontWeight: 400,
+                  whiteSpace: 'nowrap'
+                }}
+              >
+                Select...
+              </Button>
+              <Button
+                variant="outlined"
+                size="small"
+                disabled={!waiverFile}
+                onClick={() => handleWaiverFile(true)}
+                sx={{
+                  textTransform: 'none',
+                  borderRadius: 0.5,
+                  fontSize: '13px',
+                  fontFamily: '"Segoe UI", system-ui, -apple-system, sans-serif',
+                  fontWeight: 400,
+                  whiteSpace: 'nowrap'
+                }}
+              >
+                Clear
+              </Button>
+            </Box>
+            {(waiverError || waiverFile?.error) && (
+              <Typography variant="caption" sx={{ mb: 1.5, color: 'error.main' }}>
+                ⚠️ {waiverError ?? waiverFile?.error}
+              </Typography>
+            )}
+            <Typography
+              variant="subtitle1"
+              sx={{
+                mb: 1.5,
+                fontWeight: 500,
+                fontSize: '14px',
+                color: themeMode === 'dark' ? '#e0e0e0' : '#424242',
+                borderBottom: `1px solid ${muiTheme.palette.divider}`,
+                pb: 0.75,
+                fontFamily: '"Segoe UI", system-ui, -apple-system, sans-serif'
+              }}
+            >
+              Local API
+            </Typography>
+            <FormControlLabel
+              control={
+                <Checkbox
+                  checked={localApi?.enabled ?? false}
+                  disabled={!localApi}
+                  onChange={e => handleLocalApi(e.target.checked)}
+                  color="primary"
+                  size="small"
+                  sx={{
+                    padding: 0.5,
+                    '& .MuiSvgIcon-root': {
+                      fontSize: 18
+                    }
+                  }}
+                />
+              }
+              label={
+                <Typography variant="caption" sx={{
+                  fontWeight: localApi?.enabled ? 500 : 400,
+                  fontSize: '13px',
+                  fontFamily: '"Segoe UI", system-ui, -apple-system, sans-serif'
+                }}>
+                  Let other tools on this computer query keywords and validate labels
+                </Typography>
+              }
+              sx={{ mb: 1 }}
+            />
+            <Box sx={{ mb: 1.5, display: 'flex', alignItems: 'center', gap: 1, width: '100%' }}>
+              <TextField
+                {...backendFieldProps}
+                type="number"
+                label="Port"
+                value={localApiPort}
+                onChange={e => setLocalApiPort(e.target.value)}
+                inputProps={{ min: 1, max: 65535 }}
+                sx={{ width: 110, flexShrink: 0 }}
+                fullWidth={false}
+              />
+              <TextField
+                {...backendFieldProps}
+                label="Token"
+                value={localApi?.token ?? ''}
+                placeholder="Created when the API is enabled"
+                InputProps={{ ...backendFieldProps.InputProps, readOnly: true }}
+                helperText={localApi?.running ? `Listening on ${localApi.url}` : undefined}
+              />
+              <Button
+                variant="outlined"
+                size="small"
+                disabled={!localApi?.enabled}
+                onClick={() => handleLocalApi(true)}
+                sx={{
+                  textTransform: 'none',
+                  borderRadius: 0.5,
+                  fontSize: '13px',
+                  fontFamily: '"Segoe UI", system-ui, -apple-system, sans-serif',
+                  fontWeight: 400,
+                  whiteSpace: 'nowrap'
+                }}
+              >
+                Apply Port
+              </Button>
+              <Button
+                variant="outlined"
+                size="small"
+                disabled={!localApi?.token}
+                onClick={() => handleLocalApi(localApi?.enabled ?? false, true)}
+                sx={{
+                  textTransform: 'none',
+                  borderRadius: 0.5,
+                  fontSize: '13px',
+                  fontFamily: '"Segoe UI", system-ui, -apple-system, sans-serif',
+                  fontWeight: 400,
+                  whiteSpace: 'nowrap'
+                }}
+              >
+                New Token
+              </Button>
+            </Box>
+            {(localApiError || localApi?.error) && (
+              <Typography variant="caption" sx={{ mb: 1.5, color: 'error.main' }}>
+                ⚠️ {localApiError ?? localApi?.error}
+              </Typography>
+            )}
+            <Typography
+              variant="subtitle1"
+              sx={{
+                mb: 1.5,
+                fontWeight: 500,
+                fontSize: '14px',
+                color: themeMode === 'dark' ? '#e0e0e0' : '#424242',
+                borderBottom: `1px solid ${muiTheme.palette.divider}`,
+                pb: 0.75,
+                fontFamily: '"Segoe UI", system-ui, -apple-system, sans-serif'
+              }}
+            >
+              Clipboard
+            </Typography>
+            <Box sx={{ mb: 1.5, display: 'flex', alignItems: 'center', gap: 1, width: '100%' }}>
+              <TextField
+                {...backendFieldProps}
+                label="Shortcut"
+                value={hotkey}
+                placeholder="None"
+                onChange={e => setHotkey(e.target.value)}
+                helperText="e.g. CommandOrControl+Alt+Shift+L, empty for none"
+                disabled={!backendSettings}
+              />
+              <TextField
+                {...backendFieldProps}
+                select
+                label="Show result in"
+                value={hotkeyMode}
+                onChange={e => setHotkeyMode(e.target.value as HotkeyMode)}
+                disabled={!backendSettings}
+                sx={{ width: 150, flexShrink: 0 }}
+                fullWidth={false}
+              >
+                <MenuItem value="window" sx={{ fontSize: '13px' }}>Main window</MenuItem>
+                <MenuItem value="notification" sx={{ fontSize: '13px' }}>Notification</MenuItem>
+              </TextField>
+              <Button
+                variant="outlined"
+                size="small"
+                disabled={!backendSettings}
+                onClick={handleClipboardApply}
+                sx={{
+                  textTransform: 'none',
+                  borderRadius: 0.5,
+                  fontSize: '13px',
+                  fontFamily: '"Segoe UI", system-ui, -apple-system, sans-serif',
+                  fontWeight: 400,
+                  whiteSpace: 'nowrap'
+                }}
+              >
+                {clipboardSaved ? 'Saved' : 'Apply'}
+              </Button>
+            </Box>
+            <FormControlLabel
+              control={
+                <Checkbox
+                  checked={clipboardWatch}
+                  disabled={!backendSettings}
+                  onChange={e => setClipboardWatch(e.target.checked)}
+                  color="primary"
+                  size="small"
+                  sx={{
+                    padding: 0.5,
+                    '& .MuiSvgIcon-root': {
+                      fontSize: 18
+                    }
+                  }}
+                />
+              }
+              label={
+                <Typography variant="caption" sx={{
+                  fontWeight: clipboardWatch ? 500 : 400,
+                  fontSize: '13px',
+                  fontFamily: '"Segoe UI", system-ui, -apple-system, sans-serif'
+                }}>
+                  Check copied labels and show the verdict in the tray
+                </Typography>
+              }
+              sx={{ mb: 1 }}
+            />
+            <Box sx={{ mb: 1.5, display: 'flex', alignItems: 'center', gap: 1, width: '100%' }}>
+              <TextField
+                {...backendFieldProps}
+                label="Label pattern"
+                value={clipboardPattern}
+                onChange={e => setClipboardPattern(e.target.value)}
+                helperText="* and ? match like in file names"
+                disabled={!backendSettings || !clipboardWatch}
+              />
+              <TextField
+                {...backendFieldProps}
+                type="number"
+                label="Recent labels"
+                value={historySize}
+                onChange={e => setHistorySize(Math.min(50, Math.max(1, Number(e.target.value))))}
+                inputProps={{ min: 1, max: 50 }}
+                disabled={!backendSettings}
+                sx={{ width: 110, flexShrink: 0 }}
+                fullWidth={false}
+              />
+            </Box>
+            {clipboardError && (
+              <Typography variant="caption" sx={{ mb: 1.5, color: 'error.main' }}>
+                ⚠️ {clipboardError}
+              </Typography>
+            )}
             <Box sx={{ mt: 'auto', display: 'flex', alignItems: 'center', width: '100%' }}>              <Button
               variant="contained"
               color="primary"
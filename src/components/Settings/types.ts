@@ -3,6 +3,56 @@ export interface ToolBehaviorSettings {
   disappearSeconds: number;
 }
 
+// Mirrors BackendSettings in src-tauri/src/settings.rs
+export interface BackendSettings {
+  apiBaseUrl: string;
+  keywordsEndpoint: string;
+  versionEndpoint: string;
+  requestTimeoutSecs: number;
+  connectTimeoutSecs: number;
+  ruleSet?: string | null;
+  waiverFile?: string | null;
+  localApiEnabled?: boolean;
+  localApiPort?: number;
+  localApiToken?: string | null;
+  hotkey?: string | null;
+  hotkeyMode?: HotkeyMode;
+  clipboardWatch?: boolean;
+  clipboardPattern?: string;
+  historySize?: number;
+  workOffline?: boolean;
+}
+
+// Where the clipboard shortcut shows its result, mirrors HotkeyMode in src-tauri/src/hotkey.rs
+export type HotkeyMode = 'window' | 'notification';
+
+// Mirrors RuleSetInfo in src-tauri/src/rules.rs
+export interface RuleSetInfo {
+  file: string | null;
+  name: string;
+  maxLength: number;
+  active: boolean;
+  error?: string;
+}
+
+// Mirrors WaiverFileInfo in src-tauri/src/waiver.rs
+export interface WaiverFileInfo {
+  path: string;
+  count: number;
+  expired: number;
+  error?: string;
+}
+
+// Mirrors ApiInfo in src-tauri/src/api.rs
+export interface LocalApiInfo {
+  enabled: boolean;
+  running: boolean;
+  port: number;
+  url: string;
+  token: string | null;
+  error?: string;
+}
+
 export interface ThemeOption {
   label: string;
   value: ThemeValue;
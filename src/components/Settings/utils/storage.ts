@@ -1,4 +1,5 @@
-import { ToolBehaviorSettings } from '../types';
+import { invoke } from '@tauri-apps/api/core';
+import { BackendSettings, LocalApiInfo, RuleSetInfo, ToolBehaviorSettings, WaiverFileInfo } from '../types';
 import { defaultToolBehavior } from '../constants';
 import { config, storage, debugLog } from '../../../config';
 import { createStorageError, ErrorSeverity, handleError } from '../../../utils/errorHandling';
@@ -26,17 +27,15 @@ export const saveApiUrl = (apiUrl: string): void => {
   try {
     localStorage.setItem(config.storage.apiUrlKey, apiUrl);
     
-    // Also save to Tauri backend if available
-    if (window.__TAURI__ && window.__TAURI__.tauri) {
-      debugLog('Invoking Tauri backend to save API URL');
-      window.__TAURI__.tauri.invoke('save_settings_api_url', { url: apiUrl })
-        .then(() => {
-          debugLog('Successfully saved API URL to Tauri backend');
-        })
-        .catch((err: any) => {
-          handleError(err, 'Settings: save API URL to Tauri backend');
-        });
-    }
+    // Also save to Tauri backend, the keyword loaders read it from there
+    debugLog('Invoking Tauri backend to save API URL');
+    invoke('save_settings_api_url', { url: apiUrl })
+      .then(() => {
+        debugLog('Successfully saved API URL to Tauri backend');
+      })
+      .catch((err: any) => {
+        handleError(err, 'Settings: save API URL to Tauri backend');
+      });
   } catch (error) {
     handleError(
       createStorageError(
@@ -49,6 +48,94 @@ export const saveApiUrl = (apiUrl: string): void => {
   }
 };
 
+/**
+ * Get the backend settings (API location and timeouts) used by the keyword loaders
+ */
+export const getBackendSettings = async (): Promise<BackendSettings | null> => {
+  try {
+    return await invoke<BackendSettings>('get_backend_settings');
+  } catch (error) {
+    handleError(error, 'Settings: get backend settings');
+    return null;
+  }
+};
+
+/**
+ * Persist the backend settings, rejects with the backend's validation message
+ */
+export const saveBackendSettings = async (settings: BackendSettings): Promise<void> => {
+  debugLog('Saving backend settings:', settings);
+  await invoke('save_backend_settings', { settings });
+};
+
+/**
+ * List the built-in rules and the rule set files in the backend's rules folder
+ */
+export const listRuleSets = async (): Promise<RuleSetInfo[]> => {
+  try {
+    return await invoke<RuleSetInfo[]>('list_rule_sets');
+  } catch (error) {
+    handleError(error, 'Settings: list rule sets');
+    return [];
+  }
+};
+
+/**
+ * Activate a rule set by file name (null for the built-in rules), rejects with the backend's message
+ */
+export const setActiveRuleSet = async (file: string | null): Promise<RuleSetInfo> => {
+  debugLog('Activating rule set:', file);
+  return await invoke<RuleSetInfo>('set_active_rule_set', { file });
+};
+
+/**
+ * Let the user pick a rule set file and copy it into the rules folder, resolves to its file name
+ */
+export const importRuleSet = async (): Promise<string | null> => {
+  return await invoke<string | null>('import_rule_set');
+};
+
+/**
+ * The waiver file the backend validates with, null when there is none
+ */
+export const getWaiverFile = async (): Promise<WaiverFileInfo | null> => {
+  try {
+    return await invoke<WaiverFileInfo | null>('get_waiver_file');
+  } catch (error) {
+    handleError(error, 'Settings: get waiver file');
+    return null;
+  }
+};
+
+/**
+ * Let the user pick a waiver file, or stop using waivers with `clear`; rejects with the backend's message
+ */
+export const setWaiverFile = async (clear = false): Promise<WaiverFileInfo | null> => {
+  debugLog('Setting waiver file, clear:', clear);
+  return await invoke<WaiverFileInfo | null>('set_waiver_file', { clear });
+};
+
+/**
+ * Configuration and status of the local HTTP API, null when it cannot be read
+ */
+export const getLocalApi = async (): Promise<LocalApiInfo | null> => {
+  try {
+    return await invoke<LocalApiInfo>('get_local_api');
+  } catch (error) {
+    handleError(error, 'Settings: get local API');
+    return null;
+  }
+};
+
+/**
+ * Enable or disable the local HTTP API, optionally on another port or with a new token;
+ * rejects with the backend's message
+ */
+export const setLocalApi = async (enabled: boolean, port?: number, newToken = false): Promise<LocalApiInfo> => {
+  debugLog('Setting local API, enabled:', enabled, 'port:', port, 'new token:', newToken);
+  return await invoke<LocalApiInfo>('set_local_api', { enabled, port, newToken });
+};
+
 /**
  * Get tool behavior settings from localStorage
  */
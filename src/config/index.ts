@@ -3,6 +3,8 @@
  * Provides centralized management of application settings with environment-specific configurations
  */
 
+import { invoke } from '@tauri-apps/api/core';
+
 // Environment type definition
 type Environment = 'development' | 'production';
 
@@ -176,4 +178,64 @@ export const storage = {
       return false;
     }
   }
-};
\ No newline at end of file
+};
+
+// Environment names used by the backend configuration (config.dev.yaml / config.prod.yaml)
+export type BackendEnvironment = 'dev' | 'prod';
+
+// Non-secret configuration returned by the `get_app_config` command
+export interface AppConfig {
+  environment: BackendEnvironment;
+  api_url: string;
+  keywords_endpoint: string;
+  version_endpoint: string;
+  request_timeout_secs: number;
+  connect_timeout_secs: number;
+  debug_mode: boolean;
+  log_level: string;
+}
+
+let appConfigPromise: Promise<AppConfig> | null = null;
+
+/**
+ * Load the backend configuration once
+ * @returns The configuration of the environment the backend was started with
+ */
+export function loadAppConfig(): Promise<AppConfig> {
+  if (!appConfigPromise) {
+    appConfigPromise = invoke<AppConfig>('get_app_config').catch((error) => {
+      appConfigPromise = null;
+      throw error;
+    });
+  }
+  return appConfigPromise;
+}
+
+/**
+ * Helper function to get the current backend environment
+ * @returns 'dev' or 'prod', derived from the build mode if the backend is unavailable
+ */
+export async function getEnvironment(): Promise<BackendEnvironment> {
+  try {
+    return (await loadAppConfig()).environment;
+  } catch (error) {
+    debugLog(`Error loading backend configuration: ${error}`);
+    return ENV === 'production' ? 'prod' : 'dev';
+  }
+}
+
+/**
+ * Helper function to get a single value from the backend configuration
+ * @param key - The configuration key, e.g. 'debug_mode'
+ * @param defaultValue - Value returned if the key is missing or the backend is unavailable
+ * @returns The configured value or defaultValue
+ */
+export async function getConfig<T>(key: keyof AppConfig, defaultValue: T): Promise<T> {
+  try {
+    const value = (await loadAppConfig())[key];
+    return value === undefined || value === null ? defaultValue : (value as unknown as T);
+  } catch (error) {
+    debugLog(`Error loading backend configuration: ${error}`);
+    return defaultValue;
+  }
+}
@@ -159,6 +159,63 @@ export function createUnknownError(
   );
 }
 
+/**
+ * Error returned by Tauri commands and sent with the `backend-error` event
+ */
+export interface BackendError {
+  code: 'network' | 'parse' | 'cache_io' | 'window' | 'validation' | 'internal';
+  message: string;
+  retryable: boolean;
+  /** Background task that failed, only set on `backend-error` events */
+  source?: string;
+}
+
+const BACKEND_ERROR_TYPES: Record<BackendError['code'], ErrorType> = {
+  network: ErrorType.NETWORK,
+  parse: ErrorType.DATA_PARSING,
+  cache_io: ErrorType.STORAGE,
+  window: ErrorType.UNKNOWN,
+  validation: ErrorType.UNKNOWN,
+  internal: ErrorType.UNKNOWN,
+};
+
+/**
+ * Whether a rejected `invoke` or event payload is a backend error
+ */
+export function isBackendError(error: unknown): error is BackendError {
+  return typeof error === 'object'
+    && error !== null
+    && typeof (error as BackendError).code === 'string'
+    && typeof (error as BackendError).message === 'string';
+}
+
+/**
+ * Convert a backend error into an AppError
+ */
+export function fromBackendError(error: BackendError): AppError {
+  return new AppError(
+    error.message,
+    BACKEND_ERROR_TYPES[error.code] ?? ErrorType.UNKNOWN,
+    ErrorSeverity.ERROR,
+    undefined,
+    error.retryable
+  );
+}
+
+function toAppError(error: unknown): AppError {
+  if (error instanceof AppError) {
+    return error;
+  }
+  if (isBackendError(error)) {
+    return fromBackendError(error);
+  }
+  return createUnknownError(
+    error instanceof Error ? error.message : 'Unknown error occurred',
+    ErrorSeverity.ERROR,
+    error instanceof Error ? error : undefined
+  );
+}
+
 /**
  * Helper function to retry an operation with exponential backoff
  */
@@ -178,13 +235,7 @@ export async function withRetry<T>(
       attempt++;
       
       // If the error is not an AppError, convert it
-      const appError = error instanceof AppError
-        ? error
-        : createUnknownError(
-            error instanceof Error ? error.message : 'Unknown error occurred',
-            ErrorSeverity.ERROR,
-            error instanceof Error ? error : undefined
-          );
+      const appError = toAppError(error);
       
       // If the error is not retryable or we've exceeded max retries, throw
       if (!appError.retryable || attempt >= maxRetries) {
@@ -212,13 +263,7 @@ export function handleError(
   error: unknown,
   context: string,
 ): AppError {
-  const appError = error instanceof AppError
-    ? error
-    : createUnknownError(
-        error instanceof Error ? error.message : 'Unknown error occurred',
-        ErrorSeverity.ERROR,
-        error instanceof Error ? error : undefined
-      );
+  const appError = toAppError(error);
   
   // Log the error with context
   console.error(`[AILabelAssist][ERROR][${context}] ${appError.message}`, appError);